@@ -0,0 +1,592 @@
+use crate::{
+    DebuggerBacktrace, Format, Frame, JavaException, JavaThread, JavaThreadDump, Stacktrace,
+};
+
+const FATAL_EXCEPTION_PREFIX: &str = "FATAL EXCEPTION: ";
+const PROCESS_PREFIX: &str = "Process: ";
+const ANR_PID_PREFIX: &str = "----- pid ";
+const CMD_LINE_PREFIX: &str = "Cmd line: ";
+const TOMBSTONE_HEADER: &str = "*** *** *** *** *** *** *** *** *** *** *** *** *** *** *** ***";
+const TOMBSTONE_PID_PREFIX: &str = "pid: ";
+const TOMBSTONE_SIGNAL_PREFIX: &str = "signal ";
+const NATIVE_FRAME_PREFIX: &str = "native: ";
+const BUILD_ID_SEPARATOR: &str = " (BuildId: ";
+const LOG_LEVELS: [char; 7] = ['V', 'D', 'I', 'W', 'E', 'F', 'A'];
+
+/// Lines after a header that describe the crash.
+const METADATA_PREFIXES: [&str; 10] = [
+    PROCESS_PREFIX,
+    CMD_LINE_PREFIX,
+    "| ",
+    "Build fingerprint: ",
+    "Revision: ",
+    "ABI: ",
+    "Timestamp: ",
+    TOMBSTONE_PID_PREFIX,
+    TOMBSTONE_SIGNAL_PREFIX,
+    "Abort message: ",
+];
+
+/// An Android crash, ANR, or native tombstone, optionally copied from `adb
+/// logcat`.
+///
+/// ```text
+/// 01-15 10:23:45.678  1234  1234 E AndroidRuntime: FATAL EXCEPTION: main
+/// 01-15 10:23:45.678  1234  1234 E AndroidRuntime: Process: com.example.app, PID: 1234
+/// 01-15 10:23:45.678  1234  1234 E AndroidRuntime: java.lang.NullPointerException: boom
+/// 01-15 10:23:45.678  1234  1234 E AndroidRuntime:     at com.example.MainActivity.onCreate(MainActivity.java:12)
+/// ```
+///
+/// ```text
+/// backtrace:
+///       #00 pc 000000000004f8dc  /apex/com.android.runtime/lib64/bionic/libc.so (abort+164)
+///       #01 pc 00000000000123ab  /data/app/com.example.app/lib/arm64/libnative.so (crash+20)
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AndroidCrash {
+    /// Whether this is a crash, ANR, or tombstone.
+    pub kind: AndroidCrashKind,
+    /// Name of the process, e.g. `com.example.app`.
+    pub process_name: Option<String>,
+    /// ID of the process.
+    pub pid: Option<u32>,
+    /// Name of the thread that crashed, e.g. `main` for `FATAL EXCEPTION:
+    /// main`.
+    pub thread_name: Option<String>,
+    /// The uncaught exception, for `FATAL EXCEPTION` crashes.
+    ///
+    /// This is parsed from the lines without their logcat prefixes, so its
+    /// source spans are relative to those lines joined by `\n`.
+    pub exception: Option<JavaException>,
+    /// Each thread in an ANR trace.
+    ///
+    /// As with `exception`, source spans are relative to the lines without
+    /// their logcat prefixes.
+    pub threads: Vec<JavaThread>,
+    /// Signal that killed the process, e.g. `6 (SIGABRT), code -6 (SI_TKILL),
+    /// fault addr --------`.
+    pub signal: Option<String>,
+    /// Native frames of a tombstone.
+    pub stacktrace: Stacktrace,
+}
+
+/// What kind of Android crash was parsed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AndroidCrashKind {
+    /// Uncaught Java exception, `FATAL EXCEPTION: main`.
+    #[default]
+    FatalException,
+    /// Application not responding trace, from `traces.txt`.
+    Anr,
+    /// Native crash tombstone.
+    Tombstone,
+}
+
+impl AndroidCrash {
+    /// Parses the first Android crash, ANR, or tombstone in the given string.
+    ///
+    /// Logcat prefixes, such as `E AndroidRuntime: ` or `01-15 10:23:45.678
+    /// 1234  1234 E AndroidRuntime: `, are stripped from each line. Returns
+    /// `None` if there is no crash.
+    pub fn parse(s: &str) -> Option<Self> {
+        let lines = s
+            .lines()
+            .map(|line| Self::split_logcat_prefix(line).1)
+            .collect::<Vec<_>>();
+
+        Self::parse_fatal_exception(&lines)
+            .or_else(|| Self::parse_anr(&lines))
+            .or_else(|| Self::parse_tombstone(s, &lines))
+    }
+
+    pub fn kind(&self) -> AndroidCrashKind {
+        self.kind
+    }
+
+    pub fn process_name(&self) -> Option<&str> {
+        self.process_name.as_deref()
+    }
+
+    pub fn pid(&self) -> Option<u32> {
+        self.pid
+    }
+
+    pub fn thread_name(&self) -> Option<&str> {
+        self.thread_name.as_deref()
+    }
+
+    pub fn exception(&self) -> Option<&JavaException> {
+        self.exception.as_ref()
+    }
+
+    pub fn threads(&self) -> &[JavaThread] {
+        &self.threads
+    }
+
+    pub fn signal(&self) -> Option<&str> {
+        self.signal.as_deref()
+    }
+
+    pub fn stacktrace(&self) -> &Stacktrace {
+        &self.stacktrace
+    }
+
+    /// Returns whether the line begins a crash, exception, ANR thread, or
+    /// tombstone backtrace.
+    pub(crate) fn is_header_line(line: &str) -> bool {
+        let (_logcat_prefix, rest) = Self::split_logcat_prefix(line);
+        let rest_trimmed = rest.trim();
+
+        rest_trimmed.starts_with(FATAL_EXCEPTION_PREFIX)
+            || rest_trimmed.starts_with(ANR_PID_PREFIX)
+            || rest_trimmed == TOMBSTONE_HEADER
+            || rest_trimmed == "backtrace:"
+            || JavaException::is_header_line(rest)
+            || JavaThreadDump::is_header_line(rest)
+    }
+
+    /// Returns whether the line describes the header before it, e.g. `Process:
+    /// com.example.app, PID: 1234` or `  | group="main" sCount=1 ..`.
+    pub(crate) fn is_header_continuation_line(line: &str) -> bool {
+        let (_logcat_prefix, rest) = Self::split_logcat_prefix(line);
+        let rest_trimmed = rest.trim();
+
+        METADATA_PREFIXES
+            .iter()
+            .any(|prefix| rest_trimmed.starts_with(prefix))
+            || JavaThreadDump::is_header_continuation_line(rest)
+    }
+
+    /// Returns whether the line belongs to the frame before it, e.g. `... 11
+    /// more` or `- locked <0x0a1b2c3d> (a java.lang.Object)`.
+    pub(crate) fn is_continuation_line(line: &str) -> bool {
+        let (_logcat_prefix, rest) = Self::split_logcat_prefix(line);
+        JavaException::is_continuation_line(rest) || JavaThreadDump::is_continuation_line(rest)
+    }
+
+    /// Returns whether the line is a Java or native frame.
+    pub(crate) fn is_frame_line(line: &str) -> bool {
+        let (_logcat_prefix, rest) = Self::split_logcat_prefix(line);
+        JavaException::is_stack_frame_line(rest) || Self::parse_native_frame(rest).is_some()
+    }
+
+    /// Parses a Java or native frame line into a `Frame`, ignoring the logcat
+    /// prefix.
+    pub(crate) fn parse_frame(line: &str) -> Option<Frame> {
+        let (_logcat_prefix, rest) = Self::split_logcat_prefix(line);
+        Self::parse_native_frame(rest).or_else(|| Some(Frame::from(rest)))
+    }
+
+    /// Splits the index and address of native frames from the rest of the
+    /// line.
+    ///
+    /// The logcat prefix has already been split from the line as its log
+    /// prefix.
+    pub(crate) fn split_frame_prefix(line: &str) -> (&str, &str) {
+        let frame_prefix_len = match Self::native_frame_library_index(line) {
+            Some(library_index) => library_index,
+            None => Frame::split_index_prefix(line).0.len(),
+        };
+
+        line.split_at(frame_prefix_len)
+    }
+
+    /// Parses `#00 pc 000000000004f8dc  /system/lib64/libc.so (abort+164)`
+    /// into a `Frame`.
+    ///
+    /// ANR traces prefix native frames with `native: `.
+    pub(crate) fn parse_native_frame(line: &str) -> Option<Frame> {
+        let rest = line.trim_start();
+        let rest = rest.strip_prefix(NATIVE_FRAME_PREFIX).unwrap_or(rest);
+        let (prefix_len, index) = Frame::parse_index_prefix(rest)?;
+        if !rest.starts_with('#') {
+            return None;
+        }
+
+        let rest = rest[prefix_len..].strip_prefix("pc ")?.trim_start();
+        let (address, rest) = rest.split_once(char::is_whitespace)?;
+        let address = u64::from_str_radix(address, 16).ok()?;
+        let rest = rest.trim();
+        let rest = match rest.rsplit_once(BUILD_ID_SEPARATOR) {
+            Some((rest, _build_id)) => rest,
+            None => rest,
+        };
+        let (library, rest) = match rest.split_once(' ') {
+            Some((library, rest)) => (library, rest.trim()),
+            None => (rest, ""),
+        };
+
+        // `(offset 0x1000)` is the offset of the library within the APK.
+        let symbol_and_offset = rest
+            .strip_prefix('(')
+            .and_then(|rest| rest.strip_suffix(')'))
+            .map(|rest| match rest.split_once(") (") {
+                Some((_apk_offset, symbol_and_offset)) => symbol_and_offset,
+                None => rest,
+            })
+            .filter(|symbol_and_offset| !symbol_and_offset.starts_with("offset 0x"));
+        let (symbol, offset) = match symbol_and_offset.and_then(|rest| rest.rsplit_once('+')) {
+            Some((symbol, offset)) => (Some(symbol), offset.parse::<u64>().ok()),
+            None => (symbol_and_offset, None),
+        };
+
+        let (function, arguments) = symbol
+            .map(DebuggerBacktrace::split_trailing_arguments)
+            .unwrap_or_default();
+        let mut segments = Frame::split_symbol(function);
+        let function = segments.pop();
+
+        Some(Frame {
+            index: Some(index),
+            symbol: symbol.map(str::to_string),
+            module_path: segments.into_iter().map(str::to_string).collect(),
+            function: function.map(str::to_string),
+            arguments: arguments.map(str::to_string),
+            library: Some(library.to_string()),
+            address: Some(address),
+            offset,
+            ..Frame::default()
+        })
+    }
+
+    /// Splits the logcat prefix from the message, e.g. `E AndroidRuntime: `,
+    /// `E/AndroidRuntime( 1234): `, or `01-15 10:23:45.678  1234  1234 E
+    /// AndroidRuntime: `.
+    ///
+    /// If there is no logcat prefix, the prefix is empty.
+    pub(crate) fn split_logcat_prefix(line: &str) -> (&str, &str) {
+        match Self::logcat_prefix_len(line) {
+            Some(prefix_len) => line.split_at(prefix_len),
+            None => ("", line),
+        }
+    }
+
+    fn logcat_prefix_len(line: &str) -> Option<usize> {
+        let mut rest = line.trim_start();
+
+        // `01-15 10:23:45.678  1234  1234 `, or `01-15 10:23:45.678 ` for `-v time`.
+        let rest_after_timestamp = rest.split_once(' ').and_then(|(date, time_and_rest)| {
+            let (time, rest_after_time) = time_and_rest.trim_start().split_once(' ')?;
+            let is_date =
+                date.contains('-') && date.chars().all(|c| c.is_ascii_digit() || c == '-');
+            let is_time = time.contains(':')
+                && time
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || matches!(c, ':' | '.'));
+
+            (is_date && is_time).then(|| rest_after_time.trim_start())
+        });
+        let has_timestamp = rest_after_timestamp.is_some();
+        if let Some(rest_after_timestamp) = rest_after_timestamp {
+            rest = rest_after_timestamp;
+
+            // `-v threadtime` includes the process and thread IDs.
+            for _ in 0..2 {
+                let Some((id, rest_after_id)) = rest.split_once(' ') else {
+                    break;
+                };
+                if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+                    break;
+                }
+                rest = rest_after_id.trim_start();
+            }
+        }
+
+        let mut chars = rest.chars();
+        let level = chars.next()?;
+        let separator = chars.next()?;
+        if !LOG_LEVELS.contains(&level) {
+            return None;
+        }
+        let tag_and_rest = chars.as_str();
+
+        let message_start = match separator {
+            // `E/AndroidRuntime( 1234): ` or `E/AndroidRuntime: `.
+            '/' => {
+                let tag_end = tag_and_rest.find(['(', ':'])?;
+                let tag_rest = &tag_and_rest[tag_end..];
+                let tag_rest = match tag_rest.strip_prefix('(') {
+                    Some(pid_rest) => pid_rest.split_once(')')?.1,
+                    None => tag_rest,
+                };
+                let message = tag_rest.strip_prefix(':')?;
+                let message = message.strip_prefix(' ').unwrap_or(message);
+                line.len() - message.len()
+            }
+            // `E AndroidRuntime: `, with the tag padded to a fixed width.
+            ' ' => {
+                let (tag, message) = tag_and_rest.split_once(':')?;
+                let tag = tag.trim_end();
+                let is_tag = !tag.is_empty()
+                    && tag
+                        .chars()
+                        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '-' | '$'));
+                if !is_tag || !(has_timestamp || message.starts_with(' ')) {
+                    return None;
+                }
+                let message = message.strip_prefix(' ').unwrap_or(message);
+                line.len() - message.len()
+            }
+            _ => return None,
+        };
+
+        Some(message_start)
+    }
+
+    fn parse_fatal_exception(lines: &[&str]) -> Option<Self> {
+        let (fatal_exception_index, thread_name) =
+            lines.iter().enumerate().find_map(|(index, line)| {
+                line.trim()
+                    .strip_prefix(FATAL_EXCEPTION_PREFIX)
+                    .map(|thread_name| (index, thread_name))
+            })?;
+        let lines = &lines[fatal_exception_index + 1..];
+
+        // `Process: com.example.app, PID: 1234`
+        let (process_name, pid) = lines
+            .iter()
+            .find_map(|line| line.trim().strip_prefix(PROCESS_PREFIX))
+            .map(|process| match process.split_once(", PID: ") {
+                Some((process_name, pid)) => (Some(process_name), pid.parse::<u32>().ok()),
+                None => (Some(process), None),
+            })
+            .unwrap_or_default();
+
+        Some(Self {
+            kind: AndroidCrashKind::FatalException,
+            process_name: process_name.map(str::to_string),
+            pid,
+            thread_name: Some(thread_name.to_string()),
+            exception: JavaException::parse(&lines.join("\n")),
+            ..Self::default()
+        })
+    }
+
+    fn parse_anr(lines: &[&str]) -> Option<Self> {
+        let java_thread_dump =
+            JavaThreadDump::parse_with_format(&lines.join("\n"), Format::Android)?;
+
+        // `----- pid 1234 at 2024-01-15 10:23:45 -----`
+        let pid = lines.iter().find_map(|line| {
+            line.trim()
+                .strip_prefix(ANR_PID_PREFIX)
+                .and_then(|rest| rest.split_once(' '))
+                .and_then(|(pid, _rest)| pid.parse::<u32>().ok())
+        });
+        let process_name = lines
+            .iter()
+            .find_map(|line| line.trim().strip_prefix(CMD_LINE_PREFIX));
+
+        Some(Self {
+            kind: AndroidCrashKind::Anr,
+            process_name: process_name.map(str::to_string),
+            pid,
+            threads: java_thread_dump.threads,
+            ..Self::default()
+        })
+    }
+
+    fn parse_tombstone<'s>(s: &'s str, lines: &[&'s str]) -> Option<Self> {
+        let frame_lines = lines
+            .iter()
+            .copied()
+            .filter(|line| Self::parse_native_frame(line).is_some())
+            .collect::<Vec<_>>();
+        if frame_lines.is_empty() {
+            return None;
+        }
+
+        // `pid: 1234, tid: 1234, name: example.app  >>> com.example.app <<<`
+        let pid_line = lines
+            .iter()
+            .find_map(|line| line.trim().strip_prefix(TOMBSTONE_PID_PREFIX));
+        let pid = pid_line
+            .and_then(|pid_line| pid_line.split_once(','))
+            .and_then(|(pid, _rest)| pid.parse::<u32>().ok());
+        let process_name = pid_line
+            .and_then(|pid_line| pid_line.split_once(">>> "))
+            .and_then(|(_rest, process_name)| process_name.split_once(" <<<"))
+            .map(|(process_name, _rest)| process_name);
+        let signal = lines
+            .iter()
+            .find_map(|line| line.trim().strip_prefix(TOMBSTONE_SIGNAL_PREFIX));
+
+        Some(Self {
+            kind: AndroidCrashKind::Tombstone,
+            process_name: process_name.map(str::to_string),
+            pid,
+            signal: signal.map(str::to_string),
+            stacktrace: Stacktrace::from_lines(s, frame_lines, Format::Android),
+            ..Self::default()
+        })
+    }
+
+    /// Returns the index of the library in a native frame line, which is
+    /// after the frame index and address.
+    fn native_frame_library_index(line: &str) -> Option<usize> {
+        Self::parse_native_frame(line)?;
+
+        let (_prefix, rest) = line.split_once(" pc ")?;
+        let rest = rest.trim_start();
+        let (_address, library_and_rest) = rest.split_once(char::is_whitespace)?;
+
+        Some(line.len() - library_and_rest.trim_start().len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Frame;
+
+    use super::{AndroidCrash, AndroidCrashKind};
+
+    #[test]
+    fn parses_fatal_exception_from_logcat() {
+        let android_crash = AndroidCrash::parse(
+            "\
+            01-15 10:23:45.678  1234  1234 E AndroidRuntime: FATAL EXCEPTION: main\n\
+            01-15 10:23:45.678  1234  1234 E AndroidRuntime: Process: com.example.app, PID: 1234\n\
+            01-15 10:23:45.678  1234  1234 E AndroidRuntime: java.lang.RuntimeException: Unable to start activity\n\
+            01-15 10:23:45.678  1234  1234 E AndroidRuntime: \tat android.app.ActivityThread.performLaunchActivity(ActivityThread.java:3449)\n\
+            01-15 10:23:45.678  1234  1234 E AndroidRuntime: Caused by: java.lang.NullPointerException: boom\n\
+            01-15 10:23:45.678  1234  1234 E AndroidRuntime: \tat com.example.MainActivity.onCreate(MainActivity.java:12)\n\
+            01-15 10:23:45.678  1234  1234 E AndroidRuntime: \t... 11 more\n\
+            ",
+        )
+        .expect("Expected crash to be parsed.");
+
+        assert_eq!(AndroidCrashKind::FatalException, android_crash.kind());
+        assert_eq!(Some("main"), android_crash.thread_name());
+        assert_eq!(Some("com.example.app"), android_crash.process_name());
+        assert_eq!(Some(1234), android_crash.pid());
+
+        let exception = android_crash
+            .exception()
+            .expect("Expected exception to be parsed.");
+        let class_names = exception
+            .chain()
+            .map(|exception| exception.class_name())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "java.lang.RuntimeException",
+                "java.lang.NullPointerException"
+            ],
+            class_names
+        );
+    }
+
+    #[test]
+    fn parses_anr_traces() {
+        let android_crash = AndroidCrash::parse(
+            "\
+            ----- pid 1234 at 2024-01-15 10:23:45 -----\n\
+            Cmd line: com.example.app\n\
+            \n\
+            \"main\" prio=5 tid=1 Blocked\n\
+            \x20 | group=\"main\" sCount=1 ucsCount=0 flags=1 obj=0x72f3a2c8 self=0x7a8b6c0000\n\
+            \x20 at com.example.Store.save(Store.java:42)\n\
+            \x20 - waiting to lock <0x0a1b2c3d> (a java.lang.Object) held by thread 12\n\
+            \x20 at com.example.MainActivity.onClick(MainActivity.java:30)\n\
+            \x20 native: #00 pc 000000000004f8dc  /apex/com.android.runtime/lib64/bionic/libc.so (syscall+28)\n\
+            \n\
+            ----- end 1234 -----\n\
+            ",
+        )
+        .expect("Expected ANR to be parsed.");
+
+        assert_eq!(AndroidCrashKind::Anr, android_crash.kind());
+        assert_eq!(Some("com.example.app"), android_crash.process_name());
+        assert_eq!(Some(1234), android_crash.pid());
+
+        let [main] = android_crash.threads() else {
+            panic!("Expected one thread.");
+        };
+        assert_eq!("main", main.name());
+        assert_eq!(1, main.locks().len());
+        // `MainActivity.onClick` is nested under `Store.save`, as both are in
+        // `com.example`.
+        let [store_save, native_syscall] = main.stacktrace().sections.as_slice() else {
+            panic!("Expected two top level sections.");
+        };
+        assert_eq!(1, store_save.child_sections().len());
+        assert_eq!(Some("syscall"), native_syscall.frame().function());
+    }
+
+    #[test]
+    fn parses_tombstone() {
+        let android_crash = AndroidCrash::parse(
+            "\
+            *** *** *** *** *** *** *** *** *** *** *** *** *** *** *** ***\n\
+            pid: 1234, tid: 1234, name: example.app  >>> com.example.app <<<\n\
+            signal 6 (SIGABRT), code -6 (SI_TKILL), fault addr --------\n\
+            backtrace:\n\
+            \x20     #00 pc 000000000004f8dc  /apex/com.android.runtime/lib64/bionic/libc.so (abort+164) (BuildId: 1a2b)\n\
+            \x20     #01 pc 00000000000123ab  /data/app/com.example.app/lib/arm64/libnative.so (crash+20)\n\
+            ",
+        )
+        .expect("Expected tombstone to be parsed.");
+
+        assert_eq!(AndroidCrashKind::Tombstone, android_crash.kind());
+        assert_eq!(Some("com.example.app"), android_crash.process_name());
+        assert_eq!(Some(1234), android_crash.pid());
+        assert_eq!(
+            Some("6 (SIGABRT), code -6 (SI_TKILL), fault addr --------"),
+            android_crash.signal()
+        );
+        assert_eq!(2, android_crash.stacktrace().sections.len());
+    }
+
+    #[test]
+    fn parses_native_frame() {
+        let frame = AndroidCrash::parse_native_frame(
+            "      #00 pc 000000000004f8dc  /apex/com.android.runtime/lib64/bionic/libc.so (abort+164) (BuildId: 1a2b)",
+        );
+
+        let frame_expected = Frame {
+            index: Some(0),
+            symbol: Some(String::from("abort")),
+            function: Some(String::from("abort")),
+            library: Some(String::from(
+                "/apex/com.android.runtime/lib64/bionic/libc.so",
+            )),
+            address: Some(0x4f8dc),
+            offset: Some(164),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+    }
+
+    #[test]
+    fn splits_logcat_prefixes() {
+        [
+            (
+                "E AndroidRuntime: FATAL EXCEPTION: main",
+                "E AndroidRuntime: ",
+            ),
+            (
+                "E/AndroidRuntime( 1234): FATAL EXCEPTION: main",
+                "E/AndroidRuntime( 1234): ",
+            ),
+            (
+                "01-15 10:23:45.678  1234  1234 E AndroidRuntime: FATAL EXCEPTION: main",
+                "01-15 10:23:45.678  1234  1234 E AndroidRuntime: ",
+            ),
+            (
+                "01-15 10:23:45.678 E/AndroidRuntime( 1234): FATAL EXCEPTION: main",
+                "01-15 10:23:45.678 E/AndroidRuntime( 1234): ",
+            ),
+            ("FATAL EXCEPTION: main", ""),
+            // The character after the log level is not a single byte.
+            ("Dຄ %<9$", ""),
+        ]
+        .into_iter()
+        .for_each(|(line, logcat_prefix_expected)| {
+            assert_eq!(
+                logcat_prefix_expected,
+                AndroidCrash::split_logcat_prefix(line).0,
+                "{line}"
+            );
+        });
+    }
+}
@@ -0,0 +1,509 @@
+use crate::{DebuggerBacktrace, Format, Frame, GroupingKey, Stacktrace};
+
+const THREAD_PREFIX: &str = "Thread ";
+const CRASHED: &str = " Crashed";
+const LAST_EXCEPTION_BACKTRACE_HEADER: &str = "Last Exception Backtrace:";
+const BINARY_IMAGES_HEADER: &str = "Binary Images:";
+const THREAD_STATE: &str = " Thread State";
+
+/// Fields describing the crash, which are kept as metadata.
+const FIELD_NAMES: [&str; 26] = [
+    "Incident Identifier",
+    "CrashReporter Key",
+    "Hardware Model",
+    "Process",
+    "Path",
+    "Identifier",
+    "Version",
+    "Code Type",
+    "Role",
+    "Parent Process",
+    "Coalition",
+    "Date/Time",
+    "Launch Time",
+    "OS Version",
+    "Release Type",
+    "Baseband Version",
+    "Report Version",
+    "Exception Type",
+    "Exception Subtype",
+    "Exception Codes",
+    "Exception Reason",
+    "Exception Note",
+    "Termination Reason",
+    "Termination Description",
+    "Triggered by Thread",
+    "Crashed Thread",
+];
+
+/// An Apple crash report from iOS or macOS, with the stack of each thread.
+///
+/// The fields before the threads are kept as metadata.
+///
+/// ```text
+/// Process:             MyApp [1234]
+/// Exception Type:  EXC_CRASH (SIGABRT)
+/// Triggered by Thread:  0
+///
+/// Thread 0 name:  Dispatch queue: com.apple.main-thread
+/// Thread 0 Crashed:
+/// 0   libsystem_kernel.dylib         0x00000001e5f6a1b8 __pthread_kill + 8
+/// 1   MyApp                          0x0000000100a1b2c3 MyClass.method() + 120 (MyClass.swift:42)
+/// 2   MyApp                          0x0000000100a1a000 main + 64
+///
+/// Thread 1:
+/// 0   libsystem_kernel.dylib         0x00000001e5f5d0c4 __workq_kernreturn + 8
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AppleCrashReport {
+    /// Fields and their values, e.g. `("Exception Type", "EXC_CRASH
+    /// (SIGABRT)")`.
+    pub fields: Vec<(String, String)>,
+    /// Each thread in the report, in the order they are printed.
+    ///
+    /// This includes the `Last Exception Backtrace:`, which has no ID.
+    pub threads: Vec<AppleThread>,
+}
+
+/// A thread within an `AppleCrashReport`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AppleThread {
+    /// ID of the thread, e.g. `0` for `Thread 0 Crashed:`.
+    ///
+    /// This is `None` for the `Last Exception Backtrace:`.
+    pub id: Option<u32>,
+    /// Name of the thread, e.g. `Dispatch queue: com.apple.main-thread`.
+    pub name: Option<String>,
+    /// Whether the thread crashed.
+    pub crashed: bool,
+    /// Frames of the thread.
+    pub stacktrace: Stacktrace,
+}
+
+impl AppleCrashReport {
+    /// Parses the threads in the given string.
+    ///
+    /// Returns `None` if there are no thread headers, e.g. `Thread 0
+    /// Crashed:`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut fields = Vec::new();
+        let mut thread_names = Vec::new();
+        let mut threads = Vec::new();
+        let mut thread_frame_lines = Vec::new();
+        let mut is_in_thread = false;
+
+        for line in s.lines() {
+            if let Some((name, value)) = Self::parse_field(line) {
+                fields.push((name.to_string(), value.to_string()));
+            } else if let Some((id, name)) = Self::parse_thread_name(line) {
+                thread_names.push((id, name));
+            } else if let Some((id, crashed, name)) = Self::parse_thread_header(line) {
+                threads.push((id, crashed, name));
+                thread_frame_lines.push(Vec::new());
+                is_in_thread = true;
+            } else if Self::is_header_line(line) {
+                // Binary images and register dumps are not frames.
+                is_in_thread = false;
+            } else if is_in_thread && Self::parse_frame(line).is_some() {
+                if let Some(frame_lines) = thread_frame_lines.last_mut() {
+                    frame_lines.push(line);
+                }
+            }
+        }
+
+        let threads = threads
+            .into_iter()
+            .zip(thread_frame_lines)
+            .map(|((id, crashed, name), frame_lines)| {
+                // `Thread 0 name:` is printed on its own line before `Thread 0 Crashed:`.
+                let name = name.or_else(|| {
+                    thread_names
+                        .iter()
+                        .find(|(thread_id, _name)| id == Some(*thread_id))
+                        .map(|(_id, name)| *name)
+                });
+
+                AppleThread {
+                    id,
+                    name: name.map(str::to_string),
+                    crashed,
+                    stacktrace: Stacktrace::from_lines(s, frame_lines, Format::Apple),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        (!threads.is_empty()).then_some(Self { fields, threads })
+    }
+
+    pub fn fields(&self) -> &[(String, String)] {
+        &self.fields
+    }
+
+    /// Returns the value of the given field, e.g. `Exception Type`.
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field_name, _value)| field_name == name)
+            .map(|(_name, value)| value.as_str())
+    }
+
+    pub fn threads(&self) -> &[AppleThread] {
+        &self.threads
+    }
+
+    /// Returns the thread that crashed, if any.
+    pub fn crashed_thread(&self) -> Option<&AppleThread> {
+        self.threads.iter().find(|thread| thread.crashed)
+    }
+
+    /// Returns whether the line begins a thread, e.g. `Thread 0 Crashed:`, the
+    /// binary images or a register dump, or is a field.
+    pub(crate) fn is_header_line(line: &str) -> bool {
+        let line_trimmed = line.trim();
+        Self::parse_thread_header(line).is_some()
+            || Self::parse_thread_name(line).is_some()
+            || Self::parse_field(line).is_some()
+            || line_trimmed == BINARY_IMAGES_HEADER
+            || (line_trimmed.starts_with(THREAD_PREFIX)
+                && line_trimmed.contains(THREAD_STATE)
+                && line_trimmed.ends_with(':'))
+    }
+
+    /// Returns whether the line describes the header before it, e.g. a field,
+    /// `    x0: 0x0000000000000000   x1: 0x0000000000000001`, or `0x100a00000 -
+    /// 0x100bfffff MyApp arm64 ..`.
+    pub(crate) fn is_header_continuation_line(line: &str) -> bool {
+        Self::parse_field(line).is_some()
+            || Self::is_register_line(line)
+            || Self::is_binary_image_line(line)
+    }
+
+    /// Parses `1   MyApp   0x0000000100a1b2c3 MyClass.method() + 120` into a
+    /// `Frame`.
+    pub(crate) fn parse_frame(line: &str) -> Option<Frame> {
+        let (prefix, rest) = Self::split_frame_prefix(line)?;
+        let index = prefix.trim().parse::<u32>().ok()?;
+        let frame = Self::parse_image_frame(rest)?;
+
+        Some(Frame {
+            index: Some(index),
+            ..frame
+        })
+    }
+
+    /// Splits the frame index from the rest of a frame line.
+    ///
+    /// Apple frame indices are not followed by a `:`, so they are not split by
+    /// `Frame::split_index_prefix`.
+    pub(crate) fn split_frame_prefix(line: &str) -> Option<(&str, &str)> {
+        let rest = line.trim_start();
+        let digits_len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .filter(|digits_len| *digits_len > 0)?;
+        let rest_trimmed = rest[digits_len..].trim_start();
+        if rest_trimmed.len() == rest[digits_len..].len() {
+            return None;
+        }
+
+        Self::parse_image_frame(rest_trimmed)?;
+
+        Some(line.split_at(line.len() - rest_trimmed.len()))
+    }
+
+    /// Returns the key that frames are grouped by: the image, followed by the
+    /// symbol.
+    ///
+    /// Frames are compared regardless of their addresses and offsets.
+    pub(crate) fn grouping_key(line: &str) -> Option<GroupingKey<'static>> {
+        let frame = Self::parse_image_frame(line)?;
+        let image = frame.library().unwrap_or_default();
+        let symbol = frame.symbol().unwrap_or_default();
+
+        Some(GroupingKey::from(format!("{image} {symbol}")))
+    }
+
+    /// Parses `MyApp   0x0000000100a1b2c3 MyClass.method() + 120
+    /// (MyClass.swift:42)` into a `Frame`.
+    ///
+    /// Unsymbolicated frames have the load address instead of a symbol, e.g.
+    /// `MyApp   0x0000000100a1b2c3 0x100a00000 + 111299`.
+    fn parse_image_frame(rest: &str) -> Option<Frame> {
+        // Image names may contain spaces, so the image is everything before the first
+        // address.
+        let (image, address, rest) = rest.match_indices("0x").find_map(|(index, _)| {
+            let (image, address_and_rest) = rest.split_at(index);
+            if !image.ends_with(char::is_whitespace) || image.trim().is_empty() {
+                return None;
+            }
+
+            let (address, rest) = address_and_rest.split_once(char::is_whitespace)?;
+            let address = u64::from_str_radix(&address[2..], 16).ok()?;
+
+            Some((image.trim(), address, rest.trim()))
+        })?;
+        if rest.is_empty() {
+            return None;
+        }
+
+        let (rest, location) = match rest
+            .strip_suffix(')')
+            .and_then(|rest| rest.rsplit_once(" ("))
+            .and_then(|(rest, location)| Some((rest, Frame::parse_location(location)?)))
+        {
+            Some((rest, location)) => (rest, Some(location)),
+            None => (rest, None),
+        };
+        let (symbol, offset) = match rest.rsplit_once(" + ") {
+            Some((symbol, offset)) => (symbol, offset.trim().parse::<u64>().ok()),
+            None => (rest, None),
+        };
+        let (symbol, arguments) =
+            match Some(symbol.trim()).filter(|symbol| !symbol.starts_with("0x")) {
+                Some(symbol) => {
+                    let (symbol, arguments) = DebuggerBacktrace::split_trailing_arguments(symbol);
+                    (Some(symbol), arguments)
+                }
+                None => (None, None),
+            };
+        let mut segments = symbol.map(Frame::split_symbol).unwrap_or_default();
+        let function = segments.pop();
+        let (file_path, line, column) = match location {
+            Some((file_path, line, column)) => (Some(file_path.to_string()), Some(line), column),
+            None => (None, None, None),
+        };
+
+        Some(Frame {
+            symbol: symbol.map(str::to_string),
+            module_path: segments.into_iter().map(str::to_string).collect(),
+            function: function.map(str::to_string),
+            arguments: arguments.map(str::to_string),
+            library: Some(image.to_string()),
+            file_path,
+            line,
+            column,
+            address: Some(address),
+            offset,
+            ..Frame::default()
+        })
+    }
+
+    /// Returns the name and value of a field, e.g. `Exception Type:
+    /// EXC_CRASH (SIGABRT)`.
+    fn parse_field(line: &str) -> Option<(&str, &str)> {
+        let (name, value) = line.split_once(':')?;
+        FIELD_NAMES.contains(&name).then_some((name, value.trim()))
+    }
+
+    /// Returns the ID, whether the thread crashed, and the name of the thread
+    /// from `Thread 0 Crashed:`, `Thread 1:`, or `Thread 0 Crashed::  Dispatch
+    /// queue: com.apple.main-thread`.
+    ///
+    /// The ID is `None` for `Last Exception Backtrace:`.
+    fn parse_thread_header(line: &str) -> Option<(Option<u32>, bool, Option<&str>)> {
+        let line = line.trim();
+        if line == LAST_EXCEPTION_BACKTRACE_HEADER {
+            return Some((None, false, None));
+        }
+
+        let (id, rest) = Self::parse_thread_id(line)?;
+        let (crashed, rest) = match rest.strip_prefix(CRASHED) {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+        let rest = rest.strip_prefix(':')?;
+        let name = match rest.strip_prefix(':') {
+            Some(name) => Some(name.trim()).filter(|name| !name.is_empty()),
+            None if rest.trim().is_empty() => None,
+            None => return None,
+        };
+
+        Some((Some(id), crashed, name))
+    }
+
+    /// Returns the ID and name from `Thread 0 name:  Dispatch queue:
+    /// com.apple.main-thread`.
+    fn parse_thread_name(line: &str) -> Option<(u32, &str)> {
+        let (id, rest) = Self::parse_thread_id(line.trim())?;
+        let name = rest.strip_prefix(" name:")?.trim();
+
+        Some((id, name))
+    }
+
+    /// Returns the ID after `Thread `, and the rest of the line.
+    fn parse_thread_id(line: &str) -> Option<(u32, &str)> {
+        let rest = line.strip_prefix(THREAD_PREFIX)?;
+        let digits_len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let id = rest[..digits_len].parse::<u32>().ok()?;
+
+        Some((id, &rest[digits_len..]))
+    }
+
+    /// Returns whether the line lists registers, e.g. `    x0:
+    /// 0x0000000000000000   x1: 0x0000000000000001`.
+    fn is_register_line(line: &str) -> bool {
+        let mut tokens = line.split_whitespace();
+        let is_register_name = tokens
+            .next()
+            .and_then(|token| token.strip_suffix(':'))
+            .is_some_and(|name| {
+                name.starts_with(|c: char| c.is_ascii_alphabetic())
+                    && name.chars().all(|c| c.is_ascii_alphanumeric())
+            });
+
+        is_register_name && tokens.next().is_some_and(|token| token.starts_with("0x"))
+    }
+
+    /// Returns whether the line is a binary image, e.g. `0x100a00000 -
+    /// 0x100bfffff MyApp arm64  <..> /var/containers/Bundle/..`.
+    fn is_binary_image_line(line: &str) -> bool {
+        line.trim()
+            .split_once(" - ")
+            .is_some_and(|(start, rest)| start.starts_with("0x") && rest.starts_with("0x"))
+    }
+}
+
+impl AppleThread {
+    pub fn id(&self) -> Option<u32> {
+        self.id
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn crashed(&self) -> bool {
+        self.crashed
+    }
+
+    pub fn stacktrace(&self) -> &Stacktrace {
+        &self.stacktrace
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Frame;
+
+    use super::AppleCrashReport;
+
+    #[test]
+    fn parses_crash_report() {
+        let apple_crash_report = AppleCrashReport::parse(
+            "\
+            Process:             MyApp [1234]\n\
+            Exception Type:  EXC_CRASH (SIGABRT)\n\
+            Triggered by Thread:  0\n\
+            \n\
+            Last Exception Backtrace:\n\
+            0   CoreFoundation                \t0x00000001a1b2c3d4 __exceptionPreprocess + 164\n\
+            1   libobjc.A.dylib               \t0x00000001a0c1d2e3 objc_exception_throw + 60\n\
+            \n\
+            Thread 0 name:  Dispatch queue: com.apple.main-thread\n\
+            Thread 0 Crashed:\n\
+            0   libsystem_kernel.dylib        \t0x00000001e5f6a1b8 __pthread_kill + 8\n\
+            1   MyApp                         \t0x0000000100a1b2c3 MyClass.method() + 120 (MyClass.swift:42)\n\
+            2   MyApp                         \t0x0000000100a1a000 MyClass.run() + 64 (MyClass.swift:12)\n\
+            \n\
+            Thread 1:\n\
+            0   libsystem_kernel.dylib        \t0x00000001e5f5d0c4 __workq_kernreturn + 8\n\
+            \n\
+            Thread 0 crashed with ARM Thread State (64-bit):\n\
+            \x20   x0: 0x0000000000000000   x1: 0x0000000000000000\n\
+            \n\
+            Binary Images:\n\
+            0x100a00000 - 0x100bfffff MyApp arm64  <1b2c3d4e> /var/containers/Bundle/Application/MyApp.app/MyApp\n\
+            ",
+        )
+        .expect("Expected crash report to be parsed.");
+
+        assert_eq!(
+            Some("EXC_CRASH (SIGABRT)"),
+            apple_crash_report.field("Exception Type")
+        );
+        assert_eq!(Some("0"), apple_crash_report.field("Triggered by Thread"));
+
+        let [last_exception_backtrace, thread_0, thread_1] = apple_crash_report.threads() else {
+            panic!("Expected three threads.");
+        };
+        assert_eq!(None, last_exception_backtrace.id());
+        assert_eq!(2, last_exception_backtrace.stacktrace().sections.len());
+        assert_eq!(Some(0), thread_0.id());
+        assert_eq!(
+            Some("Dispatch queue: com.apple.main-thread"),
+            thread_0.name()
+        );
+        assert!(thread_0.crashed());
+        assert_eq!(Some(thread_0), apple_crash_report.crashed_thread());
+        assert_eq!(Some(1), thread_1.id());
+        assert!(!thread_1.crashed());
+
+        // `MyClass.run` is nested under `MyClass.method`, as both are in `MyApp`.
+        let [pthread_kill, my_class_method] = thread_0.stacktrace().sections.as_slice() else {
+            panic!("Expected two top level sections.");
+        };
+        assert_eq!("0   ", pthread_kill.slice_frame_index());
+        assert_eq!(
+            Some("run"),
+            my_class_method.child_sections()[0].frame().function()
+        );
+    }
+
+    #[test]
+    fn parses_macos_thread_header_with_name() {
+        let apple_crash_report = AppleCrashReport::parse(
+            "\
+            Thread 0 Crashed::  Dispatch queue: com.apple.main-thread\n\
+            0   libsystem_kernel.dylib        \t       0x18e0d2a60 __pthread_kill + 8\n\
+            ",
+        )
+        .expect("Expected crash report to be parsed.");
+
+        let [thread] = apple_crash_report.threads() else {
+            panic!("Expected one thread.");
+        };
+        assert_eq!(Some(0), thread.id());
+        assert_eq!(Some("Dispatch queue: com.apple.main-thread"), thread.name());
+        assert!(thread.crashed());
+        assert_eq!(1, thread.stacktrace().sections.len());
+    }
+
+    #[test]
+    fn parses_frame() {
+        let frame = AppleCrashReport::parse_frame(
+            "1   MyApp                         \t0x0000000100a1b2c3 MyClass.method() + 120 (MyClass.swift:42)",
+        );
+
+        let frame_expected = Frame {
+            index: Some(1),
+            symbol: Some(String::from("MyClass.method")),
+            module_path: vec![String::from("MyClass")],
+            function: Some(String::from("method")),
+            arguments: Some(String::new()),
+            library: Some(String::from("MyApp")),
+            file_path: Some(String::from("MyClass.swift")),
+            line: Some(42),
+            address: Some(0x100a1b2c3),
+            offset: Some(120),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+    }
+
+    #[test]
+    fn parses_unsymbolicated_frame() {
+        let frame = AppleCrashReport::parse_frame(
+            "3   My App                        \t0x0000000100a1b2c3 0x100a00000 + 111299",
+        );
+
+        let frame_expected = Frame {
+            index: Some(3),
+            library: Some(String::from("My App")),
+            address: Some(0x100a1b2c3),
+            offset: Some(111299),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+    }
+}
@@ -0,0 +1,429 @@
+use crate::{Format, Frame, GroupingKey, Stacktrace};
+
+const ELIXIR_HEADER_PREFIX: &str = "** (";
+
+/// An Elixir or Erlang exception and its stacktrace.
+///
+/// Elixir frames begin with the application, and end with the function and
+/// arity:
+///
+/// ```text
+/// ** (RuntimeError) boom
+///     (my_app 0.1.0) lib/my_app/worker.ex:12: MyApp.Worker.handle_call/3
+///     (stdlib 5.0) gen_server.erl:1113: :gen_server.try_handle_call/4
+/// ```
+///
+/// Erlang frames are `{module, function, arity, location}` tuples:
+///
+/// ```text
+/// {badarith,[{erlang,'+',[1,a],[{error_info,#{module => erl_erts_errors}}]},
+///            {my_mod,add,2,[{file,"src/my_mod.erl"},{line,12}]}]}
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BeamException {
+    /// Kind of the exception, e.g. `RuntimeError`, or the Erlang error
+    /// reason, e.g. `badarith`.
+    pub kind: Option<String>,
+    /// Message of the exception.
+    pub message: Option<String>,
+    /// Frames of the exception.
+    pub stacktrace: Stacktrace,
+}
+
+impl BeamException {
+    /// Parses the first Elixir or Erlang stacktrace in the given string.
+    ///
+    /// Returns `None` if there are no Elixir or Erlang frames.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut lines = s
+            .lines()
+            .skip_while(|line| !Self::is_header_line(line) && Self::parse_frame(line).is_none())
+            .peekable();
+
+        let (kind, message) = match lines
+            .next_if(|line| Self::is_header_line(line))
+            .and_then(Self::parse_header)
+        {
+            Some((kind, message)) => (Some(kind.to_string()), message.map(str::to_string)),
+            None => (
+                lines
+                    .peek()
+                    .copied()
+                    .and_then(Self::parse_erlang_reason)
+                    .map(str::to_string),
+                None,
+            ),
+        };
+
+        // Erlang error terms are often logged on one line, with every frame tuple in
+        // it.
+        let mut frame_lines = Vec::new();
+        while let Some(frame_line) = lines.next_if(|line| Self::parse_frame(line).is_some()) {
+            frame_lines.extend(Self::frame_slices(frame_line));
+        }
+
+        if kind.is_none() && frame_lines.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            kind,
+            message,
+            stacktrace: Stacktrace::from_lines(s, frame_lines, Format::Beam),
+        })
+    }
+
+    pub fn kind(&self) -> Option<&str> {
+        self.kind.as_deref()
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    pub fn stacktrace(&self) -> &Stacktrace {
+        &self.stacktrace
+    }
+
+    /// Returns whether the line is an Elixir exception header, e.g. `**
+    /// (RuntimeError) boom`.
+    pub(crate) fn is_header_line(line: &str) -> bool {
+        Self::parse_header(line).is_some()
+    }
+
+    /// Parses an Elixir frame line, or the first Erlang frame tuple in the
+    /// line, into a `Frame`.
+    pub(crate) fn parse_frame(line: &str) -> Option<Frame> {
+        Self::parse_elixir_frame(line).or_else(|| Self::parse_erlang_frame(line))
+    }
+
+    /// Returns the key that frames are grouped by: the application, followed
+    /// by the module and function.
+    ///
+    /// Frames from the same application and module are grouped together, even
+    /// though their file paths and line numbers differ.
+    pub(crate) fn grouping_key(line: &str) -> Option<GroupingKey<'static>> {
+        let frame = Self::parse_frame(line)?;
+        let application = frame.library().unwrap_or_default();
+        let module_path = frame.module_path().join(".");
+        let function = frame.function().unwrap_or_default();
+
+        Some(GroupingKey::from(format!(
+            "{application} {module_path} {function}"
+        )))
+    }
+
+    /// Parses `(my_app 0.1.0) lib/my_app/worker.ex:12:
+    /// MyApp.Worker.handle_call/3`.
+    ///
+    /// The application is optional, and the function may be within an
+    /// anonymous function, e.g. `anonymous fn/1 in MyApp.Worker.init/1`.
+    fn parse_elixir_frame(line: &str) -> Option<Frame> {
+        let line = line.trim();
+        let (application, rest) = match line.strip_prefix('(') {
+            Some(rest) => {
+                let (application, rest) = rest.split_once(") ")?;
+                (Some(application), rest)
+            }
+            None => (None, line),
+        };
+
+        let (location, symbol) = rest.split_once(": ")?;
+        if location.contains(char::is_whitespace) {
+            return None;
+        }
+        let (file_path, line_number, _column) = Frame::parse_location(location)?;
+
+        let function_and_arity = match symbol.rsplit_once(" in ") {
+            Some((_anonymous_function, function_and_arity)) => function_and_arity,
+            None => symbol,
+        };
+        let (module, function, arity) = Self::split_elixir_function(function_and_arity)?;
+
+        Some(Frame {
+            symbol: Some(symbol.to_string()),
+            module_path: module.split('.').map(str::to_string).collect(),
+            function: Some(function.to_string()),
+            arity: Some(arity),
+            library: application.map(str::to_string),
+            file_path: Some(file_path.to_string()),
+            line: Some(line_number),
+            ..Frame::default()
+        })
+    }
+
+    /// Splits `MyApp.Worker.handle_call/3`, `:gen_server.call/3`, or
+    /// `Enum."-map/2-lists^map/1-1-"/2` into the module, function, and arity.
+    fn split_elixir_function(function_and_arity: &str) -> Option<(&str, &str, u32)> {
+        let (module_and_function, arity) = function_and_arity.rsplit_once('/')?;
+        let arity = arity.parse::<u32>().ok()?;
+
+        let (module, function) = match module_and_function.strip_suffix('"') {
+            Some(rest) => {
+                let (module, function) = rest.rsplit_once(".\"")?;
+                (module, function)
+            }
+            None => module_and_function.rsplit_once('.')?,
+        };
+        let module = module.strip_prefix(':').unwrap_or(module);
+
+        let is_module = !module.is_empty()
+            && module
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '@'));
+        is_module.then_some((module, function, arity))
+    }
+
+    /// Returns the frames in a frame line: the line itself for an Elixir
+    /// frame, or each top level `{module,function,arity,location}` tuple in
+    /// the line.
+    fn frame_slices(line: &str) -> Vec<&str> {
+        if Self::parse_elixir_frame(line).is_some() {
+            return vec![line];
+        }
+
+        let mut frame_slices = Vec::new();
+        let mut index = 0;
+        while let Some(tuple_index) = line[index..].find('{').map(|offset| index + offset) {
+            let tuple = &line[tuple_index..];
+            match Self::parse_erlang_frame_tuple(tuple).and_then(|_| Self::balanced_len(tuple)) {
+                Some(tuple_len) => {
+                    frame_slices.push(&tuple[..tuple_len]);
+                    index = tuple_index + tuple_len;
+                }
+                None => index = tuple_index + 1,
+            }
+        }
+
+        frame_slices
+    }
+
+    /// Parses the first `{module,function,arity,[{file,..},{line,..}]}` tuple
+    /// in the line.
+    ///
+    /// The arity may instead be the list of arguments, e.g. `[1,a]`.
+    fn parse_erlang_frame(line: &str) -> Option<Frame> {
+        line.match_indices('{')
+            .find_map(|(index, _)| Self::parse_erlang_frame_tuple(&line[index..]))
+    }
+
+    fn parse_erlang_frame_tuple(tuple: &str) -> Option<Frame> {
+        let rest = tuple.strip_prefix('{')?;
+        let (module, rest) = Self::split_erlang_atom(rest)?;
+        let rest = rest.strip_prefix(',')?;
+        let (function, rest) = Self::split_erlang_atom(rest)?;
+        let rest = rest.strip_prefix(',')?;
+
+        let (arity, arguments, rest) = if rest.starts_with('[') {
+            let arguments_len = Self::balanced_len(rest)?;
+            let arguments = &rest[1..arguments_len - 1];
+            (None, Some(arguments), &rest[arguments_len..])
+        } else {
+            let arity_len = rest.find(|c: char| !c.is_ascii_digit())?;
+            let arity = rest[..arity_len].parse::<u32>().ok()?;
+            (Some(arity), None, &rest[arity_len..])
+        };
+
+        let rest = rest.strip_prefix(',')?;
+        let location_len = Self::balanced_len(rest).filter(|_| rest.starts_with('['))?;
+        let location = &rest[..location_len];
+        rest[location_len..].strip_prefix('}')?;
+
+        let file_path = location
+            .split_once("{file,\"")
+            .and_then(|(_, rest)| rest.split_once('"'))
+            .map(|(file_path, _)| file_path.to_string());
+        let line_number = location
+            .split_once("{line,")
+            .and_then(|(_, rest)| rest.split_once('}'))
+            .and_then(|(line_number, _)| line_number.parse::<u32>().ok());
+
+        let symbol = match arity {
+            Some(arity) => format!("{module}:{function}/{arity}"),
+            None => format!("{module}:{function}"),
+        };
+
+        Some(Frame {
+            symbol: Some(symbol),
+            module_path: vec![module.to_string()],
+            function: Some(function.to_string()),
+            arguments: arguments.map(str::to_string),
+            arity,
+            file_path,
+            line: line_number,
+            ..Frame::default()
+        })
+    }
+
+    /// Splits a bare or quoted atom, e.g. `my_mod` or `'+'`, from the start of
+    /// the string.
+    fn split_erlang_atom(s: &str) -> Option<(&str, &str)> {
+        if let Some(rest) = s.strip_prefix('\'') {
+            let (atom, rest) = rest.split_once('\'')?;
+            return Some((atom, rest));
+        }
+
+        let atom_len = s
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '@')))
+            .unwrap_or(s.len());
+        let atom = &s[..atom_len];
+
+        atom.starts_with(|c: char| c.is_ascii_lowercase())
+            .then(|| s.split_at(atom_len))
+    }
+
+    /// Returns the length of the bracketed term at the start of the string,
+    /// including the closing bracket.
+    fn balanced_len(s: &str) -> Option<usize> {
+        let mut depth = 0usize;
+        let mut quote = None;
+        for (index, c) in s.char_indices() {
+            match (quote, c) {
+                (Some(quote_char), c) if c == quote_char => quote = None,
+                (Some(_), _) => {}
+                (None, '"' | '\'') => quote = Some(c),
+                (None, '[' | '{' | '(') => depth += 1,
+                (None, ']' | '}' | ')') => {
+                    depth = depth.checked_sub(1)?;
+                    if depth == 0 {
+                        return Some(index + c.len_utf8());
+                    }
+                }
+                (None, _) => {}
+            }
+        }
+
+        None
+    }
+
+    /// Returns the kind and message from `** (RuntimeError) boom`.
+    fn parse_header(line: &str) -> Option<(&str, Option<&str>)> {
+        let rest = line.trim().strip_prefix(ELIXIR_HEADER_PREFIX)?;
+        match rest.split_once(") ") {
+            Some((kind, message)) => Some((kind, Some(message))),
+            None => Some((rest.strip_suffix(')')?, None)),
+        }
+    }
+
+    /// Returns the reason from `{badarith,[{erlang,'+',..`.
+    fn parse_erlang_reason(line: &str) -> Option<&str> {
+        let rest = line.trim().strip_prefix('{')?;
+        let (reason, rest) = Self::split_erlang_atom(rest)?;
+
+        rest.starts_with(",[{").then_some(reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Frame;
+
+    use super::BeamException;
+
+    #[test]
+    fn parses_elixir_exception() {
+        let beam_exception = BeamException::parse(
+            "\
+            ** (RuntimeError) boom\n\
+            \x20   (my_app 0.1.0) lib/my_app/worker.ex:12: MyApp.Worker.handle_call/3\n\
+            \x20   (my_app 0.1.0) lib/my_app/worker.ex:30: anonymous fn/1 in MyApp.Worker.init/1\n\
+            \x20   (stdlib 5.0) gen_server.erl:1113: :gen_server.try_handle_call/4\n\
+            \x20   (stdlib 5.0) gen_server.erl:1142: :gen_server.handle_msg/6\n\
+            ",
+        )
+        .expect("Expected exception to be parsed.");
+
+        assert_eq!(Some("RuntimeError"), beam_exception.kind());
+        assert_eq!(Some("boom"), beam_exception.message());
+
+        // Frames collapse by application and module.
+        let [my_app, stdlib] = beam_exception.stacktrace().sections.as_slice() else {
+            panic!("Expected two top level sections.");
+        };
+        assert_eq!(Some("my_app 0.1.0"), my_app.frame().library());
+        assert_eq!(1, my_app.child_sections().len());
+        assert_eq!(Some("stdlib 5.0"), stdlib.frame().library());
+        assert_eq!(1, stdlib.child_sections().len());
+    }
+
+    #[test]
+    fn parses_elixir_frame() {
+        let frame = BeamException::parse_frame(
+            "    (my_app 0.1.0) lib/my_app/worker.ex:30: anonymous fn/1 in MyApp.Worker.init/1",
+        );
+
+        let frame_expected = Frame {
+            symbol: Some(String::from("anonymous fn/1 in MyApp.Worker.init/1")),
+            module_path: vec![String::from("MyApp"), String::from("Worker")],
+            function: Some(String::from("init")),
+            arity: Some(1),
+            library: Some(String::from("my_app 0.1.0")),
+            file_path: Some(String::from("lib/my_app/worker.ex")),
+            line: Some(30),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+    }
+
+    #[test]
+    fn parses_erlang_exception() {
+        let beam_exception = BeamException::parse(
+            "\
+            {badarith,[{erlang,'+',[1,a],[{error_info,#{module => erl_erts_errors}}]},\n\
+            \x20          {my_mod,add,2,[{file,\"src/my_mod.erl\"},{line,12}]},\n\
+            \x20          {shell,exprs,7,[{file,\"shell.erl\"},{line,674}]}]}\n\
+            ",
+        )
+        .expect("Expected exception to be parsed.");
+
+        assert_eq!(Some("badarith"), beam_exception.kind());
+        assert_eq!(None, beam_exception.message());
+
+        let frame = BeamException::parse_frame(
+            "           {my_mod,add,2,[{file,\"src/my_mod.erl\"},{line,12}]},",
+        );
+        let frame_expected = Frame {
+            symbol: Some(String::from("my_mod:add/2")),
+            module_path: vec![String::from("my_mod")],
+            function: Some(String::from("add")),
+            arity: Some(2),
+            file_path: Some(String::from("src/my_mod.erl")),
+            line: Some(12),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+
+        let frame = BeamException::parse_frame(
+            "{badarith,[{erlang,'+',[1,a],[{error_info,#{module => erl_erts_errors}}]},",
+        );
+        let frame_expected = Frame {
+            symbol: Some(String::from("erlang:+")),
+            module_path: vec![String::from("erlang")],
+            function: Some(String::from("+")),
+            arguments: Some(String::from("1,a")),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+    }
+
+    #[test]
+    fn parses_erlang_exception_on_one_line() {
+        let beam_exception = BeamException::parse(
+            "{badarg,[{erlang,atom_to_list,[123],[]},{my_mod,f,1,[{file,\"my_mod.erl\"},{line,5}]}]}",
+        )
+        .expect("Expected exception to be parsed.");
+
+        assert_eq!(Some("badarg"), beam_exception.kind());
+
+        let symbols = beam_exception
+            .stacktrace()
+            .sections
+            .iter()
+            .map(|section| section.frame().symbol())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![Some("erlang:atom_to_list"), Some("my_mod:f/1")],
+            symbols
+        );
+    }
+}
@@ -0,0 +1,474 @@
+use crate::{Format, Frame, GroupingKey, Stacktrace};
+
+const GDB_THREAD_PREFIX: &str = "Thread ";
+const LLDB_THREAD_PREFIX: &str = "thread #";
+
+/// A native backtrace printed by `bt` in gdb or lldb, with the stack of each
+/// thread.
+///
+/// ```text
+/// Thread 1 (Thread 0x7ffff7d89740 (LWP 4321)):
+/// #0  0x00007ffff7e2e9fc in pthread_kill () from /lib/x86_64-linux-gnu/libc.so.6
+/// #1  0x0000555555555161 in app::Store::load (this=0x7fffffffe0a0, id=7) at src/store.cc:42
+/// #2  0x00005555555551a4 in main (argc=1, argv=0x7fffffffe1c8) at src/main.cc:9
+/// ```
+///
+/// ```text
+/// * thread #1, queue = 'com.apple.main-thread', stop reason = signal SIGABRT
+///   * frame #0: 0x00000001a0e8a5e0 libsystem_kernel.dylib`__pthread_kill + 8
+///     frame #1: 0x0000000100003f50 app`app::Store::load(int) + 12 at store.cc:42:5
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DebuggerBacktrace {
+    /// Which debugger printed the backtrace.
+    pub debugger: Debugger,
+    /// Each thread in the backtrace.
+    pub threads: Vec<DebuggerThread>,
+}
+
+/// Which debugger printed a backtrace.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Debugger {
+    /// `#3  0x00005555 in foo::bar (x=1) at src/a.cc:12`.
+    #[default]
+    Gdb,
+    /// ``frame #3: 0x00000001 a.out`foo::bar(int) + 12 at a.cc:12:5``.
+    Lldb,
+}
+
+/// A thread within a `DebuggerBacktrace`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DebuggerThread {
+    /// ID of the thread, e.g. `2` for `Thread 2 (..):` or `thread #2`.
+    ///
+    /// This is `None` for frames printed without a thread header.
+    pub id: Option<u32>,
+    /// Details after the thread ID, e.g. `Thread 0x7ffff7d89740 (LWP 4321)`
+    /// or `stop reason = signal SIGABRT`.
+    pub description: Option<String>,
+    /// Frames of the thread.
+    pub stacktrace: Stacktrace,
+}
+
+impl DebuggerBacktrace {
+    /// Parses the threads in the given string.
+    ///
+    /// Returns `None` if there are no gdb or lldb frames.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut debugger = None;
+        let mut threads = Vec::new();
+        let mut thread_frame_lines = Vec::new();
+
+        for line in s.lines() {
+            if let Some((id, description)) = Self::parse_thread_header(line) {
+                threads.push((Some(id), description));
+                thread_frame_lines.push(Vec::new());
+            } else if let Some(line_debugger) = Self::line_debugger(line) {
+                debugger.get_or_insert(line_debugger);
+                if thread_frame_lines.is_empty() {
+                    threads.push((None, None));
+                    thread_frame_lines.push(Vec::new());
+                }
+                if let Some(frame_lines) = thread_frame_lines.last_mut() {
+                    frame_lines.push(line);
+                }
+            }
+        }
+
+        let threads = threads
+            .into_iter()
+            .zip(thread_frame_lines)
+            .map(|((id, description), frame_lines)| DebuggerThread {
+                id,
+                description: description.map(str::to_string),
+                stacktrace: Stacktrace::from_lines(s, frame_lines, Format::Debugger),
+            })
+            .collect();
+
+        Some(Self {
+            debugger: debugger?,
+            threads,
+        })
+    }
+
+    pub fn debugger(&self) -> Debugger {
+        self.debugger
+    }
+
+    pub fn threads(&self) -> &[DebuggerThread] {
+        &self.threads
+    }
+
+    /// Returns whether the line begins a thread, e.g. `Thread 2 (Thread
+    /// 0x7ffff6fff700 (LWP 4322)):` or `* thread #2`.
+    pub(crate) fn is_header_line(line: &str) -> bool {
+        Self::parse_thread_header(line).is_some()
+    }
+
+    /// Parses a gdb or lldb frame line into a `Frame`.
+    pub(crate) fn parse_frame(line: &str) -> Option<Frame> {
+        let (prefix_len, index) = Frame::parse_index_prefix(line)?;
+        let rest = line[prefix_len..].trim_end();
+
+        let frame = match Self::line_debugger(line)? {
+            Debugger::Gdb => Self::parse_gdb_frame(rest),
+            Debugger::Lldb => Self::parse_lldb_frame(rest),
+        };
+
+        Some(Frame {
+            index: Some(index),
+            ..frame
+        })
+    }
+
+    /// Splits the frame index and address from the rest of a frame line.
+    ///
+    /// The address differs on every line, so frames are compared from the
+    /// function onwards, e.g. `app::Store::load (..) at src/store.cc:42`.
+    pub(crate) fn split_frame_prefix(line: &str) -> Option<(&str, &str)> {
+        let (prefix_len, _index) = Frame::parse_index_prefix(line)?;
+        let rest = &line[prefix_len..];
+
+        let address_len = match Self::line_debugger(line)? {
+            Debugger::Gdb => rest
+                .split_once(" in ")
+                .filter(|(address, _rest)| address.starts_with("0x"))
+                .map(|(address, _rest)| address.len() + " in ".len()),
+            Debugger::Lldb => rest
+                .split_once(' ')
+                .filter(|(address, _rest)| address.starts_with("0x"))
+                .map(|(address, _rest)| address.len() + ' '.len_utf8()),
+        }
+        .unwrap_or(0);
+
+        Some(line.split_at(prefix_len + address_len))
+    }
+
+    /// Returns the key that frames are grouped by: the demangled function, or
+    /// the library if the function is unknown.
+    ///
+    /// Frames are grouped by namespace, regardless of their addresses.
+    pub(crate) fn grouping_key(line: &str) -> Option<GroupingKey<'static>> {
+        let frame = Self::parse_frame(line)?;
+        let key = frame
+            .symbol()
+            .or_else(|| frame.library())
+            .unwrap_or_default();
+
+        Some(GroupingKey::from(key.to_string()))
+    }
+
+    /// Returns which debugger printed the frame line, if it is a debugger
+    /// frame.
+    fn line_debugger(line: &str) -> Option<Debugger> {
+        let (prefix_len, _index) = Frame::parse_index_prefix(line)?;
+        let prefix = line[..prefix_len].trim_start();
+        let prefix = prefix.strip_prefix("* ").unwrap_or(prefix);
+
+        // PHP frames are also prefixed with `#N `, but gdb frames either begin with
+        // the address, or have a space before the arguments.
+        let rest = line[prefix_len..].trim();
+        let is_gdb_frame = rest.starts_with("0x") || rest.contains(" (");
+
+        if prefix.starts_with("frame #") {
+            Some(Debugger::Lldb)
+        } else if prefix.starts_with('#') && is_gdb_frame {
+            Some(Debugger::Gdb)
+        } else {
+            None
+        }
+    }
+
+    /// Parses `0x00005555 in foo::bar (x=1) at src/a.cc:12` or `0x00007fff in
+    /// ?? () from /lib/libc.so.6`.
+    fn parse_gdb_frame(rest: &str) -> Frame {
+        let (address, rest) = match rest.split_once(" in ") {
+            Some((address, rest)) if address.starts_with("0x") => {
+                (Frame::parse_address(address), rest)
+            }
+            _ => (None, rest),
+        };
+        let (rest, library) = match rest.rsplit_once(" from ") {
+            Some((rest, library)) => (rest, Some(library)),
+            None => (rest, None),
+        };
+        let (rest, location) = match rest.rsplit_once(" at ") {
+            Some((rest, location)) => (rest, Frame::parse_location(location)),
+            None => (rest, None),
+        };
+        let (function, arguments) = Self::split_trailing_arguments(rest);
+
+        Self::frame(function, arguments, library, location, address, None)
+    }
+
+    /// Parses ``0x00000001 a.out`foo::bar(int) + 12 at a.cc:12:5``.
+    fn parse_lldb_frame(rest: &str) -> Frame {
+        let (address, rest) = match rest.split_once(' ') {
+            Some((address, rest)) if address.starts_with("0x") => {
+                (Frame::parse_address(address), rest)
+            }
+            _ => (None, rest),
+        };
+        let (rest, location) = match rest.rsplit_once(" at ") {
+            Some((rest, location)) => (rest, Frame::parse_location(location)),
+            None => (rest, None),
+        };
+        let (library, rest) = match rest.split_once('`') {
+            Some((library, rest)) => (Some(library), rest),
+            None => (Some(rest), ""),
+        };
+        let (rest, offset) = match rest.rsplit_once(" + ") {
+            Some((rest, offset)) => (rest, offset.parse::<u64>().ok()),
+            None => (rest, None),
+        };
+        let (function, arguments) = Self::split_trailing_arguments(rest);
+
+        Self::frame(function, arguments, library, location, address, offset)
+    }
+
+    fn frame(
+        function: &str,
+        arguments: Option<&str>,
+        library: Option<&str>,
+        location: Option<(&str, u32, Option<u32>)>,
+        address: Option<u64>,
+        offset: Option<u64>,
+    ) -> Frame {
+        // gdb prints `??` when it cannot find the function.
+        let symbol = Some(function).filter(|function| !function.is_empty() && *function != "??");
+        let mut segments = symbol.map(Frame::split_symbol).unwrap_or_default();
+        let function = segments.pop();
+        let (file_path, line, column) = match location {
+            Some((file_path, line, column)) => (Some(file_path.to_string()), Some(line), column),
+            None => (None, None, None),
+        };
+
+        Frame {
+            symbol: symbol.map(str::to_string),
+            module_path: segments.into_iter().map(str::to_string).collect(),
+            function: function.map(str::to_string),
+            arguments: arguments.map(str::to_string),
+            library: library.map(str::to_string),
+            file_path,
+            line,
+            column,
+            address,
+            offset,
+            ..Frame::default()
+        }
+    }
+
+    /// Splits `foo::bar (x=1)` or `foo::bar(int)` into the function and its
+    /// arguments.
+    ///
+    /// Only the trailing parenthesised group is treated as arguments, so
+    /// `operator()(int)` is split into `operator()` and `int`.
+    pub(crate) fn split_trailing_arguments(function: &str) -> (&str, Option<&str>) {
+        let function = function.trim();
+        let Some(function_without_paren) = function.strip_suffix(')') else {
+            return (function, None);
+        };
+
+        let mut depth = 0usize;
+        for (index, c) in function_without_paren.char_indices().rev() {
+            match c {
+                ')' => depth += 1,
+                '(' if depth == 0 => {
+                    return (
+                        function_without_paren[..index].trim_end(),
+                        Some(&function_without_paren[index + 1..]),
+                    );
+                }
+                '(' => depth -= 1,
+                _ => {}
+            }
+        }
+
+        (function, None)
+    }
+
+    /// Returns the ID and description of a gdb or lldb thread header.
+    fn parse_thread_header(line: &str) -> Option<(u32, Option<&str>)> {
+        let line = line.trim();
+        let line = line.strip_prefix("* ").unwrap_or(line);
+
+        if let Some(rest) = line.strip_prefix(GDB_THREAD_PREFIX) {
+            let (id, description) = rest.strip_suffix(':')?.split_once(" (")?;
+            let id = id.parse::<u32>().ok()?;
+            let description = description.strip_suffix(')')?;
+
+            Some((id, Some(description)))
+        } else if let Some(rest) = line.strip_prefix(LLDB_THREAD_PREFIX) {
+            let (id, description) = match rest.split_once(", ") {
+                Some((id, description)) => (id, Some(description)),
+                None => (rest, None),
+            };
+            let id = id.parse::<u32>().ok()?;
+
+            Some((id, description))
+        } else {
+            None
+        }
+    }
+}
+
+impl DebuggerThread {
+    pub fn id(&self) -> Option<u32> {
+        self.id
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn stacktrace(&self) -> &Stacktrace {
+        &self.stacktrace
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Frame;
+
+    use super::{Debugger, DebuggerBacktrace};
+
+    #[test]
+    fn parses_gdb_frame() {
+        let frame = DebuggerBacktrace::parse_frame(
+            "#1  0x0000555555555161 in app::Store::load (this=0x7fffffffe0a0, id=7) at src/store.cc:42",
+        );
+
+        let frame_expected = Frame {
+            index: Some(1),
+            symbol: Some(String::from("app::Store::load")),
+            module_path: vec![String::from("app"), String::from("Store")],
+            function: Some(String::from("load")),
+            arguments: Some(String::from("this=0x7fffffffe0a0, id=7")),
+            file_path: Some(String::from("src/store.cc")),
+            line: Some(42),
+            address: Some(0x555555555161),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+    }
+
+    #[test]
+    fn parses_gdb_frame_from_library() {
+        let frame = DebuggerBacktrace::parse_frame(
+            "#0  0x00007ffff7e2e9fc in ?? () from /lib/x86_64-linux-gnu/libc.so.6",
+        );
+
+        let frame_expected = Frame {
+            index: Some(0),
+            arguments: Some(String::new()),
+            library: Some(String::from("/lib/x86_64-linux-gnu/libc.so.6")),
+            address: Some(0x7ffff7e2e9fc),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+    }
+
+    #[test]
+    fn parses_lldb_frame() {
+        let frame = DebuggerBacktrace::parse_frame(
+            "    frame #1: 0x0000000100003f50 app`app::Store::load(int) + 12 at store.cc:42:5",
+        );
+
+        let frame_expected = Frame {
+            index: Some(1),
+            symbol: Some(String::from("app::Store::load")),
+            module_path: vec![String::from("app"), String::from("Store")],
+            function: Some(String::from("load")),
+            arguments: Some(String::from("int")),
+            library: Some(String::from("app")),
+            file_path: Some(String::from("store.cc")),
+            line: Some(42),
+            column: Some(5),
+            address: Some(0x100003f50),
+            offset: Some(12),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+    }
+
+    #[test]
+    fn parses_threads() {
+        let debugger_backtrace = DebuggerBacktrace::parse(
+            "\
+            * thread #1, queue = 'com.apple.main-thread', stop reason = signal SIGABRT\n\
+            \x20 * frame #0: 0x00000001a0e8a5e0 libsystem_kernel.dylib`__pthread_kill + 8\n\
+            \x20   frame #1: 0x0000000100003f50 app`app::Store::load(int) + 12 at store.cc:42:5\n\
+            \x20 thread #2\n\
+            \x20   frame #0: 0x00000001a0e84b2c libsystem_kernel.dylib`__workq_kernreturn + 8\n\
+            ",
+        )
+        .expect("Expected backtrace to be parsed.");
+
+        assert_eq!(Debugger::Lldb, debugger_backtrace.debugger());
+
+        let [thread_1, thread_2] = debugger_backtrace.threads() else {
+            panic!("Expected two threads.");
+        };
+        assert_eq!(Some(1), thread_1.id());
+        assert_eq!(
+            Some("queue = 'com.apple.main-thread', stop reason = signal SIGABRT"),
+            thread_1.description()
+        );
+        assert_eq!(2, thread_1.stacktrace().sections.len());
+        assert_eq!(Some(2), thread_2.id());
+        assert_eq!(None, thread_2.description());
+    }
+
+    #[test]
+    fn groups_gdb_frames_by_namespace() {
+        let debugger_backtrace = DebuggerBacktrace::parse(
+            "\
+            Thread 1 (Thread 0x7ffff7d89740 (LWP 4321)):\n\
+            #0  0x0000555555555100 in app::Store::get (this=0x7fffffffe0a0) at src/store.cc:20\n\
+            #1  0x0000555555555161 in app::Store::load (this=0x7fffffffe0a0, id=7) at src/store.cc:42\n\
+            #2  0x00005555555551a4 in main (argc=1, argv=0x7fffffffe1c8) at src/main.cc:9\n\
+            ",
+        )
+        .expect("Expected backtrace to be parsed.");
+
+        let [thread] = debugger_backtrace.threads() else {
+            panic!("Expected one thread.");
+        };
+        assert_eq!(Debugger::Gdb, debugger_backtrace.debugger());
+        assert_eq!(
+            Some("Thread 0x7ffff7d89740 (LWP 4321)"),
+            thread.description()
+        );
+
+        let [store_get, main] = thread.stacktrace().sections.as_slice() else {
+            panic!("Expected two top level sections.");
+        };
+        assert_eq!("#0  0x0000555555555100 in ", store_get.slice_frame_index());
+        assert_eq!(
+            Some("load"),
+            store_get.child_sections()[0].frame().function()
+        );
+        assert_eq!(Some("main"), main.frame().function());
+    }
+
+    #[test]
+    fn splits_address_into_frame_prefix() {
+        assert_eq!(
+            Some((
+                "#1  0x0000555555555161 in ",
+                "app::Store::load () at src/store.cc:42"
+            )),
+            DebuggerBacktrace::split_frame_prefix(
+                "#1  0x0000555555555161 in app::Store::load () at src/store.cc:42"
+            )
+        );
+        assert_eq!(
+            Some(("  frame #1: 0x0000000100003f50 ", "app`main + 12")),
+            DebuggerBacktrace::split_frame_prefix("  frame #1: 0x0000000100003f50 app`main + 12")
+        );
+        assert_eq!(
+            None,
+            DebuggerBacktrace::split_frame_prefix("   3: core::fmt::write")
+        );
+    }
+}
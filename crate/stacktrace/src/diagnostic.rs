@@ -0,0 +1,141 @@
+use std::fmt;
+
+use crate::{Format, Frame};
+
+/// A non-fatal problem found while parsing a stack trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    /// Line number of the problem, starting from `1`.
+    pub line_number: usize,
+    /// What the problem is.
+    pub kind: DiagnosticKind,
+}
+
+/// What a `Diagnostic` is about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DiagnosticKind {
+    /// The line is not a header, frame, or continuation line.
+    UnrecognisedLine,
+    /// The line looks like a frame that was cut off, e.g. `at
+    /// com.example.App.run(App.ja`.
+    TruncatedFrame,
+    /// The line is a frame in a different format to the rest of the stack
+    /// trace.
+    MixedFormat(Format),
+}
+
+impl Diagnostic {
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    pub fn kind(&self) -> DiagnosticKind {
+        self.kind
+    }
+
+    /// Returns the diagnostics for each line of the string in the given
+    /// format, and the number of recognised lines.
+    ///
+    /// Blank lines are not diagnosed.
+    pub(crate) fn collect(s: &str, format: Format) -> (Vec<Self>, usize) {
+        let mut frame_line = None;
+        let mut recognised_count = 0usize;
+
+        let diagnostics = s
+            .lines()
+            .enumerate()
+            .filter(|(_line_index, line)| !line.trim().is_empty())
+            .filter_map(|(line_index, line)| {
+                let is_recognised = Self::is_recognised_line(format, frame_line, line);
+                let is_frame_line = match format {
+                    Format::Generic => is_recognised && !format.is_header_line(line),
+                    _ => format.is_frame_line(line),
+                };
+                if is_frame_line {
+                    frame_line = Some(line);
+                }
+                if is_recognised {
+                    recognised_count += 1;
+                }
+
+                let kind = if (is_frame_line || !is_recognised) && Self::is_truncated(line) {
+                    DiagnosticKind::TruncatedFrame
+                } else if is_recognised {
+                    return None;
+                } else if let Some(line_format) = Self::line_format(format, line) {
+                    DiagnosticKind::MixedFormat(line_format)
+                } else {
+                    DiagnosticKind::UnrecognisedLine
+                };
+
+                Some(Self {
+                    line_number: line_index + 1,
+                    kind,
+                })
+            })
+            .collect();
+
+        (diagnostics, recognised_count)
+    }
+
+    /// Returns whether the line is recognised in the given format.
+    ///
+    /// `Generic` recognises frames of every format, and lines that look like a
+    /// symbol or location, e.g. `a::b::Class.method_one`.
+    fn is_recognised_line(format: Format, frame_line: Option<&str>, line: &str) -> bool {
+        if format.is_recognised_line(frame_line, line) {
+            return true;
+        }
+
+        match format {
+            Format::Generic => {
+                Self::line_format(format, line).is_some() || {
+                    let frame = Frame::from(line);
+                    frame.file_path().is_some()
+                        || (!frame.module_path().is_empty()
+                            && frame
+                                .symbol()
+                                .is_some_and(|symbol| !symbol.contains(char::is_whitespace)))
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the first format other than the given one that the line is a
+    /// frame in.
+    fn line_format(format: Format, line: &str) -> Option<Format> {
+        Format::ALL[1..]
+            .iter()
+            .copied()
+            .filter(|line_format| *line_format != format)
+            .find(|line_format| line_format.is_frame_line(line))
+    }
+
+    /// Returns whether the line has more opening brackets than closing
+    /// brackets.
+    fn is_truncated(line: &str) -> bool {
+        let depth = line.chars().fold(0isize, |depth, c| match c {
+            '(' | '[' | '{' => depth + 1,
+            ')' | ']' | '}' => depth - 1,
+            _ => depth,
+        });
+
+        depth > 0
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let line_number = self.line_number;
+        match self.kind {
+            DiagnosticKind::UnrecognisedLine => write!(f, "line {line_number}: unrecognised line"),
+            DiagnosticKind::TruncatedFrame => write!(f, "line {line_number}: frame is truncated"),
+            DiagnosticKind::MixedFormat(format) => write!(
+                f,
+                "line {line_number}: frame is in a different format: {}",
+                format.name()
+            ),
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use crate::{Format, Frame, Stacktrace};
+
+const INNER_EXCEPTION_SEPARATOR: &str = " ---> ";
+const END_OF_INNER_EXCEPTION_MARKER: &str = "--- End of inner exception stack trace ---";
+const END_OF_ASYNC_SEGMENT_MARKERS: [&str; 2] = [
+    "--- End of stack trace from previous location ---",
+    "--- End of stack trace from previous location where exception was thrown ---",
+];
+const UNHANDLED_EXCEPTION_PREFIXES: [&str; 2] = ["Unhandled exception. ", "Unhandled Exception: "];
+
+/// A .NET exception, along with its inner exception.
+///
+/// ```text
+/// System.InvalidOperationException: Failed to load user. ---> System.ArgumentNullException: Value cannot be null. (Parameter 'id')
+///    at MyApp.Services.UserService.Load(String id) in C:\src\MyApp\Services\UserService.cs:line 42
+///    --- End of inner exception stack trace ---
+///    at MyApp.Program.Main(String[] args) in C:\src\MyApp\Program.cs:line 12
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DotnetException {
+    /// Fully qualified type name of the exception, e.g.
+    /// `System.InvalidOperationException`.
+    pub exception_type: String,
+    /// Message of the exception.
+    pub message: Option<String>,
+    /// Frames of this exception, split at each `--- End of stack trace from
+    /// previous location ---` async boundary.
+    pub stacktraces: Vec<Stacktrace>,
+    /// Exception that caused this exception, from `---> `.
+    pub inner_exception: Option<Box<DotnetException>>,
+}
+
+impl DotnetException {
+    /// Parses the first .NET exception in the given string.
+    ///
+    /// Returns `None` if there is no exception header.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut lines = s
+            .lines()
+            .skip_while(|line| Self::parse_header(line).is_none())
+            .peekable();
+
+        let mut exception_types_and_messages = lines.next().and_then(Self::parse_header)?;
+
+        // Messages may span multiple lines, and belong to the innermost exception.
+        while let Some(line) =
+            lines.next_if(|line| !Self::is_frame_or_marker_line(line) && !line.trim().is_empty())
+        {
+            if let Some((_exception_type, message)) = exception_types_and_messages.last_mut() {
+                let message = message.get_or_insert_with(String::new);
+                if !message.is_empty() {
+                    message.push('\n');
+                }
+                message.push_str(line);
+            }
+        }
+
+        // Frames of the innermost exception are printed first, so the blocks are in
+        // reverse order of `exception_types_and_messages`.
+        let mut frame_blocks = vec![vec![Vec::new()]];
+        while let Some(line) = lines.next_if(|line| Self::is_frame_or_marker_line(line)) {
+            let line_trimmed = line.trim();
+            if line_trimmed == END_OF_INNER_EXCEPTION_MARKER {
+                frame_blocks.push(vec![Vec::new()]);
+            } else if END_OF_ASYNC_SEGMENT_MARKERS.contains(&line_trimmed) {
+                if let Some(segments) = frame_blocks.last_mut() {
+                    segments.push(Vec::new());
+                }
+            } else if let Some(segment) = frame_blocks
+                .last_mut()
+                .and_then(|segments| segments.last_mut())
+            {
+                segment.push(line);
+            }
+        }
+
+        let mut frame_blocks = frame_blocks.into_iter();
+        exception_types_and_messages.into_iter().rev().fold(
+            None,
+            |inner_exception, (exception_type, message)| {
+                let stacktraces = frame_blocks
+                    .next()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|segment| !segment.is_empty())
+                    .map(|segment| Stacktrace::from_lines(s, segment, Format::Dotnet))
+                    .collect();
+
+                Some(Self {
+                    exception_type: exception_type.to_string(),
+                    message,
+                    stacktraces,
+                    inner_exception: inner_exception.map(Box::new),
+                })
+            },
+        )
+    }
+
+    pub fn exception_type(&self) -> &str {
+        &self.exception_type
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    pub fn stacktraces(&self) -> &[Stacktrace] {
+        &self.stacktraces
+    }
+
+    pub fn inner_exception(&self) -> Option<&DotnetException> {
+        self.inner_exception.as_deref()
+    }
+
+    /// Returns an iterator over this exception and each of its inner
+    /// exceptions.
+    pub fn chain(&self) -> impl Iterator<Item = &DotnetException> {
+        std::iter::successors(Some(self), |exception| exception.inner_exception())
+    }
+
+    /// Returns whether the line is an unindented exception header, or a
+    /// `--- End of .. ---` marker.
+    ///
+    /// Frames after a marker are grouped separately from the frames before
+    /// it.
+    pub(crate) fn is_header_line(line: &str) -> bool {
+        Self::is_marker_line(line)
+            || (!line.starts_with(char::is_whitespace) && Self::parse_header(line).is_some())
+    }
+
+    /// Parses `at Namespace.Class.Method(Args) in C:\path\File.cs:line 42`
+    /// into a `Frame`.
+    pub(crate) fn parse_frame(line: &str) -> Option<Frame> {
+        let rest = line.trim().strip_prefix("at ")?;
+        let (signature, location) = match rest.rsplit_once(" in ") {
+            Some((signature, location)) if location.contains(":line ") => {
+                (signature, Some(location))
+            }
+            _ => (rest, None),
+        };
+
+        // JVM frames have the location within the parentheses, and V8 frames have a
+        // space before the parentheses.
+        let (symbol, arguments) = signature.strip_suffix(')')?.split_once('(')?;
+        let is_dotnet_signature = !symbol.is_empty()
+            && !symbol.contains(char::is_whitespace)
+            && !arguments.contains(':')
+            && !matches!(arguments, "Native Method" | "Unknown Source");
+        if !is_dotnet_signature {
+            return None;
+        }
+
+        let (file_path, line) = match location
+            .and_then(|location| location.rsplit_once(":line "))
+            .and_then(|(file_path, line)| Some((file_path, line.parse::<u32>().ok()?)))
+        {
+            Some((file_path, line)) => (Some(file_path.to_string()), Some(line)),
+            None => (None, None),
+        };
+
+        let mut segments = Frame::split_symbol(symbol);
+        let function = segments.pop();
+
+        Some(Frame {
+            symbol: Some(symbol.to_string()),
+            module_path: segments.into_iter().map(str::to_string).collect(),
+            function: function.map(str::to_string),
+            arguments: Some(arguments.to_string()),
+            file_path,
+            line,
+            ..Frame::default()
+        })
+    }
+
+    fn is_frame_or_marker_line(line: &str) -> bool {
+        Self::is_marker_line(line) || Self::parse_frame(line).is_some()
+    }
+
+    fn is_marker_line(line: &str) -> bool {
+        let line_trimmed = line.trim();
+        line_trimmed == END_OF_INNER_EXCEPTION_MARKER
+            || END_OF_ASYNC_SEGMENT_MARKERS.contains(&line_trimmed)
+    }
+
+    /// Returns the exception type and message of each exception in the
+    /// header, outermost first.
+    fn parse_header(line: &str) -> Option<Vec<(&str, Option<String>)>> {
+        let line = line.trim();
+        let line = UNHANDLED_EXCEPTION_PREFIXES
+            .iter()
+            .find_map(|prefix| line.strip_prefix(prefix))
+            .unwrap_or(line);
+
+        line.split(INNER_EXCEPTION_SEPARATOR)
+            .map(|exception| {
+                let (exception_type, message) = match exception.split_once(": ") {
+                    Some((exception_type, message)) => (exception_type, Some(message.to_string())),
+                    None => (exception.trim_end_matches(':'), None),
+                };
+
+                let is_exception_type = exception_type.ends_with("Exception")
+                    && exception_type
+                        .chars()
+                        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '`'));
+
+                is_exception_type.then_some((exception_type, message))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Frame, Stacktrace};
+
+    use super::DotnetException;
+
+    #[test]
+    fn parses_exception_with_inner_exception() {
+        let s = "\
+            System.InvalidOperationException: Failed to load user. ---> System.ArgumentNullException: Value cannot be null. (Parameter 'id')\n\
+            \x20  at MyApp.Services.UserService.Load(String id) in C:\\src\\MyApp\\Services\\UserService.cs:line 42\n\
+            \x20  --- End of inner exception stack trace ---\n\
+            \x20  at MyApp.Program.Main(String[] args) in C:\\src\\MyApp\\Program.cs:line 12\n\
+            ";
+        let dotnet_exception = DotnetException::parse(s);
+
+        let dotnet_exception_expected = DotnetException {
+            exception_type: String::from("System.InvalidOperationException"),
+            message: Some(String::from("Failed to load user.")),
+            stacktraces: vec![Stacktrace::from_substring(
+                s,
+                "   at MyApp.Program.Main(String[] args) in C:\\src\\MyApp\\Program.cs:line 12\n",
+            )],
+            inner_exception: Some(Box::new(DotnetException {
+                exception_type: String::from("System.ArgumentNullException"),
+                message: Some(String::from("Value cannot be null. (Parameter 'id')")),
+                stacktraces: vec![Stacktrace::from_substring(
+                s,
+                    "   at MyApp.Services.UserService.Load(String id) in C:\\src\\MyApp\\Services\\UserService.cs:line 42\n",
+                )],
+                inner_exception: None,
+            })),
+        };
+        assert_eq!(Some(dotnet_exception_expected), dotnet_exception);
+    }
+
+    #[test]
+    fn parses_multi_line_message_after_header_without_message() {
+        let dotnet_exception = DotnetException::parse(
+            "\
+            System.InvalidOperationException\n\
+            first line\n\
+            second line\n\
+            \x20  at MyApp.Program.Main(String[] args) in C:\\src\\MyApp\\Program.cs:line 12\n\
+            ",
+        )
+        .expect("Expected exception to be parsed.");
+
+        assert_eq!(
+            "System.InvalidOperationException",
+            dotnet_exception.exception_type()
+        );
+        assert_eq!(Some("first line\nsecond line"), dotnet_exception.message());
+    }
+
+    #[test]
+    fn splits_frames_at_async_boundaries() {
+        let dotnet_exception = DotnetException::parse(
+            "\
+            Unhandled exception. System.Net.Http.HttpRequestException: Connection refused\n\
+            \x20  at System.Net.Http.HttpConnectionPool.ConnectAsync(HttpRequestMessage request)\n\
+            \x20  at System.Net.Http.HttpClient.SendAsync(HttpRequestMessage request)\n\
+            --- End of stack trace from previous location ---\n\
+            \x20  at MyApp.Program.Main(String[] args) in /src/MyApp/Program.cs:line 9\n\
+            \x20  at MyApp.Program.<Main>(String[] args)\n\
+            ",
+        )
+        .expect("Expected exception to be parsed.");
+
+        assert_eq!(
+            "System.Net.Http.HttpRequestException",
+            dotnet_exception.exception_type()
+        );
+        assert_eq!(2, dotnet_exception.stacktraces().len());
+        assert_eq!(None, dotnet_exception.inner_exception());
+    }
+
+    #[test]
+    fn parses_frame() {
+        let frame = DotnetException::parse_frame(
+            "   at MyApp.Services.UserService.Load(String id) in C:\\src\\MyApp\\Services\\UserService.cs:line 42\n",
+        );
+
+        let frame_expected = Frame {
+            symbol: Some(String::from("MyApp.Services.UserService.Load")),
+            module_path: vec![
+                String::from("MyApp"),
+                String::from("Services"),
+                String::from("UserService"),
+            ],
+            function: Some(String::from("Load")),
+            arguments: Some(String::from("String id")),
+            file_path: Some(String::from("C:\\src\\MyApp\\Services\\UserService.cs")),
+            line: Some(42),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+    }
+
+    #[test]
+    fn does_not_parse_jvm_or_v8_frames() {
+        assert_eq!(
+            None,
+            DotnetException::parse_frame("\tat com.example.App.run(App.java:21)")
+        );
+        assert_eq!(
+            None,
+            DotnetException::parse_frame("    at Array.forEach (<anonymous>)")
+        );
+    }
+}
@@ -0,0 +1,653 @@
+use crate::{
+    AndroidCrash, AppleCrashReport, BeamException, DebuggerBacktrace, DotnetException, Frame,
+    GoroutineDump, GroupingKey, JavaException, JavaThreadDump, JavascriptError, KernelOops,
+    PhpException, PythonException, RubyException, SanitizerReport,
+};
+
+const RUST_BACKTRACE_HEADER: &str = "stack backtrace:";
+
+/// A stack trace format, which determines how lines are recognised and
+/// grouped.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Lines are recognised using every format, and compared by their leading
+    /// characters.
+    ///
+    /// This is used when no other format is detected.
+    #[default]
+    Generic,
+    /// Rust backtrace, e.g. `   3: core::fmt::write`.
+    Rust,
+    /// Java exception, e.g. `        at com.example.App.run(App.java:21)`.
+    Java,
+    /// Java thread dump printed by `jstack`, e.g. `"main" #1 prio=5 ..`.
+    JavaThreadDump,
+    /// Android crash, ANR, or tombstone, optionally with logcat prefixes, e.g.
+    /// `E AndroidRuntime: FATAL EXCEPTION: main`.
+    Android,
+    /// Python traceback, e.g. `  File "main.py", line 2, in main`.
+    Python,
+    /// V8 or SpiderMonkey JavaScript stack, e.g. `    at next
+    /// (/app/route.js:137:13)`.
+    Javascript,
+    /// Go panic or goroutine dump, e.g. `goroutine 1 [running]:`.
+    Go,
+    /// .NET exception, e.g. `   at MyApp.Program.Main(String[] args)`.
+    Dotnet,
+    /// Ruby backtrace, e.g. `app/models/user.rb:42:in 'User#save'`.
+    Ruby,
+    /// PHP exception, e.g. `#0 /var/www/x.php(12): Foo->bar()`.
+    Php,
+    /// Elixir or Erlang stacktrace, e.g. `(my_app 0.1.0) lib/my_app.ex:12:
+    /// MyApp.run/0`.
+    Beam,
+    /// ASan, LSan, MSan, TSan, or UBSan report, e.g. `    #0 0x4f5a2b in main
+    /// /src/app.c:12:3`.
+    Sanitizer,
+    /// Linux kernel oops, panic, or warning, e.g. `
+    /// do_one_initcall+0x46/0x1e0`.
+    Kernel,
+    /// iOS or macOS crash report, e.g. `1   MyApp   0x0000000100a1b2c3
+    /// MyClass.method() + 120`.
+    Apple,
+    /// gdb or lldb backtrace, e.g. `#3  0x00005555 in foo::bar (x=1)`.
+    Debugger,
+}
+
+impl Format {
+    /// All formats, with `Generic` first.
+    pub const ALL: [Format; 16] = [
+        Format::Generic,
+        Format::Rust,
+        Format::Java,
+        Format::JavaThreadDump,
+        Format::Android,
+        Format::Python,
+        Format::Javascript,
+        Format::Go,
+        Format::Dotnet,
+        Format::Ruby,
+        Format::Php,
+        Format::Beam,
+        Format::Sanitizer,
+        Format::Kernel,
+        Format::Apple,
+        Format::Debugger,
+    ];
+
+    /// Returns the human readable name of the format, e.g. `Rust backtrace`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Generic => "Generic stack trace",
+            Self::Rust => "Rust backtrace",
+            Self::Java => "Java exception",
+            Self::JavaThreadDump => "Java thread dump",
+            Self::Android => "Android crash / ANR",
+            Self::Python => "Python traceback",
+            Self::Javascript => "JavaScript error",
+            Self::Go => "Go panic",
+            Self::Dotnet => ".NET exception",
+            Self::Ruby => "Ruby backtrace",
+            Self::Php => "PHP exception",
+            Self::Beam => "Erlang / Elixir stacktrace",
+            Self::Sanitizer => "Sanitizer report",
+            Self::Kernel => "Linux kernel oops",
+            Self::Apple => "Apple crash report",
+            Self::Debugger => "gdb / lldb backtrace",
+        }
+    }
+
+    /// Returns how confident we are that the string is in this format, from
+    /// `0.0` to `1.0`.
+    ///
+    /// This is the proportion of non-blank lines that are recognised as a
+    /// header, frame, or continuation line of this format. `Generic` is the
+    /// fallback format, so its confidence is always `0.0`.
+    pub fn confidence(self, s: &str) -> f32 {
+        if self == Self::Generic {
+            return 0.0;
+        }
+
+        let mut line_count = 0usize;
+        let mut recognised_count = 0usize;
+        let mut frame_line = None;
+        s.lines()
+            .filter(|line| !line.trim().is_empty())
+            .for_each(|line| {
+                let is_recognised = self.is_recognised_line(frame_line, line);
+
+                if self.is_frame_line(line) {
+                    frame_line = Some(line);
+                }
+                line_count += 1;
+                if is_recognised {
+                    recognised_count += 1;
+                }
+            });
+
+        if line_count == 0 {
+            0.0
+        } else {
+            recognised_count as f32 / line_count as f32
+        }
+    }
+
+    /// Returns whether the line is a header, frame, or continuation line of
+    /// this format.
+    ///
+    /// `frame_line` is the most recent frame line, which continuation lines
+    /// belong to.
+    pub(crate) fn is_recognised_line(self, frame_line: Option<&str>, line: &str) -> bool {
+        self.is_frame_line(line)
+            || self.is_header_line(line)
+            || self.is_header_continuation_line(line)
+            || frame_line.is_some_and(|frame_line| self.is_continuation_line(frame_line, line))
+    }
+
+    /// Returns whether the line introduces the frames after it.
+    pub(crate) fn is_header_line(self, line: &str) -> bool {
+        match self {
+            // Python exception lines are only headers when the stack trace is known to be
+            // a Python traceback, as any unindented word looks like an exception type.
+            Self::Generic => {
+                PythonException::is_header_line(line)
+                    || Self::ALL[1..]
+                        .iter()
+                        .filter(|format| **format != Self::Python)
+                        .any(|format| format.is_header_line(line))
+            }
+            Self::Rust => Self::is_rust_header_line(line),
+            Self::Java => JavaException::is_header_line(line),
+            Self::JavaThreadDump => JavaThreadDump::is_header_line(line),
+            Self::Android => AndroidCrash::is_header_line(line),
+            Self::Python => {
+                PythonException::is_header_line(line) || PythonException::is_exception_line(line)
+            }
+            Self::Javascript => JavascriptError::is_header_line(line),
+            Self::Go => GoroutineDump::is_header_line(line),
+            Self::Dotnet => DotnetException::is_header_line(line),
+            Self::Ruby => RubyException::is_header_line(line),
+            Self::Php => PhpException::is_header_line(line),
+            Self::Beam => BeamException::is_header_line(line),
+            Self::Sanitizer => SanitizerReport::is_header_line(line),
+            Self::Kernel => KernelOops::is_header_line(line),
+            Self::Apple => AppleCrashReport::is_header_line(line),
+            Self::Debugger => DebuggerBacktrace::is_header_line(line),
+        }
+    }
+
+    /// Returns whether the line ends the frames of the header on
+    /// `header_line`, e.g. the exception line after a Python traceback's
+    /// frames.
+    pub(crate) fn is_footer_line(self, header_line: &str, line: &str) -> bool {
+        match self {
+            Self::Generic | Self::Python => PythonException::is_footer_line(header_line, line),
+            Self::Rust
+            | Self::Java
+            | Self::JavaThreadDump
+            | Self::Android
+            | Self::Javascript
+            | Self::Go
+            | Self::Dotnet
+            | Self::Ruby
+            | Self::Php
+            | Self::Beam
+            | Self::Sanitizer
+            | Self::Kernel
+            | Self::Apple
+            | Self::Debugger => false,
+        }
+    }
+
+    /// Returns whether the line belongs to the footer before it, e.g. the
+    /// `During handling of the above exception, ..` separator after a Python
+    /// exception line.
+    pub(crate) fn is_footer_continuation_line(self, line: &str) -> bool {
+        match self {
+            Self::Generic | Self::Python => PythonException::is_chain_separator_line(line),
+            Self::Rust
+            | Self::Java
+            | Self::JavaThreadDump
+            | Self::Android
+            | Self::Javascript
+            | Self::Go
+            | Self::Dotnet
+            | Self::Ruby
+            | Self::Php
+            | Self::Beam
+            | Self::Sanitizer
+            | Self::Kernel
+            | Self::Apple
+            | Self::Debugger => false,
+        }
+    }
+
+    /// Returns whether the line belongs to the frame on `frame_line`.
+    pub(crate) fn is_continuation_line(self, frame_line: &str, line: &str) -> bool {
+        match self {
+            Self::Generic => {
+                Frame::is_rust_continuation_line(frame_line, line)
+                    || JavaException::is_continuation_line(line)
+                    || PythonException::is_continuation_line(frame_line, line)
+                    || GoroutineDump::is_continuation_line(line)
+                    || PhpException::is_continuation_line(line)
+                    || JavaThreadDump::is_continuation_line(line)
+            }
+            Self::Rust => Frame::is_continuation_line(line),
+            Self::Java => JavaException::is_continuation_line(line),
+            Self::JavaThreadDump => JavaThreadDump::is_continuation_line(line),
+            Self::Android => AndroidCrash::is_continuation_line(line),
+            Self::Python => PythonException::is_continuation_line(frame_line, line),
+            Self::Go => GoroutineDump::is_continuation_line(line),
+            Self::Php => PhpException::is_continuation_line(line),
+            Self::Javascript
+            | Self::Dotnet
+            | Self::Ruby
+            | Self::Beam
+            | Self::Sanitizer
+            | Self::Kernel
+            | Self::Apple
+            | Self::Debugger => false,
+        }
+    }
+
+    /// Returns whether the line belongs to the header before it, e.g. the
+    /// register dump after `BUG: ..` in a kernel oops.
+    pub(crate) fn is_header_continuation_line(self, line: &str) -> bool {
+        match self {
+            Self::Generic => {
+                KernelOops::is_metadata_line(line)
+                    || JavaThreadDump::is_header_continuation_line(line)
+                    || AppleCrashReport::is_header_continuation_line(line)
+                    || PhpException::is_header_continuation_line(line)
+            }
+            Self::JavaThreadDump => JavaThreadDump::is_header_continuation_line(line),
+            Self::Android => AndroidCrash::is_header_continuation_line(line),
+            Self::Kernel => KernelOops::is_metadata_line(line),
+            Self::Apple => AppleCrashReport::is_header_continuation_line(line),
+            Self::Php => PhpException::is_header_continuation_line(line),
+            Self::Rust
+            | Self::Java
+            | Self::Python
+            | Self::Javascript
+            | Self::Go
+            | Self::Dotnet
+            | Self::Ruby
+            | Self::Beam
+            | Self::Sanitizer
+            | Self::Debugger => false,
+        }
+    }
+
+    pub(crate) fn parse_frame(self, line: &str) -> Frame {
+        let frame = match self {
+            Self::Generic => SanitizerReport::parse_frame(line)
+                .or_else(|| DebuggerBacktrace::parse_frame(line))
+                .or_else(|| PythonException::parse_frame(line))
+                .or_else(|| JavascriptError::parse_frame(line))
+                .or_else(|| DotnetException::parse_frame(line))
+                .or_else(|| RubyException::parse_frame(line))
+                .or_else(|| PhpException::parse_frame(line))
+                .or_else(|| BeamException::parse_frame(line))
+                .or_else(|| KernelOops::parse_frame(line))
+                .or_else(|| AppleCrashReport::parse_frame(line))
+                .or_else(|| AndroidCrash::parse_native_frame(line))
+                .or_else(|| GoroutineDump::parse_frame(line)),
+            Self::Rust | Self::Java | Self::JavaThreadDump => None,
+            Self::Android => AndroidCrash::parse_frame(line),
+            Self::Python => PythonException::parse_frame(line),
+            Self::Javascript => JavascriptError::parse_frame(line),
+            Self::Go => GoroutineDump::parse_frame(line),
+            Self::Dotnet => DotnetException::parse_frame(line),
+            Self::Ruby => RubyException::parse_frame(line),
+            Self::Php => PhpException::parse_frame(line),
+            Self::Beam => BeamException::parse_frame(line),
+            Self::Sanitizer => SanitizerReport::parse_frame(line),
+            Self::Kernel => KernelOops::parse_frame(line),
+            Self::Apple => AppleCrashReport::parse_frame(line),
+            Self::Debugger => DebuggerBacktrace::parse_frame(line),
+        };
+
+        frame.unwrap_or_else(|| Frame::from(line))
+    }
+
+    pub(crate) fn frame_with_continuation_line(
+        self,
+        frame: Frame,
+        frame_line: &str,
+        continuation_line: &str,
+    ) -> Frame {
+        let is_python = matches!(self, Self::Generic | Self::Python);
+        let is_go = matches!(self, Self::Generic | Self::Go);
+
+        if is_python && PythonException::is_frame_line(frame_line) {
+            PythonException::frame_with_source_line(frame, continuation_line)
+        } else if is_go && GoroutineDump::is_continuation_line(continuation_line) {
+            GoroutineDump::frame_with_location_line(frame, continuation_line)
+        } else {
+            frame.with_continuation_line(continuation_line)
+        }
+    }
+
+    /// Returns the key that the line is compared with other lines by.
+    ///
+    /// This is the line itself, unless the format groups frames by something
+    /// other than their leading characters.
+    pub(crate) fn grouping_key(self, line: &str) -> GroupingKey<'_> {
+        let grouping_key = match self {
+            Self::Generic => JavascriptError::grouping_key(line)
+                .or_else(|| PythonException::grouping_key(line))
+                .or_else(|| RubyException::grouping_key(line))
+                .or_else(|| PhpException::grouping_key(line))
+                .or_else(|| BeamException::grouping_key(line))
+                .or_else(|| SanitizerReport::grouping_key(line))
+                .or_else(|| KernelOops::grouping_key(line))
+                .or_else(|| AppleCrashReport::grouping_key(line))
+                .or_else(|| DebuggerBacktrace::grouping_key(line)),
+            Self::Python => PythonException::grouping_key(line),
+            Self::Javascript => JavascriptError::grouping_key(line),
+            Self::Ruby => RubyException::grouping_key(line),
+            Self::Php => PhpException::grouping_key(line),
+            Self::Beam => BeamException::grouping_key(line),
+            Self::Sanitizer => SanitizerReport::grouping_key(line),
+            Self::Kernel => KernelOops::grouping_key(line),
+            Self::Apple => AppleCrashReport::grouping_key(line),
+            Self::Debugger => DebuggerBacktrace::grouping_key(line),
+            Self::Rust
+            | Self::Java
+            | Self::JavaThreadDump
+            | Self::Android
+            | Self::Go
+            | Self::Dotnet => None,
+        };
+
+        grouping_key.unwrap_or_else(|| GroupingKey::from(line))
+    }
+
+    /// Returns the byte index where the frame's location starts, for formats
+    /// that group frames by a location at the end of the line.
+    ///
+    /// The slice common with previous frames ends before this, so that it
+    /// doesn't cut through a location, leaving only the line and column.
+    pub(crate) fn location_start(self, line: &str) -> Option<usize> {
+        match self {
+            Self::Generic | Self::Javascript => JavascriptError::location_start(line),
+            Self::Rust
+            | Self::Java
+            | Self::JavaThreadDump
+            | Self::Android
+            | Self::Python
+            | Self::Go
+            | Self::Dotnet
+            | Self::Ruby
+            | Self::Php
+            | Self::Beam
+            | Self::Sanitizer
+            | Self::Kernel
+            | Self::Apple
+            | Self::Debugger => None,
+        }
+    }
+
+    /// Splits the line into the prefix that is not compared with other lines,
+    /// and the rest of the line.
+    ///
+    /// This is the frame index, and for debugger backtraces the frame address.
+    pub(crate) fn split_frame_prefix(self, line: &str) -> (&str, &str) {
+        let frame_prefix_and_rest = match self {
+            Self::Generic => DebuggerBacktrace::split_frame_prefix(line)
+                .or_else(|| KernelOops::split_frame_prefix(line))
+                .or_else(|| AppleCrashReport::split_frame_prefix(line)),
+            Self::Sanitizer | Self::Debugger => DebuggerBacktrace::split_frame_prefix(line),
+            Self::Kernel => KernelOops::split_frame_prefix(line),
+            Self::Apple => AppleCrashReport::split_frame_prefix(line),
+            Self::Android => Some(AndroidCrash::split_frame_prefix(line)),
+            Self::Rust
+            | Self::Java
+            | Self::JavaThreadDump
+            | Self::Python
+            | Self::Javascript
+            | Self::Go
+            | Self::Dotnet
+            | Self::Ruby
+            | Self::Php
+            | Self::Beam => None,
+        };
+
+        frame_prefix_and_rest.unwrap_or_else(|| Frame::split_index_prefix(line))
+    }
+
+    /// Returns whether the line is a frame in this format.
+    ///
+    /// This is used to detect the format, so it is stricter than `parse_frame`,
+    /// which accepts any line.
+    pub(crate) fn is_frame_line(self, line: &str) -> bool {
+        match self {
+            Self::Generic => false,
+            Self::Rust => Self::is_rust_frame_line(line),
+            Self::Java | Self::JavaThreadDump => JavaException::is_stack_frame_line(line),
+            Self::Android => AndroidCrash::is_frame_line(line),
+            Self::Python => PythonException::is_frame_line(line),
+            Self::Javascript => JavascriptError::is_frame_line(line),
+            Self::Go => GoroutineDump::parse_frame(line).is_some(),
+            Self::Dotnet => DotnetException::parse_frame(line).is_some(),
+            Self::Ruby => RubyException::parse_frame(line).is_some(),
+            Self::Php => PhpException::parse_frame(line).is_some(),
+            Self::Beam => BeamException::parse_frame(line).is_some(),
+            Self::Sanitizer => SanitizerReport::parse_frame(line).is_some(),
+            Self::Kernel => KernelOops::parse_frame(line).is_some(),
+            Self::Apple => AppleCrashReport::parse_frame(line).is_some(),
+            Self::Debugger => DebuggerBacktrace::parse_frame(line).is_some(),
+        }
+    }
+
+    /// Returns whether the line begins a Rust backtrace, e.g. `stack
+    /// backtrace:` or `thread 'main' panicked at src/main.rs:2:5:`.
+    fn is_rust_header_line(line: &str) -> bool {
+        let line = line.trim();
+        line == RUST_BACKTRACE_HEADER
+            || (line.starts_with("thread '") && line.contains("' panicked at "))
+    }
+
+    /// Returns whether the line is an indexed Rust frame, e.g. `   3:
+    /// core::fmt::write`.
+    fn is_rust_frame_line(line: &str) -> bool {
+        let (slice_frame_index, rest) = Frame::split_index_prefix(line);
+        slice_frame_index.trim_end().ends_with(':')
+            && !slice_frame_index.contains('#')
+            && !rest.trim().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FormatDetection, Stacktrace};
+
+    use super::Format;
+
+    #[test]
+    fn detects_rust_backtrace() {
+        let s = "\
+            stack backtrace:\n\
+            \x20  0: std::panicking::begin_panic\n\
+            \x20            at /rustc/library/std/src/panicking.rs:616:12\n\
+            \x20  1: app::main\n\
+            \x20            at ./src/main.rs:2:5\n\
+            ";
+
+        assert_eq!(Format::Rust, Stacktrace::detect_format(s));
+        assert_eq!(1.0, Format::Rust.confidence(s));
+    }
+
+    #[test]
+    fn detects_each_format() {
+        [
+            (
+                Format::Java,
+                "\
+                java.lang.IllegalStateException: Failed to run\n\
+                \tat com.example.App.run(App.java:21)\n\
+                \tat com.example.App.main(App.java:14)\n\
+                ",
+            ),
+            (
+                Format::JavaThreadDump,
+                "\
+                \"main\" #1 prio=5 os_prio=0 tid=0x00007f3c2800a000 nid=0x1a03 runnable\n\
+                \x20  java.lang.Thread.State: RUNNABLE\n\
+                \tat com.example.App.run(App.java:21)\n\
+                \t- locked <0x000000071a6b9000> (a java.lang.Object)\n\
+                \tat com.example.App.main(App.java:14)\n\
+                ",
+            ),
+            (
+                Format::Android,
+                "\
+                E AndroidRuntime: FATAL EXCEPTION: main\n\
+                E AndroidRuntime: Process: com.example.app, PID: 1234\n\
+                E AndroidRuntime: java.lang.NullPointerException: boom\n\
+                E AndroidRuntime: \tat com.example.MainActivity.onCreate(MainActivity.java:12)\n\
+                ",
+            ),
+            (
+                Format::Python,
+                "\
+                Traceback (most recent call last):\n\
+                \x20 File \"main.py\", line 2, in main\n\
+                \x20   raise ValueError(\"bad value\")\n\
+                ValueError: bad value\n\
+                ",
+            ),
+            (
+                Format::Javascript,
+                "\
+                TypeError: x is undefined\n\
+                \x20   at Object.handler (/app/src/routes/user.js:42:13)\n\
+                \x20   at next (/app/node_modules/express/lib/router/route.js:137:13)\n\
+                ",
+            ),
+            (
+                Format::Go,
+                "\
+                goroutine 1 [running]:\n\
+                main.main()\n\
+                \t/home/user/app/main.go:8 +0x1d\n\
+                ",
+            ),
+            (
+                Format::Dotnet,
+                "\
+                System.InvalidOperationException: Failed\n\
+                \x20  at MyApp.Program.Main(String[] args) in C:\\src\\Program.cs:line 12\n\
+                ",
+            ),
+            (
+                Format::Ruby,
+                "\
+                app/models/user.rb:42:in 'User#save': undefined method 'id' for nil (NoMethodError)\n\
+                \tfrom app/controllers/users_controller.rb:12:in 'UsersController#create'\n\
+                ",
+            ),
+            (
+                Format::Php,
+                "\
+                PHP Fatal error:  Uncaught RuntimeException: Failed in /var/www/src/Db.php:10\n\
+                Stack trace:\n\
+                #0 /var/www/public/index.php(12): App\\Db->connect()\n\
+                #1 {main}\n\
+                \x20 thrown in /var/www/src/Db.php on line 10\n\
+                ",
+            ),
+            (
+                Format::Beam,
+                "\
+                ** (RuntimeError) boom\n\
+                \x20   (my_app 0.1.0) lib/my_app/worker.ex:12: MyApp.Worker.handle_call/3\n\
+                \x20   (stdlib 5.0) gen_server.erl:1113: :gen_server.try_handle_call/4\n\
+                ",
+            ),
+            (
+                Format::Sanitizer,
+                "\
+                ==4321==ERROR: AddressSanitizer: heap-use-after-free on address 0x602000000010\n\
+                READ of size 4 at 0x602000000010 thread T0\n\
+                \x20   #0 0x4f5a2b in main /src/app.c:12:3\n\
+                SUMMARY: AddressSanitizer: heap-use-after-free /src/app.c:12:3 in main\n\
+                ",
+            ),
+            (
+                Format::Kernel,
+                "\
+                BUG: kernel NULL pointer dereference, address: 0000000000000000\n\
+                RIP: 0010:my_init+0x15/0x30 [my_module]\n\
+                Call Trace:\n\
+                \x20<TASK>\n\
+                \x20do_one_initcall+0x46/0x1e0\n\
+                \x20do_init_module+0x52/0x220 [my_module]\n\
+                \x20</TASK>\n\
+                ",
+            ),
+            (
+                Format::Apple,
+                "\
+                Exception Type:  EXC_CRASH (SIGABRT)\n\
+                Thread 0 Crashed:\n\
+                0   libsystem_kernel.dylib        \t0x00000001e5f6a1b8 __pthread_kill + 8\n\
+                1   MyApp                         \t0x0000000100a1b2c3 MyClass.method() + 120\n\
+                ",
+            ),
+            (
+                Format::Debugger,
+                "\
+                #0  0x00007ffff7e2e9fc in pthread_kill () from /lib/x86_64-linux-gnu/libc.so.6\n\
+                #1  0x0000555555555161 in app::Store::load (id=7) at src/store.cc:42\n\
+                ",
+            ),
+        ]
+        .into_iter()
+        .for_each(|(format_expected, s)| {
+            assert_eq!(format_expected, Stacktrace::detect_format(s), "{s}");
+        });
+    }
+
+    #[test]
+    fn detects_format_with_confidence_when_other_formats_recognise_some_lines() {
+        let s = "\
+            Error: boom\n\
+            \x20   at foo (/app/a.js:1:2)\n\
+            \x20   at bar (/app/b.js:3:4)\n\
+            \x20   at /app/c.js:5:6\n\
+            ";
+
+        // The header is also an exception line in these formats.
+        assert_eq!(0.25, Format::Java.confidence(s));
+        assert_eq!(0.25, Format::Android.confidence(s));
+        assert_eq!(0.25, Format::Python.confidence(s));
+
+        let format_detection = Stacktrace::detect_format_with_confidence(s);
+
+        assert_eq!(
+            FormatDetection {
+                format: Format::Javascript,
+                confidence: 1.0,
+            },
+            format_detection
+        );
+    }
+
+    #[test]
+    fn detects_generic_when_no_format_matches() {
+        assert_eq!(
+            Format::Generic,
+            Stacktrace::detect_format("a::b::Class.method_one")
+        );
+        assert_eq!(Format::Generic, Stacktrace::detect_format(""));
+        assert_eq!(
+            FormatDetection {
+                format: Format::Generic,
+                confidence: 0.0,
+            },
+            Stacktrace::detect_format_with_confidence("")
+        );
+    }
+}
@@ -0,0 +1,26 @@
+use crate::Format;
+
+/// The format a stack trace is most likely in, and how confident we are.
+///
+/// Returned by [`Stacktrace::detect_format_with_confidence`].
+///
+/// [`Stacktrace::detect_format_with_confidence`]: crate::Stacktrace::detect_format_with_confidence
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FormatDetection {
+    /// The most confident format, or [`Format::Generic`] if no format
+    /// recognises any line.
+    pub format: Format,
+    /// Confidence of `format` from [`Format::confidence`], from `0.0` to
+    /// `1.0`.
+    pub confidence: f32,
+}
+
+impl FormatDetection {
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    pub fn confidence(&self) -> f32 {
+        self.confidence
+    }
+}
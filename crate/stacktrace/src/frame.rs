@@ -0,0 +1,494 @@
+use crate::GroupingKey;
+
+/// Structured information parsed from a single stack trace line.
+///
+/// Each stack trace format only includes some of this information, so every
+/// field is optional.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Frame {
+    /// Index of the frame within the stack trace, e.g. `3` for `#3`.
+    pub index: Option<u32>,
+    /// Full symbol of the frame, e.g. `a::b::Class.method_one`.
+    pub symbol: Option<String>,
+    /// Segments of the symbol before the function name, e.g. `["a", "b",
+    /// "Class"]`.
+    pub module_path: Vec<String>,
+    /// Name of the function, e.g. `method_one`.
+    pub function: Option<String>,
+    /// Arguments of the function, e.g. `String id` for `Load(String id)`.
+    pub arguments: Option<String>,
+    /// Number of arguments of the function, e.g. `3` for
+    /// `MyApp.Worker.handle_call/3`.
+    pub arity: Option<u32>,
+    /// Shared library, executable, application, or kernel module that contains
+    /// the function, e.g. `libc.so.6`, or `my_app 0.1.0` for Elixir frames.
+    pub library: Option<String>,
+    /// Path to the source file, e.g. `src/main.rs`.
+    pub file_path: Option<String>,
+    /// Line number within the source file.
+    pub line: Option<u32>,
+    /// Column number within the source line.
+    pub column: Option<u32>,
+    /// Instruction address of the frame, e.g. `0x6bb546`.
+    pub address: Option<u64>,
+    /// Offset of the instruction from the start of the function, e.g. `0x1d`
+    /// for `+0x1d`.
+    pub offset: Option<u64>,
+    /// Size of the function, e.g. `0x40` for `my_init+0x1a/0x40`.
+    pub size: Option<u64>,
+    /// Whether the frame is reliable, e.g. `Some(false)` for kernel frames
+    /// prefixed with `? `.
+    ///
+    /// This is `None` for formats that don't mark unreliable frames.
+    pub reliable: Option<bool>,
+    /// Source code at the frame's line, when the stack trace includes it.
+    pub source: Option<String>,
+}
+
+impl Frame {
+    pub fn index(&self) -> Option<u32> {
+        self.index
+    }
+
+    pub fn symbol(&self) -> Option<&str> {
+        self.symbol.as_deref()
+    }
+
+    pub fn module_path(&self) -> &[String] {
+        &self.module_path
+    }
+
+    pub fn function(&self) -> Option<&str> {
+        self.function.as_deref()
+    }
+
+    pub fn arguments(&self) -> Option<&str> {
+        self.arguments.as_deref()
+    }
+
+    pub fn arity(&self) -> Option<u32> {
+        self.arity
+    }
+
+    pub fn library(&self) -> Option<&str> {
+        self.library.as_deref()
+    }
+
+    pub fn file_path(&self) -> Option<&str> {
+        self.file_path.as_deref()
+    }
+
+    pub fn line(&self) -> Option<u32> {
+        self.line
+    }
+
+    pub fn column(&self) -> Option<u32> {
+        self.column
+    }
+
+    pub fn address(&self) -> Option<u64> {
+        self.address
+    }
+
+    pub fn offset(&self) -> Option<u64> {
+        self.offset
+    }
+
+    pub fn size(&self) -> Option<u64> {
+        self.size
+    }
+
+    pub fn reliable(&self) -> Option<bool> {
+        self.reliable
+    }
+
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Returns the file path, module path, and function of this frame, for
+    /// formats that group frames by file and class.
+    ///
+    /// The file path is compared as a whole, so frames in different files are
+    /// not grouped because their paths share a directory.
+    pub(crate) fn file_and_module_key(&self) -> GroupingKey<'static> {
+        let file_path = self.file_path().unwrap_or_default().to_string();
+        let module_path = self.module_path().join("::");
+        let function = self.function().unwrap_or_default();
+
+        GroupingKey::new([file_path], format!("{module_path} {function}"))
+    }
+
+    /// Returns whether the line holds the location of the previous line's
+    /// frame, e.g. `      at src/main.rs:12`.
+    pub fn is_continuation_line(line: &str) -> bool {
+        line.trim_start()
+            .strip_prefix("at ")
+            .and_then(Self::parse_location)
+            .is_some()
+    }
+
+    /// Returns whether the line holds the location of the previous line's Rust
+    /// frame, e.g. `      at src/main.rs:12` after `app::main`.
+    ///
+    /// This is stricter than [`Frame::is_continuation_line`], for stack traces
+    /// whose format is not known, where `    at /app/a.js:1:2` is a V8 frame
+    /// of its own.
+    pub(crate) fn is_rust_continuation_line(frame_line: &str, line: &str) -> bool {
+        let (_slice_frame_index, symbol) = Self::split_index_prefix(frame_line);
+        let symbol = symbol.trim();
+        let is_rust_symbol = symbol.contains("::") && !symbol.starts_with("at ");
+        let is_rust_location = line
+            .trim_start()
+            .strip_prefix("at ")
+            .and_then(Self::parse_location)
+            .is_some_and(|(file_path, _line, _column)| file_path.ends_with(".rs"));
+
+        is_rust_symbol && is_rust_location
+    }
+
+    /// Returns this frame with the location filled in from a continuation
+    /// line.
+    ///
+    /// Information already present on this frame is kept.
+    pub fn with_continuation_line(mut self, continuation_line: &str) -> Self {
+        let Frame {
+            file_path,
+            line,
+            column,
+            ..
+        } = Frame::from(continuation_line);
+
+        if self.file_path.is_none() {
+            self.file_path = file_path;
+            self.line = line;
+            self.column = column;
+        }
+
+        self
+    }
+
+    /// Splits a line into its frame index prefix and the rest of the line.
+    ///
+    /// The following prefixes are recognized, with any leading whitespace:
+    ///
+    /// * `N: `: Rust backtraces.
+    /// * `#N `: gdb backtraces.
+    /// * `frame #N: `: lldb backtraces, optionally preceded by `* `.
+    ///
+    /// If there is no frame index, the prefix is empty.
+    pub fn split_index_prefix(line: &str) -> (&str, &str) {
+        match Self::parse_index_prefix(line) {
+            Some((prefix_len, _index)) => line.split_at(prefix_len),
+            None => ("", line),
+        }
+    }
+
+    /// Returns the length of the frame index prefix, and the index.
+    pub(crate) fn parse_index_prefix(line: &str) -> Option<(usize, u32)> {
+        let rest = line.trim_start();
+        let rest = rest.strip_prefix("* ").unwrap_or(rest);
+        let (rest, requires_colon) = if let Some(rest) = rest.strip_prefix("frame #") {
+            (rest, true)
+        } else if let Some(rest) = rest.strip_prefix('#') {
+            (rest, false)
+        } else {
+            (rest, true)
+        };
+
+        let digits_len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let index = rest[..digits_len].parse::<u32>().ok()?;
+        let rest = &rest[digits_len..];
+        let rest = if requires_colon {
+            rest.strip_prefix(':')?
+        } else {
+            rest
+        };
+
+        // The index must be followed by whitespace, so that we don't treat
+        // `12:34:56` as a frame index.
+        let rest_trimmed = rest.trim_start();
+        if rest_trimmed.len() == rest.len() && !rest.is_empty() {
+            return None;
+        }
+
+        Some((line.len() - rest_trimmed.len(), index))
+    }
+
+    /// Returns the symbol and location parts of a frame line.
+    ///
+    /// The following forms are recognized:
+    ///
+    /// * `symbol@location`: SpiderMonkey / wasm frames.
+    /// * `symbol at location`: native frames.
+    /// * `symbol(location)`: JVM frames.
+    fn split_symbol_and_location(line: &str) -> (&str, Option<&str>) {
+        if let Some((symbol, location)) = line.rsplit_once('@') {
+            return (symbol, Some(location));
+        }
+        if let Some((symbol, location)) = line.rsplit_once(" at ") {
+            return (symbol, Some(location));
+        }
+        if let Some(line_without_paren) = line.strip_suffix(')') {
+            if let Some((symbol, location)) = line_without_paren.rsplit_once('(') {
+                return (symbol, Some(location));
+            }
+        }
+
+        // The whole line may be a location, e.g. `src/main.rs:12`.
+        match Self::parse_location(line) {
+            Some(_) => ("", Some(line)),
+            None => (line, None),
+        }
+    }
+
+    /// Parses `path:line` or `path:line:column` into its parts.
+    pub(crate) fn parse_location(location: &str) -> Option<(&str, u32, Option<u32>)> {
+        let location = location.trim();
+        let (rest, last) = location.rsplit_once(':')?;
+        let last = last.parse::<u32>().ok()?;
+
+        let (file_path, line, column) = match rest
+            .rsplit_once(':')
+            .and_then(|(file_path, line)| Some((file_path, line.parse::<u32>().ok()?)))
+        {
+            Some((file_path, line)) => (file_path, line, Some(last)),
+            None => (rest, last, None),
+        };
+
+        if file_path.is_empty() {
+            None
+        } else {
+            Some((file_path, line, column))
+        }
+    }
+
+    /// Returns the first `0x`-prefixed hexadecimal number in the line.
+    pub(crate) fn parse_address(line: &str) -> Option<u64> {
+        line.split(|c: char| !c.is_ascii_alphanumeric())
+            .filter_map(|token| token.strip_prefix("0x"))
+            .find_map(|hex| u64::from_str_radix(hex, 16).ok())
+    }
+
+    /// Splits a symbol into segments on `::` and `.`, ignoring separators
+    /// within brackets, e.g. `<a::B as c::D>::fmt`.
+    pub(crate) fn split_symbol(symbol: &str) -> Vec<&str> {
+        let mut segments = Vec::new();
+        let mut depth = 0usize;
+        let mut segment_start = 0;
+        let mut char_indices = symbol.char_indices().peekable();
+
+        while let Some((index, c)) = char_indices.next() {
+            match c {
+                '<' | '(' | '[' | '{' => depth += 1,
+                '>' | ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                '.' if depth == 0 => {
+                    segments.push(&symbol[segment_start..index]);
+                    segment_start = index + 1;
+                }
+                ':' if depth == 0 && matches!(char_indices.peek(), Some((_, ':'))) => {
+                    char_indices.next();
+                    segments.push(&symbol[segment_start..index]);
+                    segment_start = index + 2;
+                }
+                _ => {}
+            }
+        }
+        segments.push(&symbol[segment_start..]);
+
+        segments.retain(|segment| !segment.is_empty());
+        segments
+    }
+}
+
+impl<'s> From<&'s str> for Frame {
+    fn from(line: &'s str) -> Self {
+        let index = Self::parse_index_prefix(line).map(|(_prefix_len, index)| index);
+        let (_slice_frame_index, line) = Self::split_index_prefix(line);
+        let line = line.trim();
+        let line = line.strip_prefix("at ").unwrap_or(line);
+
+        let address = Self::parse_address(line);
+        let (symbol, location) = Self::split_symbol_and_location(line);
+        let symbol = symbol.trim();
+        let (file_path, line, column) = match location.and_then(Self::parse_location) {
+            Some((file_path, line, column)) => (Some(file_path.to_string()), Some(line), column),
+            None => (None, None, None),
+        };
+
+        let mut segments = Self::split_symbol(symbol);
+        let function = segments.pop().map(str::to_string);
+        let module_path = segments.into_iter().map(str::to_string).collect();
+        let symbol = (!symbol.is_empty()).then(|| symbol.to_string());
+
+        Self {
+            index,
+            symbol,
+            module_path,
+            function,
+            arguments: None,
+            arity: None,
+            library: None,
+            file_path,
+            line,
+            column,
+            address,
+            offset: None,
+            size: None,
+            reliable: None,
+            source: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Frame;
+
+    #[test]
+    fn parses_rust_symbol() {
+        let frame = Frame::from(
+            "<std::sys_common::backtrace::_print::DisplayBacktrace as core::fmt::Display>::fmt",
+        );
+
+        assert_eq!(
+            Frame {
+                index: None,
+                symbol: Some(String::from(
+                    "<std::sys_common::backtrace::_print::DisplayBacktrace as core::fmt::Display>::fmt"
+                )),
+                module_path: vec![String::from(
+                    "<std::sys_common::backtrace::_print::DisplayBacktrace as core::fmt::Display>"
+                )],
+                function: Some(String::from("fmt")),
+                arguments: None,
+                arity: None,
+                library: None,
+                file_path: None,
+                line: None,
+                column: None,
+                address: None,
+                offset: None,
+                size: None,
+                reliable: None,
+                source: None,
+            },
+            frame
+        );
+    }
+
+    #[test]
+    fn parses_java_frame() {
+        let frame = Frame::from("        at com.example.adder.app.App.run(App.java:21)");
+
+        assert_eq!(
+            Frame {
+                index: None,
+                symbol: Some(String::from("com.example.adder.app.App.run")),
+                module_path: vec![
+                    String::from("com"),
+                    String::from("example"),
+                    String::from("adder"),
+                    String::from("app"),
+                    String::from("App"),
+                ],
+                function: Some(String::from("run")),
+                arguments: None,
+                arity: None,
+                library: None,
+                file_path: Some(String::from("App.java")),
+                line: Some(21),
+                column: None,
+                address: None,
+                offset: None,
+                size: None,
+                reliable: None,
+                source: None,
+            },
+            frame
+        );
+    }
+
+    #[test]
+    fn parses_wasm_frame() {
+        let frame = Frame::from(
+            "dot_ix_playground.wasm.console_error_panic_hook::Error::new::h8adb78d6eba1ab93@http://127.0.0.1:7890/pkg/dot_ix.wasm:wasm-function[16925]:0x636d40",
+        );
+
+        assert_eq!(
+            Some("dot_ix_playground.wasm.console_error_panic_hook::Error::new::h8adb78d6eba1ab93"),
+            frame.symbol()
+        );
+        assert_eq!(Some("h8adb78d6eba1ab93"), frame.function());
+        assert_eq!(None, frame.file_path());
+        assert_eq!(Some(0x636d40), frame.address());
+    }
+
+    #[test]
+    fn parses_url_location_with_column() {
+        let frame = Frame::from(
+            "__wbg_get_imports/imports.wbg.__wbg_new_abda76e883b18a5f@http://127.0.0.1:7890/pkg/dot_ix.js:489:13",
+        );
+
+        assert_eq!(
+            Some("http://127.0.0.1:7890/pkg/dot_ix.js"),
+            frame.file_path()
+        );
+        assert_eq!(Some(489), frame.line());
+        assert_eq!(Some(13), frame.column());
+    }
+
+    #[test]
+    fn parses_frame_index_prefixes() {
+        [
+            ("   3: core::fmt::write", "   3: ", 3),
+            ("#12 0x00005555 in main ()", "#12 ", 12),
+            ("frame #4: 0x0000 a.out`main", "frame #4: ", 4),
+            ("  * frame #0: 0x0000 a.out`main", "  * frame #0: ", 0),
+        ]
+        .into_iter()
+        .for_each(|(line, slice_frame_index_expected, index_expected)| {
+            let (slice_frame_index, _rest) = Frame::split_index_prefix(line);
+
+            assert_eq!(slice_frame_index_expected, slice_frame_index);
+            assert_eq!(Some(index_expected), Frame::from(line).index());
+        });
+    }
+
+    #[test]
+    fn does_not_parse_timestamp_as_frame_index() {
+        let (slice_frame_index, rest) = Frame::split_index_prefix("12:34:56 ERROR");
+
+        assert_eq!("", slice_frame_index);
+        assert_eq!("12:34:56 ERROR", rest);
+    }
+
+    #[test]
+    fn fills_location_from_continuation_line() {
+        let line = "   4: core::fmt::write";
+        let continuation_line = "             at src/libcore/fmt/mod.rs:1030";
+
+        assert!(!Frame::is_continuation_line(line));
+        assert!(Frame::is_continuation_line(continuation_line));
+        assert!(!Frame::is_continuation_line(
+            "        at com.example.adder.app.App.run(App.java:21)"
+        ));
+
+        let frame = Frame::from(line).with_continuation_line(continuation_line);
+        assert_eq!(Some(4), frame.index());
+        assert_eq!(Some("core::fmt::write"), frame.symbol());
+        assert_eq!(Some("src/libcore/fmt/mod.rs"), frame.file_path());
+        assert_eq!(Some(1030), frame.line());
+    }
+
+    #[test]
+    fn parses_location_only_line() {
+        let frame = Frame::from("src/libstd/panicking.rs:196");
+
+        assert_eq!(None, frame.symbol());
+        assert_eq!(Some("src/libstd/panicking.rs"), frame.file_path());
+        assert_eq!(Some(196), frame.line());
+    }
+}
@@ -0,0 +1,337 @@
+use crate::{Format, Frame, Stacktrace};
+
+const GOROUTINE_PREFIX: &str = "goroutine ";
+const CREATED_BY_PREFIX: &str = "created by ";
+const PANIC_PREFIXES: [&str; 2] = ["panic: ", "fatal error: "];
+
+/// A Go panic or goroutine dump, with the stack of each goroutine.
+///
+/// ```text
+/// panic: runtime error: index out of range [5] with length 3
+///
+/// goroutine 1 [running]:
+/// main.process(...)
+///         /home/user/app/main.go:12
+/// main.main()
+///         /home/user/app/main.go:8 +0x1d
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GoroutineDump {
+    /// Message of the panic, e.g. `runtime error: index out of range [5] with
+    /// length 3`.
+    pub panic_message: Option<String>,
+    /// Each goroutine in the dump.
+    pub goroutines: Vec<Goroutine>,
+}
+
+/// A goroutine within a `GoroutineDump`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Goroutine {
+    /// ID of the goroutine, e.g. `1` for `goroutine 1 [running]:`.
+    pub id: u64,
+    /// State of the goroutine, e.g. `running` or `chan receive`.
+    pub state: String,
+    /// Additional details after the state, e.g. `5 minutes` or `locked to
+    /// thread`.
+    pub state_details: Vec<String>,
+    /// Frames of the goroutine.
+    pub stacktrace: Stacktrace,
+    /// The function that started this goroutine, from `created by ..`.
+    pub created_by: Option<Frame>,
+}
+
+impl GoroutineDump {
+    /// Parses the goroutines in the given string.
+    ///
+    /// Returns `None` if there are no `goroutine N [state]:` lines.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut lines = s.lines().peekable();
+        let mut panic_message = None;
+        let mut goroutines = Vec::new();
+
+        while let Some(line) = lines.next() {
+            if let Some(message) = Self::parse_panic_message(line) {
+                panic_message.get_or_insert_with(|| message.to_string());
+                continue;
+            }
+
+            let Some((id, state, state_details)) = Self::parse_header(line) else {
+                continue;
+            };
+
+            let mut frame_lines = Vec::new();
+            let mut created_by = None;
+            while let Some(frame_line) = lines.next_if(|line| {
+                Self::parse_frame(line).is_some() || Self::is_continuation_line(line)
+            }) {
+                match frame_line.trim().strip_prefix(CREATED_BY_PREFIX) {
+                    Some(_) => {
+                        let frame = Self::parse_frame(frame_line).unwrap_or_default();
+                        let frame = match lines.next_if(|line| Self::is_continuation_line(line)) {
+                            Some(location_line) => {
+                                Self::frame_with_location_line(frame, location_line)
+                            }
+                            None => frame,
+                        };
+                        created_by = Some(frame);
+                    }
+                    None => frame_lines.push(frame_line),
+                }
+            }
+
+            goroutines.push(Goroutine {
+                id,
+                state: state.to_string(),
+                state_details: state_details.into_iter().map(str::to_string).collect(),
+                stacktrace: Stacktrace::from_lines(s, frame_lines, Format::Go),
+                created_by,
+            });
+        }
+
+        (!goroutines.is_empty()).then_some(Self {
+            panic_message,
+            goroutines,
+        })
+    }
+
+    pub fn panic_message(&self) -> Option<&str> {
+        self.panic_message.as_deref()
+    }
+
+    pub fn goroutines(&self) -> &[Goroutine] {
+        &self.goroutines
+    }
+
+    /// Returns whether the line begins a goroutine, e.g. `goroutine 1
+    /// [running]:`, or is the panic message.
+    pub(crate) fn is_header_line(line: &str) -> bool {
+        Self::parse_header(line).is_some() || Self::parse_panic_message(line).is_some()
+    }
+
+    /// Returns whether the line is the location of the previous frame, e.g.
+    /// `\t/home/user/app/main.go:8 +0x1d`.
+    pub(crate) fn is_continuation_line(line: &str) -> bool {
+        line.starts_with(char::is_whitespace) && Self::parse_location_line(line).is_some()
+    }
+
+    /// Parses a Go function line, e.g. `net/http.(*conn).serve(0xc000118000)`
+    /// or `created by main.main in goroutine 1`, into a `Frame`.
+    pub(crate) fn parse_frame(line: &str) -> Option<Frame> {
+        let line = line.trim();
+        let symbol = match line.strip_prefix(CREATED_BY_PREFIX) {
+            Some(creator) => match creator.split_once(" in goroutine ") {
+                Some((symbol, _goroutine_id)) => symbol,
+                None => creator,
+            },
+            None => {
+                let (symbol, arguments) = line.strip_suffix(')')?.rsplit_once('(')?;
+                let is_go_arguments = arguments.split(", ").all(|argument| {
+                    argument.is_empty()
+                        || argument == "..."
+                        || argument.starts_with("0x")
+                        || argument.starts_with('{')
+                        || argument.ends_with('}')
+                });
+                if !is_go_arguments {
+                    return None;
+                }
+                symbol
+            }
+        };
+
+        // The package path may contain `.`s, e.g. `gopkg.in/yaml.v3`, so we only split
+        // the symbol after the last `/`.
+        let (package_dir, package_and_function) = match symbol.rsplit_once('/') {
+            Some((package_dir, package_and_function)) => (Some(package_dir), package_and_function),
+            None => (None, symbol),
+        };
+        let mut segments = Frame::split_symbol(package_and_function)
+            .into_iter()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        let function = segments.pop();
+        if let (Some(package_dir), Some(package_name)) = (package_dir, segments.first_mut()) {
+            *package_name = format!("{package_dir}/{package_name}");
+        }
+
+        Some(Frame {
+            symbol: Some(symbol.to_string()),
+            module_path: segments,
+            function,
+            ..Frame::default()
+        })
+    }
+
+    /// Returns the frame with its location filled in from a location line,
+    /// e.g. `\t/home/user/app/main.go:8 +0x1d`.
+    pub(crate) fn frame_with_location_line(mut frame: Frame, location_line: &str) -> Frame {
+        if let Some((file_path, line, offset)) = Self::parse_location_line(location_line) {
+            frame.file_path = Some(file_path.to_string());
+            frame.line = Some(line);
+            frame.offset = offset;
+        }
+
+        frame
+    }
+
+    /// Returns the file path, line, and program counter offset of a location
+    /// line.
+    fn parse_location_line(line: &str) -> Option<(&str, u32, Option<u64>)> {
+        let line = line.trim();
+        let (location, offset) = match line.rsplit_once(" +0x") {
+            Some((location, offset)) => (location, u64::from_str_radix(offset, 16).ok()),
+            None => (line, None),
+        };
+        let (file_path, line, _column) = Frame::parse_location(location)?;
+
+        (!file_path.contains(char::is_whitespace)).then_some((file_path, line, offset))
+    }
+
+    /// Returns the ID, state, and state details from `goroutine 18 [chan
+    /// receive, 5 minutes]:`.
+    fn parse_header(line: &str) -> Option<(u64, &str, Vec<&str>)> {
+        let rest = line.trim().strip_prefix(GOROUTINE_PREFIX)?;
+        let (id, rest) = rest.split_once(" [")?;
+        let state_and_details = rest.strip_suffix("]:")?;
+        let id = id.parse::<u64>().ok()?;
+
+        let mut state_and_details = state_and_details.split(", ");
+        let state = state_and_details.next().unwrap_or_default();
+
+        Some((id, state, state_and_details.collect()))
+    }
+
+    fn parse_panic_message(line: &str) -> Option<&str> {
+        let line = line.trim();
+        PANIC_PREFIXES
+            .iter()
+            .find_map(|prefix| line.strip_prefix(prefix))
+    }
+}
+
+impl Goroutine {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn state(&self) -> &str {
+        &self.state
+    }
+
+    pub fn state_details(&self) -> &[String] {
+        &self.state_details
+    }
+
+    pub fn stacktrace(&self) -> &Stacktrace {
+        &self.stacktrace
+    }
+
+    pub fn created_by(&self) -> Option<&Frame> {
+        self.created_by.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Frame, Stacktrace};
+
+    use super::{Goroutine, GoroutineDump};
+
+    #[test]
+    fn parses_panic() {
+        let s = "\
+            panic: runtime error: index out of range [5] with length 3\n\
+            \n\
+            goroutine 1 [running]:\n\
+            main.process(...)\n\
+            \t/home/user/app/main.go:12\n\
+            main.main()\n\
+            \t/home/user/app/main.go:8 +0x1d\n\
+            exit status 2\n\
+            ";
+        let goroutine_dump = GoroutineDump::parse(s);
+
+        let goroutine_dump_expected = GoroutineDump {
+            panic_message: Some(String::from(
+                "runtime error: index out of range [5] with length 3",
+            )),
+            goroutines: vec![Goroutine {
+                id: 1,
+                state: String::from("running"),
+                state_details: Vec::new(),
+                stacktrace: Stacktrace::from_substring(
+                    s,
+                    "\
+                    main.process(...)\n\
+                    \t/home/user/app/main.go:12\n\
+                    main.main()\n\
+                    \t/home/user/app/main.go:8 +0x1d\n\
+                    ",
+                ),
+                created_by: None,
+            }],
+        };
+        assert_eq!(Some(&goroutine_dump_expected), goroutine_dump.as_ref());
+
+        let section = &goroutine_dump_expected.goroutines[0].stacktrace.sections[0];
+        let frame_expected = Frame {
+            symbol: Some(String::from("main.process")),
+            module_path: vec![String::from("main")],
+            function: Some(String::from("process")),
+            file_path: Some(String::from("/home/user/app/main.go")),
+            line: Some(12),
+            ..Frame::default()
+        };
+        assert_eq!(&frame_expected, section.frame());
+    }
+
+    #[test]
+    fn parses_goroutine_dump() {
+        let goroutine_dump = GoroutineDump::parse(
+            "\
+            goroutine 18 [chan receive, 5 minutes]:\n\
+            net/http.(*conn).serve(0xc000118000, {0x7a1e20, 0xc0000a8000})\n\
+            \t/usr/local/go/src/net/http/server.go:1995 +0x612\n\
+            created by net/http.(*Server).Serve in goroutine 1\n\
+            \t/usr/local/go/src/net/http/server.go:3089 +0x5ed\n\
+            \n\
+            goroutine 7 [select, locked to thread]:\n\
+            runtime.gopark(0x0?, 0x0?, 0x0?, 0x0?, 0x0?)\n\
+            \t/usr/local/go/src/runtime/proc.go:398 +0xce\n\
+            ",
+        )
+        .expect("Expected goroutines to be parsed.");
+
+        let [goroutine_18, goroutine_7] = goroutine_dump.goroutines() else {
+            panic!("Expected two goroutines.");
+        };
+
+        assert_eq!(18, goroutine_18.id());
+        assert_eq!("chan receive", goroutine_18.state());
+        assert_eq!([String::from("5 minutes")], goroutine_18.state_details());
+        assert_eq!(1, goroutine_18.stacktrace().sections.len());
+
+        let created_by_expected = Frame {
+            symbol: Some(String::from("net/http.(*Server).Serve")),
+            module_path: vec![String::from("net/http"), String::from("(*Server)")],
+            function: Some(String::from("Serve")),
+            file_path: Some(String::from("/usr/local/go/src/net/http/server.go")),
+            line: Some(3089),
+            offset: Some(0x5ed),
+            ..Frame::default()
+        };
+        assert_eq!(Some(&created_by_expected), goroutine_18.created_by());
+
+        assert_eq!(7, goroutine_7.id());
+        assert_eq!("select", goroutine_7.state());
+        assert_eq!(None, goroutine_7.created_by());
+    }
+
+    #[test]
+    fn does_not_parse_jvm_frame_as_go_frame() {
+        assert_eq!(
+            None,
+            GoroutineDump::parse_frame("com.example.App.run(App.java:21)")
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use std::borrow::Cow;
+
+/// Key that a frame line is compared with other frame lines by.
+///
+/// Whole segments, such as a script URL or file path, are only in common with
+/// another key's when they are equal, so frames from different files are not
+/// grouped because their paths share a directory. The prefix segment, such as
+/// the symbol, is compared by its leading characters, and only when every
+/// whole segment is in common.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct GroupingKey<'l> {
+    /// Segments that are compared as a whole, e.g. the script URL.
+    whole_segments: Vec<Cow<'l, str>>,
+    /// Segment that is compared by its leading characters, e.g. the symbol.
+    prefix_segment: Cow<'l, str>,
+}
+
+impl<'l> GroupingKey<'l> {
+    /// Returns a key with the given whole segments and prefix segment.
+    pub(crate) fn new<W, P>(whole_segments: W, prefix_segment: P) -> Self
+    where
+        W: IntoIterator,
+        W::Item: Into<Cow<'l, str>>,
+        P: Into<Cow<'l, str>>,
+    {
+        Self {
+            whole_segments: whole_segments.into_iter().map(Into::into).collect(),
+            prefix_segment: prefix_segment.into(),
+        }
+    }
+
+    /// Returns the length of the whole segments that are equal to the other
+    /// key's, and whether every whole segment is equal.
+    pub(crate) fn whole_segments_common_len(&self, other: &GroupingKey<'_>) -> (usize, bool) {
+        let common_len = self
+            .whole_segments
+            .iter()
+            .zip(other.whole_segments.iter())
+            .take_while(|(segment, other_segment)| segment == other_segment)
+            .map(|(segment, _other_segment)| segment.len())
+            .sum();
+        let is_all_common = self.whole_segments == other.whole_segments;
+
+        (common_len, is_all_common)
+    }
+
+    pub(crate) fn prefix_segment(&self) -> &str {
+        &self.prefix_segment
+    }
+}
+
+impl<'l> From<&'l str> for GroupingKey<'l> {
+    fn from(line: &'l str) -> Self {
+        Self::new(Vec::<&str>::new(), line)
+    }
+}
+
+impl From<String> for GroupingKey<'_> {
+    fn from(key: String) -> Self {
+        Self::new(Vec::<&str>::new(), key)
+    }
+}
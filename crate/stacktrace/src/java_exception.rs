@@ -0,0 +1,421 @@
+use std::iter::Peekable;
+
+use crate::{Format, Frame, Stacktrace};
+
+const EXCEPTION_IN_THREAD_PREFIX: &str = "Exception in thread \"";
+const CAUSED_BY_PREFIX: &str = "Caused by: ";
+const SUPPRESSED_PREFIX: &str = "Suppressed: ";
+
+/// A Java exception, along with its cause and suppressed exceptions.
+///
+/// ```text
+/// Exception in thread "main" java.lang.IllegalStateException: Failed to run
+///         at com.example.adder.app.App.run(App.java:21)
+///         at com.example.adder.app.App.main(App.java:14)
+/// Caused by: com.example.adder.AdderException
+///         at com.example.adder.Adder.add(Adder.java:13)
+///         ... 2 more
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JavaException {
+    /// Name of the thread the exception was thrown in, e.g. `main`.
+    pub thread_name: Option<String>,
+    /// Fully qualified class name of the exception, e.g.
+    /// `java.lang.IllegalStateException`.
+    pub class_name: String,
+    /// Message of the exception, e.g. `Failed to run`.
+    pub message: Option<String>,
+    /// Frames of this exception.
+    pub stacktrace: Stacktrace,
+    /// Number of frames in common with the enclosing exception, from `... N
+    /// more`.
+    pub elided_frame_count: Option<u32>,
+    /// Exceptions suppressed by this exception, from `Suppressed: `.
+    pub suppressed: Vec<JavaException>,
+    /// Exception that caused this exception, from `Caused by: `.
+    pub cause: Option<Box<JavaException>>,
+}
+
+impl JavaException {
+    /// Parses the first Java exception in the given string.
+    ///
+    /// Lines before the first exception header are skipped. Returns `None` if
+    /// there is no exception header.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut lines = s
+            .lines()
+            .skip_while(|line| !Self::is_header_line(line))
+            .peekable();
+
+        Self::parse_exception(s, &mut lines)
+    }
+
+    pub fn thread_name(&self) -> Option<&str> {
+        self.thread_name.as_deref()
+    }
+
+    pub fn class_name(&self) -> &str {
+        &self.class_name
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    pub fn stacktrace(&self) -> &Stacktrace {
+        &self.stacktrace
+    }
+
+    pub fn elided_frame_count(&self) -> Option<u32> {
+        self.elided_frame_count
+    }
+
+    pub fn suppressed(&self) -> &[JavaException] {
+        &self.suppressed
+    }
+
+    pub fn cause(&self) -> Option<&JavaException> {
+        self.cause.as_deref()
+    }
+
+    /// Returns an iterator over this exception and each of its causes.
+    pub fn chain(&self) -> impl Iterator<Item = &JavaException> {
+        std::iter::successors(Some(self), |exception| exception.cause())
+    }
+
+    /// Returns whether the line begins a Java exception.
+    ///
+    /// This is one of:
+    ///
+    /// * `Exception in thread "main" ..`
+    /// * `Caused by: ..`
+    /// * `Suppressed: ..`
+    /// * An unindented exception class name, e.g. `java.lang.Exception: msg`.
+    pub(crate) fn is_header_line(line: &str) -> bool {
+        let line_trimmed = line.trim_start();
+        if line_trimmed.starts_with(EXCEPTION_IN_THREAD_PREFIX)
+            || line_trimmed.starts_with(CAUSED_BY_PREFIX)
+            || line_trimmed.starts_with(SUPPRESSED_PREFIX)
+        {
+            return true;
+        }
+
+        line_trimmed.len() == line.len()
+            && Self::parse_header(line).is_some_and(|(_thread_name, class_name, _message)| {
+                let simple_name = class_name.rsplit('.').next().unwrap_or(class_name);
+                ["Exception", "Error", "Throwable"]
+                    .iter()
+                    .any(|suffix| simple_name.ends_with(suffix))
+            })
+    }
+
+    /// Returns whether the line notes frames that were elided, e.g. `... 2
+    /// more`.
+    pub(crate) fn is_continuation_line(line: &str) -> bool {
+        Self::parse_elided_frame_count(line).is_some()
+    }
+
+    /// Returns whether the line is a JVM frame, e.g. `at a.B.c(B.java:1)` or
+    /// `a.B.c(Native Method)`.
+    ///
+    /// This is stricter than the frames accepted within an exception, so that
+    /// frames from other languages are not mistaken for JVM frames.
+    pub(crate) fn is_stack_frame_line(line: &str) -> bool {
+        let line_trimmed = line.trim();
+        let line_trimmed = line_trimmed.strip_prefix("at ").unwrap_or(line_trimmed);
+        let Some((symbol, location)) = line_trimmed
+            .strip_suffix(')')
+            .and_then(|line_trimmed| line_trimmed.split_once('('))
+        else {
+            return false;
+        };
+
+        let is_location = matches!(location, "Native Method" | "Unknown Source")
+            || (location.starts_with(char::is_alphabetic)
+                && !location.contains(char::is_whitespace)
+                && Frame::parse_location(location).is_none_or(|(_, _, column)| column.is_none()));
+
+        !symbol.is_empty() && !symbol.contains(char::is_whitespace) && is_location
+    }
+
+    /// Parses the exception at the start of `lines`, which are lines of `s`.
+    fn parse_exception<'s, I>(s: &'s str, lines: &mut Peekable<I>) -> Option<Self>
+    where
+        I: Iterator<Item = &'s str>,
+    {
+        let header_line = lines.next()?;
+        let header_indent_len = Self::indent_len(header_line);
+        let (thread_name, class_name, message) = Self::parse_header(header_line)?;
+
+        let mut message = message.map(str::to_string);
+        let mut frame_lines = Vec::new();
+        let mut elided_frame_count = None;
+        let mut suppressed = Vec::new();
+        let mut cause = None;
+
+        while let Some(line) = lines.peek().copied() {
+            let indent_len = Self::indent_len(line);
+            let line_trimmed = line.trim();
+
+            if line_trimmed.starts_with(CAUSED_BY_PREFIX) {
+                // A `Caused by: ` with less indentation belongs to an enclosing exception.
+                if indent_len >= header_indent_len {
+                    cause = Self::parse_exception(s, lines).map(Box::new);
+                }
+                break;
+            } else if line_trimmed.starts_with(SUPPRESSED_PREFIX) {
+                if indent_len <= header_indent_len {
+                    break;
+                }
+                match Self::parse_exception(s, lines) {
+                    Some(suppressed_exception) => suppressed.push(suppressed_exception),
+                    None => break,
+                }
+            } else if line_trimmed.is_empty()
+                || line_trimmed.starts_with(EXCEPTION_IN_THREAD_PREFIX)
+            {
+                break;
+            } else if let Some(count) = Self::parse_elided_frame_count(line) {
+                elided_frame_count = Some(count);
+                lines.next();
+            } else if Self::is_frame_line(line_trimmed) {
+                frame_lines.push(line);
+                lines.next();
+            } else if frame_lines.is_empty() {
+                // Messages may span multiple lines.
+                let message = message.get_or_insert_with(String::new);
+                if !message.is_empty() {
+                    message.push('\n');
+                }
+                message.push_str(line);
+                lines.next();
+            } else {
+                break;
+            }
+        }
+
+        Some(Self {
+            thread_name: thread_name.map(str::to_string),
+            class_name: class_name.to_string(),
+            message,
+            stacktrace: Stacktrace::from_lines(s, frame_lines, Format::Java),
+            elided_frame_count,
+            suppressed,
+            cause,
+        })
+    }
+
+    /// Returns the thread name, class name, and message of an exception
+    /// header.
+    fn parse_header(line: &str) -> Option<(Option<&str>, &str, Option<&str>)> {
+        let line = line.trim();
+        let (thread_name, rest) = match line.strip_prefix(EXCEPTION_IN_THREAD_PREFIX) {
+            Some(rest) => {
+                let (thread_name, rest) = rest.split_once("\" ")?;
+                (Some(thread_name), rest)
+            }
+            None => {
+                let rest = line
+                    .strip_prefix(CAUSED_BY_PREFIX)
+                    .or_else(|| line.strip_prefix(SUPPRESSED_PREFIX))
+                    .unwrap_or(line);
+                (None, rest)
+            }
+        };
+
+        let (class_name, message) = match rest.split_once(": ") {
+            Some((class_name, message)) => (class_name, Some(message)),
+            None => (rest.trim_end_matches(':'), None),
+        };
+
+        let is_class_name = !class_name.is_empty()
+            && class_name
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | '.'));
+
+        is_class_name.then_some((thread_name, class_name, message))
+    }
+
+    /// Returns whether the line is a frame, e.g. `at a.B.c(B.java:1)` or
+    /// `a.B.c(Native Method)`.
+    fn is_frame_line(line_trimmed: &str) -> bool {
+        line_trimmed.starts_with("at ")
+            || (line_trimmed.ends_with(')') && line_trimmed.contains('('))
+    }
+
+    /// Returns `N` from `... N more` or `... N common frames omitted`.
+    fn parse_elided_frame_count(line: &str) -> Option<u32> {
+        let rest = line.trim().strip_prefix("... ")?;
+        let count = rest
+            .strip_suffix(" more")
+            .or_else(|| rest.strip_suffix(" common frames omitted"))?;
+
+        count.parse::<u32>().ok()
+    }
+
+    fn indent_len(line: &str) -> usize {
+        line.len() - line.trim_start().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LineEnding, Stacktrace};
+
+    use super::JavaException;
+
+    #[test]
+    fn parses_exception_with_cause() {
+        let s = "\
+            Exception in thread \"main\" java.lang.IllegalStateException: Failed to run\n\
+            \x20       at com.example.adder.app.App.run(App.java:21)\n\
+            \x20       at com.example.adder.app.App.main(App.java:14)\n\
+            Caused by: com.example.adder.AdderException\n\
+            \x20       at com.example.adder.Adder.add(Adder.java:13)\n\
+            \x20       ... 2 more\n\
+            ";
+        let java_exception = JavaException::parse(s);
+
+        let java_exception_expected = JavaException {
+            thread_name: Some(String::from("main")),
+            class_name: String::from("java.lang.IllegalStateException"),
+            message: Some(String::from("Failed to run")),
+            stacktrace: Stacktrace::from_substring(
+                s,
+                "\
+                \x20       at com.example.adder.app.App.run(App.java:21)\n\
+                \x20       at com.example.adder.app.App.main(App.java:14)\n\
+                ",
+            ),
+            elided_frame_count: None,
+            suppressed: Vec::new(),
+            cause: Some(Box::new(JavaException {
+                thread_name: None,
+                class_name: String::from("com.example.adder.AdderException"),
+                message: None,
+                stacktrace: Stacktrace::from_substring(
+                    s,
+                    "        at com.example.adder.Adder.add(Adder.java:13)\n",
+                ),
+                elided_frame_count: Some(2),
+                suppressed: Vec::new(),
+                cause: None,
+            })),
+        };
+        assert_eq!(Some(java_exception_expected), java_exception);
+    }
+
+    #[test]
+    fn parses_exception_with_suppressed_exceptions() {
+        let java_exception = JavaException::parse(
+            "\
+            java.lang.Exception: outer\n\
+            \tat Main.main(Main.java:5)\n\
+            \tSuppressed: java.lang.RuntimeException: close failed\n\
+            \t\tat Main$Resource.close(Main.java:12)\n\
+            \t\t... 1 more\n\
+            \tCaused by: java.io.IOException: disk full\n\
+            \t\tat Main$Resource.flush(Main.java:16)\n\
+            \t\t... 2 more\n\
+            Caused by: java.lang.IllegalArgumentException\n\
+            \tat Main.run(Main.java:9)\n\
+            \t... 1 more\n\
+            ",
+        )
+        .expect("Expected exception to be parsed.");
+
+        assert_eq!("java.lang.Exception", java_exception.class_name());
+        assert_eq!(Some("outer"), java_exception.message());
+        assert_eq!(1, java_exception.stacktrace().sections.len());
+
+        let [suppressed] = java_exception.suppressed() else {
+            panic!("Expected one suppressed exception.");
+        };
+        assert_eq!("java.lang.RuntimeException", suppressed.class_name());
+        assert_eq!(Some(1), suppressed.elided_frame_count());
+        assert_eq!(
+            Some("java.io.IOException"),
+            suppressed.cause().map(JavaException::class_name)
+        );
+
+        let class_names = java_exception
+            .chain()
+            .map(JavaException::class_name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec!["java.lang.Exception", "java.lang.IllegalArgumentException"],
+            class_names
+        );
+    }
+
+    #[test]
+    fn parses_multi_line_message() {
+        let java_exception = JavaException::parse(
+            "\
+            java.lang.IllegalArgumentException: first line\n\
+            second line\n\
+            \x20   com.example.stacktrace.Example.fail(Example.java:11)\n\
+            \x20   sun.reflect.NativeMethodAccessorImpl.invoke0(Native Method)\n\
+            ",
+        )
+        .expect("Expected exception to be parsed.");
+
+        assert_eq!(Some("first line\nsecond line"), java_exception.message());
+        assert_eq!(None, java_exception.thread_name());
+    }
+
+    #[test]
+    fn parses_multi_line_message_after_header_without_message() {
+        let java_exception = JavaException::parse(
+            "\
+            java.lang.IllegalArgumentException\n\
+            first line\n\
+            second line\n\
+            \x20   com.example.stacktrace.Example.fail(Example.java:11)\n\
+            ",
+        )
+        .expect("Expected exception to be parsed.");
+
+        assert_eq!(
+            "java.lang.IllegalArgumentException",
+            java_exception.class_name()
+        );
+        assert_eq!(Some("first line\nsecond line"), java_exception.message());
+    }
+
+    #[test]
+    fn source_spans_are_relative_to_parsed_string() {
+        let s = "\
+            java.lang.IllegalStateException: bad state\r\n\
+            \x20   at com.example.App.run(App.java:21)\r\n\
+            \x20   at com.example.App.main(App.java:14)";
+        let java_exception = JavaException::parse(s).expect("Expected exception to be parsed.");
+        let stacktrace = java_exception.stacktrace();
+
+        let [run] = stacktrace.sections.as_slice() else {
+            panic!("Expected one top level section.");
+        };
+        let run_start = s
+            .find("    at com.example.App.run")
+            .expect("Expected run frame.");
+        let main_start = s
+            .find("    at com.example.App.main")
+            .expect("Expected main frame.");
+        assert_eq!(2, run.source_span().line_number);
+        assert_eq!(run_start, run.source_span().lines.start);
+        assert_eq!(LineEnding::CrLf, run.line_trivia()[0].line_ending());
+        assert_eq!(Some(run.id()), stacktrace.section_id_at(run_start + 10));
+        assert_eq!(
+            stacktrace.sections[0].child_sections()[0].id(),
+            stacktrace
+                .section_id_at(main_start)
+                .expect("Expected main frame section.")
+        );
+        assert_eq!(None, stacktrace.section_id_at(0));
+    }
+
+    #[test]
+    fn returns_none_when_no_exception_header() {
+        assert_eq!(None, JavaException::parse("a::b::Class.method_one"));
+    }
+}
@@ -0,0 +1,429 @@
+use crate::{Format, Stacktrace};
+
+const THREAD_STATE_PREFIX: &str = "java.lang.Thread.State: ";
+const FULL_THREAD_DUMP_PREFIX: &str = "Full thread dump ";
+const LOCKED_OWNABLE_SYNCHRONIZERS_HEADER: &str = "Locked ownable synchronizers:";
+const LOCK_PREFIX: &str = "- ";
+const DAEMON: &str = "daemon";
+
+/// A Java thread dump printed by `jstack` or `kill -3`, with the stack of each
+/// thread.
+///
+/// ```text
+/// "main" #1 prio=5 os_prio=0 tid=0x00007f3c2800a000 nid=0x1a03 waiting on condition [0x00007f3c2f1fe000]
+///    java.lang.Thread.State: WAITING (parking)
+///         at jdk.internal.misc.Unsafe.park(Native Method)
+///         - parking to wait for  <0x000000071a6b8f88> (a java.util.concurrent.CountDownLatch$Sync)
+///         at com.example.App.main(App.java:14)
+///
+///    Locked ownable synchronizers:
+///         - None
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JavaThreadDump {
+    /// Each thread in the dump, in the order they are printed.
+    pub threads: Vec<JavaThread>,
+}
+
+/// A thread within a `JavaThreadDump`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JavaThread {
+    /// Name of the thread, e.g. `main`.
+    pub name: String,
+    /// Thread number, e.g. `1` for `#1`.
+    pub number: Option<u32>,
+    /// Whether the thread is a daemon thread.
+    pub daemon: bool,
+    /// Priority of the thread, e.g. `5` for `prio=5`.
+    pub priority: Option<u32>,
+    /// State of the thread, e.g. `WAITING (parking)`.
+    pub state: Option<String>,
+    /// Frames of the thread.
+    ///
+    /// Lock annotations are not included, so that threads with the same
+    /// frames have equal stacktraces.
+    pub stacktrace: Stacktrace,
+    /// Locks annotated on the thread's frames, e.g. `- locked <0x..> (a
+    /// java.lang.Object)`.
+    pub locks: Vec<JavaLock>,
+    /// Locks listed under `Locked ownable synchronizers:`.
+    pub locked_ownable_synchronizers: Vec<JavaLock>,
+}
+
+/// A lock annotation within a `JavaThread`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JavaLock {
+    /// What the thread is doing with the lock, e.g. `locked`, `waiting on`, or
+    /// `parking to wait for`.
+    pub action: String,
+    /// Address of the lock object, e.g. `0x000000071a6b8f88`.
+    pub address: Option<u64>,
+    /// Class of the lock object, e.g. `java.lang.Object`.
+    pub class_name: Option<String>,
+    /// Index of the frame that the annotation follows.
+    ///
+    /// This is `None` for locked ownable synchronizers.
+    pub frame_index: Option<usize>,
+}
+
+/// Threads within a `JavaThreadDump` that have the same state and frames.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JavaThreadGroup {
+    /// State of the threads, e.g. `WAITING (parking)`.
+    pub state: Option<String>,
+    /// Names of the threads in this group.
+    pub thread_names: Vec<String>,
+    /// Frames shared by the threads.
+    pub stacktrace: Stacktrace,
+}
+
+impl JavaThreadDump {
+    /// Parses the threads in the given string.
+    ///
+    /// Returns `None` if there are no thread headers, e.g. `"main" #1 prio=5
+    /// ..`.
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::parse_with_format(s, Format::JavaThreadDump)
+    }
+
+    /// Parses the threads in the given string, recognising frames in the given
+    /// format.
+    ///
+    /// Android ANR traces use the same thread blocks, but also have native
+    /// frames.
+    pub(crate) fn parse_with_format(s: &str, format: Format) -> Option<Self> {
+        let mut lines = s.lines().peekable();
+        let mut threads = Vec::new();
+
+        while let Some(line) = lines.next() {
+            let Some((name, number, daemon, priority)) = Self::parse_thread_header(line) else {
+                continue;
+            };
+
+            let mut state = None;
+            let mut frame_lines = Vec::new();
+            let mut locks = Vec::new();
+            let mut locked_ownable_synchronizers = Vec::new();
+            let mut is_in_ownable_synchronizers = false;
+            while let Some(line) = lines.next_if(|line| Self::parse_thread_header(line).is_none()) {
+                let line_trimmed = line.trim();
+
+                if let Some(line_state) = line_trimmed.strip_prefix(THREAD_STATE_PREFIX) {
+                    state = Some(line_state.to_string());
+                } else if line_trimmed == LOCKED_OWNABLE_SYNCHRONIZERS_HEADER {
+                    is_in_ownable_synchronizers = true;
+                } else if let Some(lock) = Self::parse_lock(line) {
+                    if is_in_ownable_synchronizers {
+                        locked_ownable_synchronizers.push(lock);
+                    } else {
+                        locks.push(JavaLock {
+                            frame_index: frame_lines.len().checked_sub(1),
+                            ..lock
+                        });
+                    }
+                } else if format.is_frame_line(line) {
+                    frame_lines.push(line);
+                }
+            }
+
+            threads.push(JavaThread {
+                name: name.to_string(),
+                number,
+                daemon,
+                priority,
+                state,
+                stacktrace: Stacktrace::from_lines(s, frame_lines, format),
+                locks,
+                locked_ownable_synchronizers,
+            });
+        }
+
+        (!threads.is_empty()).then_some(Self { threads })
+    }
+
+    pub fn threads(&self) -> &[JavaThread] {
+        &self.threads
+    }
+
+    /// Returns the threads grouped by their state and frames, with the largest
+    /// groups first.
+    ///
+    /// Groups with the same number of threads are in the order their first
+    /// thread is printed.
+    pub fn thread_groups(&self) -> Vec<JavaThreadGroup> {
+        let mut thread_groups: Vec<JavaThreadGroup> = Vec::new();
+
+        self.threads.iter().for_each(|thread| {
+            let thread_group = thread_groups.iter_mut().find(|thread_group| {
+                thread_group.state == thread.state
+                    && thread_group
+                        .stacktrace
+                        .eq_ignoring_source(&thread.stacktrace)
+            });
+            match thread_group {
+                Some(thread_group) => thread_group.thread_names.push(thread.name.clone()),
+                None => thread_groups.push(JavaThreadGroup {
+                    state: thread.state.clone(),
+                    thread_names: vec![thread.name.clone()],
+                    stacktrace: thread.stacktrace.clone(),
+                }),
+            }
+        });
+
+        thread_groups.sort_by_key(|thread_group| std::cmp::Reverse(thread_group.count()));
+        thread_groups
+    }
+
+    /// Returns whether the line begins a thread, e.g. `"main" #1 prio=5 ..`,
+    /// or the dump.
+    pub(crate) fn is_header_line(line: &str) -> bool {
+        let line_trimmed = line.trim();
+        Self::parse_thread_header(line).is_some()
+            || line_trimmed.starts_with(FULL_THREAD_DUMP_PREFIX)
+            || line_trimmed == LOCKED_OWNABLE_SYNCHRONIZERS_HEADER
+    }
+
+    /// Returns whether the line describes the thread header before it, e.g.
+    /// `   java.lang.Thread.State: WAITING (parking)`.
+    pub(crate) fn is_header_continuation_line(line: &str) -> bool {
+        line.trim().starts_with(THREAD_STATE_PREFIX) || Self::is_continuation_line(line)
+    }
+
+    /// Returns whether the line is a lock annotation, e.g. `- locked <0x..> (a
+    /// java.lang.Object)`, or `- None`.
+    pub(crate) fn is_continuation_line(line: &str) -> bool {
+        line.trim() == "- None" || Self::parse_lock(line).is_some()
+    }
+
+    /// Returns the name, number, whether the thread is a daemon, and priority
+    /// from `"main" #1 daemon prio=5 os_prio=0 tid=0x.. nid=0x.. runnable`.
+    fn parse_thread_header(line: &str) -> Option<(&str, Option<u32>, bool, Option<u32>)> {
+        let rest = line.strip_prefix('"')?;
+        let (name, rest) = rest.rsplit_once("\" ")?;
+
+        let mut number = None;
+        let mut daemon = false;
+        let mut priority = None;
+        let mut has_thread_ids = false;
+        rest.split_whitespace().for_each(|token| {
+            if let Some(token_number) = token.strip_prefix('#') {
+                number = token_number.parse::<u32>().ok();
+            } else if let Some(token_priority) = token.strip_prefix("prio=") {
+                priority = token_priority.parse::<u32>().ok();
+            } else if token == DAEMON {
+                daemon = true;
+            } else if token.starts_with("tid=") || token.starts_with("nid=") {
+                has_thread_ids = true;
+            }
+        });
+
+        (has_thread_ids || priority.is_some()).then_some((name, number, daemon, priority))
+    }
+
+    /// Parses `- locked <0x000000071a6b8f88> (a java.lang.Object)` into a
+    /// `JavaLock`.
+    fn parse_lock(line: &str) -> Option<JavaLock> {
+        let rest = line.trim().strip_prefix(LOCK_PREFIX)?;
+        let (action, rest) = rest.split_once('<')?;
+        let (object, rest) = rest.split_once('>')?;
+
+        let address = object
+            .strip_prefix("0x")
+            .and_then(|address| u64::from_str_radix(address, 16).ok());
+        let class_name = rest
+            .trim()
+            .strip_prefix("(a ")
+            .and_then(|class_name| class_name.strip_suffix(')'))
+            .map(str::to_string);
+        let action = match action.trim() {
+            "" => "locked",
+            action => action,
+        };
+
+        Some(JavaLock {
+            action: action.to_string(),
+            address,
+            class_name,
+            frame_index: None,
+        })
+    }
+}
+
+impl JavaThread {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn number(&self) -> Option<u32> {
+        self.number
+    }
+
+    pub fn daemon(&self) -> bool {
+        self.daemon
+    }
+
+    pub fn priority(&self) -> Option<u32> {
+        self.priority
+    }
+
+    pub fn state(&self) -> Option<&str> {
+        self.state.as_deref()
+    }
+
+    pub fn stacktrace(&self) -> &Stacktrace {
+        &self.stacktrace
+    }
+
+    pub fn locks(&self) -> &[JavaLock] {
+        &self.locks
+    }
+
+    pub fn locked_ownable_synchronizers(&self) -> &[JavaLock] {
+        &self.locked_ownable_synchronizers
+    }
+}
+
+impl JavaLock {
+    pub fn action(&self) -> &str {
+        &self.action
+    }
+
+    pub fn address(&self) -> Option<u64> {
+        self.address
+    }
+
+    pub fn class_name(&self) -> Option<&str> {
+        self.class_name.as_deref()
+    }
+
+    pub fn frame_index(&self) -> Option<usize> {
+        self.frame_index
+    }
+}
+
+impl JavaThreadGroup {
+    pub fn state(&self) -> Option<&str> {
+        self.state.as_deref()
+    }
+
+    pub fn thread_names(&self) -> &[String] {
+        &self.thread_names
+    }
+
+    pub fn stacktrace(&self) -> &Stacktrace {
+        &self.stacktrace
+    }
+
+    /// Returns the number of threads in this group.
+    pub fn count(&self) -> usize {
+        self.thread_names.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JavaLock, JavaThreadDump};
+
+    const THREAD_DUMP: &str = "\
+        Full thread dump OpenJDK 64-Bit Server VM (17.0.2+8 mixed mode):\n\
+        \n\
+        \"main\" #1 prio=5 os_prio=0 tid=0x00007f3c2800a000 nid=0x1a03 waiting on condition [0x00007f3c2f1fe000]\n\
+        \x20  java.lang.Thread.State: WAITING (parking)\n\
+        \tat jdk.internal.misc.Unsafe.park(Native Method)\n\
+        \t- parking to wait for  <0x000000071a6b8f88> (a java.util.concurrent.CountDownLatch$Sync)\n\
+        \tat com.example.App.main(App.java:14)\n\
+        \n\
+        \x20  Locked ownable synchronizers:\n\
+        \t- None\n\
+        \n\
+        \"worker-1\" #12 daemon prio=5 os_prio=0 tid=0x00007f3c28b1c000 nid=0x1a20 waiting for monitor entry [0x00007f3bf8dfe000]\n\
+        \x20  java.lang.Thread.State: BLOCKED (on object monitor)\n\
+        \tat com.example.Store.save(Store.java:42)\n\
+        \t- waiting to lock <0x000000071a6b9000> (a java.lang.Object)\n\
+        \tat com.example.Worker.run(Worker.java:20)\n\
+        \n\
+        \"worker-2\" #13 daemon prio=5 os_prio=0 tid=0x00007f3c28b1d800 nid=0x1a21 waiting for monitor entry [0x00007f3bf8cfd000]\n\
+        \x20  java.lang.Thread.State: BLOCKED (on object monitor)\n\
+        \tat com.example.Store.save(Store.java:42)\n\
+        \t- waiting to lock <0x000000071a6b9000> (a java.lang.Object)\n\
+        \tat com.example.Worker.run(Worker.java:20)\n\
+        \n\
+        \x20  Locked ownable synchronizers:\n\
+        \t- <0x000000071a6ba010> (a java.util.concurrent.locks.ReentrantLock$NonfairSync)\n\
+        \n\
+        \"VM Thread\" os_prio=0 tid=0x00007f3c28074800 nid=0x1a0a runnable\n\
+        ";
+
+    #[test]
+    fn parses_threads() {
+        let java_thread_dump =
+            JavaThreadDump::parse(THREAD_DUMP).expect("Expected thread dump to be parsed.");
+
+        let [main, worker_1, _worker_2, vm_thread] = java_thread_dump.threads() else {
+            panic!("Expected four threads.");
+        };
+        assert_eq!("main", main.name());
+        assert_eq!(Some(1), main.number());
+        assert!(!main.daemon());
+        assert_eq!(Some(5), main.priority());
+        assert_eq!(Some("WAITING (parking)"), main.state());
+        assert_eq!(
+            &[JavaLock {
+                action: String::from("parking to wait for"),
+                address: Some(0x71a6b8f88),
+                class_name: Some(String::from("java.util.concurrent.CountDownLatch$Sync")),
+                frame_index: Some(0),
+            }],
+            main.locks()
+        );
+        assert!(worker_1.daemon());
+        assert_eq!(Some(12), worker_1.number());
+        assert_eq!("VM Thread", vm_thread.name());
+        assert_eq!(None, vm_thread.state());
+        assert!(vm_thread.stacktrace().sections.is_empty());
+    }
+
+    #[test]
+    fn parses_locked_ownable_synchronizers() {
+        let java_thread_dump =
+            JavaThreadDump::parse(THREAD_DUMP).expect("Expected thread dump to be parsed.");
+
+        let worker_2 = &java_thread_dump.threads()[2];
+        assert_eq!(1, worker_2.locks().len());
+        assert_eq!(
+            &[JavaLock {
+                action: String::from("locked"),
+                address: Some(0x71a6ba010),
+                class_name: Some(String::from(
+                    "java.util.concurrent.locks.ReentrantLock$NonfairSync"
+                )),
+                frame_index: None,
+            }],
+            worker_2.locked_ownable_synchronizers()
+        );
+    }
+
+    #[test]
+    fn groups_threads_with_identical_stacks() {
+        let java_thread_dump =
+            JavaThreadDump::parse(THREAD_DUMP).expect("Expected thread dump to be parsed.");
+
+        let thread_groups = java_thread_dump.thread_groups();
+        let thread_group_summaries = thread_groups
+            .iter()
+            .map(|thread_group| (thread_group.count(), thread_group.state()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (2, Some("BLOCKED (on object monitor)")),
+                (1, Some("WAITING (parking)")),
+                (1, None),
+            ],
+            thread_group_summaries
+        );
+        assert_eq!(
+            &[String::from("worker-1"), String::from("worker-2")],
+            thread_groups[0].thread_names()
+        );
+    }
+}
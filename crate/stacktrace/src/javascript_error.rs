@@ -0,0 +1,400 @@
+use crate::{Format, Frame, GroupingKey, Stacktrace};
+
+/// A JavaScript error and its stack.
+///
+/// Both V8 (Chrome, Node.js) and SpiderMonkey (Firefox) stacks are supported:
+///
+/// ```text
+/// TypeError: Cannot read properties of undefined (reading 'id')
+///     at Object.handler (/app/src/routes/user.js:42:13)
+///     at next (/app/node_modules/express/lib/router/route.js:137:13)
+/// ```
+///
+/// ```text
+/// handler@http://localhost:8080/app.js:42:13
+/// @http://localhost:8080/app.js:50:1
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JavascriptError {
+    /// Name of the error, e.g. `TypeError`.
+    ///
+    /// SpiderMonkey stacks don't include the error, so this is `None` for
+    /// them.
+    pub name: Option<String>,
+    /// Message of the error.
+    pub message: Option<String>,
+    /// Which JavaScript engine produced the stack.
+    pub dialect: JavascriptDialect,
+    /// Frames of the error.
+    pub stacktrace: Stacktrace,
+}
+
+/// Which JavaScript engine produced a stack.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum JavascriptDialect {
+    /// Chrome / Node.js: `    at func (url:line:column)`.
+    #[default]
+    V8,
+    /// Firefox: `func@url:line:column`.
+    SpiderMonkey,
+}
+
+impl JavascriptError {
+    /// Parses the first JavaScript error in the given string.
+    ///
+    /// Returns `None` if there are no JavaScript frames.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut lines = s
+            .lines()
+            .skip_while(|line| !Self::is_header_line(line) && Self::line_dialect(line).is_none())
+            .peekable();
+
+        let (name, mut message) = match lines.next_if(|line| Self::is_header_line(line)) {
+            Some(header_line) => {
+                let (name, message) = Self::parse_header(header_line)?;
+                (Some(name.to_string()), message.map(str::to_string))
+            }
+            None => (None, None),
+        };
+
+        // Messages may span multiple lines.
+        while let Some(line) =
+            lines.next_if(|line| Self::line_dialect(line).is_none() && !line.trim().is_empty())
+        {
+            let message = message.get_or_insert_with(String::new);
+            if !message.is_empty() {
+                message.push('\n');
+            }
+            message.push_str(line);
+        }
+
+        let dialect = lines.peek().copied().and_then(Self::line_dialect)?;
+        let mut frame_lines = Vec::new();
+        while let Some(frame_line) = lines.next_if(|line| Self::line_dialect(line).is_some()) {
+            frame_lines.push(frame_line);
+        }
+
+        Some(Self {
+            name,
+            message,
+            dialect,
+            stacktrace: Stacktrace::from_lines(s, frame_lines, Format::Javascript),
+        })
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    pub fn dialect(&self) -> JavascriptDialect {
+        self.dialect
+    }
+
+    pub fn stacktrace(&self) -> &Stacktrace {
+        &self.stacktrace
+    }
+
+    /// Returns whether the line is an unindented error header, e.g.
+    /// `TypeError: x is undefined` or `Uncaught Error: failed`.
+    pub(crate) fn is_header_line(line: &str) -> bool {
+        !line.starts_with(char::is_whitespace) && Self::parse_header(line).is_some()
+    }
+
+    /// Returns whether the line is a V8 or SpiderMonkey frame.
+    pub(crate) fn is_frame_line(line: &str) -> bool {
+        Self::line_dialect(line).is_some()
+    }
+
+    /// Parses a V8 or SpiderMonkey frame line into a `Frame`.
+    pub(crate) fn parse_frame(line: &str) -> Option<Frame> {
+        let (function, location) = Self::split_function_and_location(line)?;
+        let function = function
+            .map(Self::strip_method_alias)
+            .filter(|function| !function.is_empty());
+
+        let (file_path, line, column, address) = Self::parse_location(location);
+        let mut segments = function.map(Frame::split_symbol).unwrap_or_default();
+        let function_name = segments.pop();
+
+        Some(Frame {
+            symbol: function.map(str::to_string),
+            module_path: segments.into_iter().map(str::to_string).collect(),
+            function: function_name.map(str::to_string),
+            file_path: file_path.map(str::to_string),
+            line,
+            column,
+            address,
+            ..Frame::default()
+        })
+    }
+
+    /// Returns the key that frames are grouped by: the script URL, followed
+    /// by the function's symbol.
+    ///
+    /// Consecutive frames from the same script are nested together, even
+    /// though the function names differ. Frames from different scripts are not
+    /// grouped, even if their URLs share a host or directory.
+    pub(crate) fn grouping_key(line: &str) -> Option<GroupingKey<'static>> {
+        let frame = Self::parse_frame(line)?;
+        let Frame {
+            file_path, symbol, ..
+        } = frame;
+
+        Some(GroupingKey::new(
+            [file_path.unwrap_or_default()],
+            symbol.unwrap_or_default(),
+        ))
+    }
+
+    /// Returns the byte index in the line where the frame's location starts,
+    /// if it is a JavaScript frame.
+    pub(crate) fn location_start(line: &str) -> Option<usize> {
+        let (_function, location) = Self::split_function_and_location(line)?;
+        Some(location.as_ptr() as usize - line.as_ptr() as usize)
+    }
+
+    /// Returns which dialect the frame line is in, if it is a JavaScript
+    /// frame.
+    fn line_dialect(line: &str) -> Option<JavascriptDialect> {
+        Self::split_function_and_location(line)?;
+
+        if line.trim_start().starts_with("at ") {
+            Some(JavascriptDialect::V8)
+        } else {
+            Some(JavascriptDialect::SpiderMonkey)
+        }
+    }
+
+    fn split_function_and_location(line: &str) -> Option<(Option<&str>, &str)> {
+        let line_trimmed = line.trim();
+        if line_trimmed.starts_with("at ") {
+            Self::split_v8_function_and_location(line_trimmed)
+        } else {
+            Self::split_spider_monkey_function_and_location(line_trimmed)
+        }
+    }
+
+    /// Splits `at func (url:1:2)` or `at url:1:2` into the function and
+    /// location.
+    ///
+    /// The location must have a column, which distinguishes V8 frames from
+    /// JVM frames.
+    fn split_v8_function_and_location(line_trimmed: &str) -> Option<(Option<&str>, &str)> {
+        let rest = line_trimmed.strip_prefix("at ")?;
+        let rest = rest.strip_prefix("async ").unwrap_or(rest);
+
+        let (function, location) = match rest
+            .strip_suffix(')')
+            .and_then(|rest| rest.split_once(" ("))
+        {
+            Some((function, location)) => (Some(function), location),
+            None => (None, rest),
+        };
+
+        let is_v8_location = matches!(location, "<anonymous>" | "native")
+            || location.starts_with("index ")
+            || Frame::parse_location(location).is_some_and(|(_, _, column)| column.is_some());
+
+        is_v8_location.then_some((function, location))
+    }
+
+    /// Splits `func@url:1:2` into the function and location.
+    fn split_spider_monkey_function_and_location(
+        line_trimmed: &str,
+    ) -> Option<(Option<&str>, &str)> {
+        let (function, location) = line_trimmed.split_once('@')?;
+        let is_spider_monkey_location = location.contains(":wasm-function[")
+            || Frame::parse_location(location).is_some_and(|(_, _, column)| column.is_some());
+
+        is_spider_monkey_location.then_some((Some(function), location))
+    }
+
+    /// Returns the file path, line, column, and address of a location.
+    ///
+    /// Wasm locations are in the form `url:wasm-function[123]:0x6bb546`.
+    fn parse_location(location: &str) -> (Option<&str>, Option<u32>, Option<u32>, Option<u64>) {
+        if let Some((url, function_and_address)) = location.split_once(":wasm-function[") {
+            return (
+                Some(url),
+                None,
+                None,
+                Frame::parse_address(function_and_address),
+            );
+        }
+
+        match Frame::parse_location(location) {
+            Some((file_path, line, column)) => (Some(file_path), Some(line), column, None),
+            None => (None, None, None, None),
+        }
+    }
+
+    /// Strips `[as alias]` from `Layer.handle [as handle_request]`.
+    fn strip_method_alias(function: &str) -> &str {
+        match function.split_once(" [as ") {
+            Some((function, _alias)) => function,
+            None => function,
+        }
+    }
+
+    /// Returns the error name and message from `TypeError: message`.
+    fn parse_header(line: &str) -> Option<(&str, Option<&str>)> {
+        let line = line.trim();
+        let line = line.strip_prefix("Uncaught ").unwrap_or(line);
+        let (name, message) = match line.split_once(": ") {
+            Some((name, message)) => (name, Some(message)),
+            None => (line, None),
+        };
+
+        let is_error_name = (name.ends_with("Error") || name.ends_with("Exception"))
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+
+        is_error_name.then_some((name, message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Frame;
+
+    use super::{JavascriptDialect, JavascriptError};
+
+    #[test]
+    fn parses_v8_error() {
+        let javascript_error = JavascriptError::parse(
+            "\
+            TypeError: Cannot read properties of undefined (reading 'id')\n\
+            \x20   at Object.handler (/app/src/routes/user.js:42:13)\n\
+            \x20   at Layer.handle [as handle_request] (/app/node_modules/express/lib/router/layer.js:95:5)\n\
+            \x20   at async Promise.all (index 0)\n\
+            \x20   at /app/src/index.js:7:3\n\
+            ",
+        )
+        .expect("Expected error to be parsed.");
+
+        assert_eq!(Some("TypeError"), javascript_error.name());
+        assert_eq!(
+            Some("Cannot read properties of undefined (reading 'id')"),
+            javascript_error.message()
+        );
+        assert_eq!(JavascriptDialect::V8, javascript_error.dialect());
+
+        let frame = JavascriptError::parse_frame(
+            "    at Layer.handle [as handle_request] (/app/node_modules/express/lib/router/layer.js:95:5)",
+        );
+        let frame_expected = Frame {
+            symbol: Some(String::from("Layer.handle")),
+            module_path: vec![String::from("Layer")],
+            function: Some(String::from("handle")),
+            file_path: Some(String::from(
+                "/app/node_modules/express/lib/router/layer.js",
+            )),
+            line: Some(95),
+            column: Some(5),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+
+        let frame = JavascriptError::parse_frame("    at /app/src/index.js:7:3");
+        let frame_expected = Frame {
+            file_path: Some(String::from("/app/src/index.js")),
+            line: Some(7),
+            column: Some(3),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+    }
+
+    #[test]
+    fn parses_multi_line_message_after_header_without_message() {
+        let javascript_error = JavascriptError::parse(
+            "\
+            TypeError\n\
+            first line\n\
+            second line\n\
+            \x20   at foo (/app/a.js:1:2)\n\
+            ",
+        )
+        .expect("Expected error to be parsed.");
+
+        assert_eq!(Some("TypeError"), javascript_error.name());
+        assert_eq!(Some("first line\nsecond line"), javascript_error.message());
+    }
+
+    #[test]
+    fn keeps_locations_of_frames_from_the_same_script_whole() {
+        let javascript_error = JavascriptError::parse(
+            "\
+            Error: boom\n\
+            \x20   at foo (/app/a.js:1:2)\n\
+            \x20   at /app/b.js:3:4\n\
+            \x20   at /app/b.js:5:4\n\
+            ",
+        )
+        .expect("Expected error to be parsed.");
+
+        let sections = &javascript_error.stacktrace().sections;
+        assert_eq!(2, sections.len());
+
+        let [section] = sections[1].child_sections() else {
+            panic!("Expected frames from the same script to be nested.");
+        };
+        assert_eq!("    at", section.slice_common_with_previous_frames());
+        assert_eq!(" /app/b.js:5:4", section.slice_remainder());
+    }
+
+    #[test]
+    fn parses_spider_monkey_stack() {
+        let javascript_error = JavascriptError::parse(
+            "\
+            handler@http://localhost:8080/app.js:42:13\n\
+            @http://localhost:8080/app.js:50:1\n\
+            ",
+        )
+        .expect("Expected error to be parsed.");
+
+        assert_eq!(None, javascript_error.name());
+        assert_eq!(JavascriptDialect::SpiderMonkey, javascript_error.dialect());
+
+        let frame = JavascriptError::parse_frame("@http://localhost:8080/app.js:50:1");
+        let frame_expected = Frame {
+            file_path: Some(String::from("http://localhost:8080/app.js")),
+            line: Some(50),
+            column: Some(1),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+    }
+
+    #[test]
+    fn parses_spider_monkey_wasm_frame() {
+        let frame = JavascriptError::parse_frame(
+            "dot_ix_playground.wasm.__wbg_new_abda76e883b18a5f externref shim@http://127.0.0.1:7890/pkg/dot_ix.wasm:wasm-function[25993]:0x6bb546",
+        );
+
+        let frame_expected = Frame {
+            symbol: Some(String::from(
+                "dot_ix_playground.wasm.__wbg_new_abda76e883b18a5f externref shim",
+            )),
+            module_path: vec![String::from("dot_ix_playground"), String::from("wasm")],
+            function: Some(String::from("__wbg_new_abda76e883b18a5f externref shim")),
+            file_path: Some(String::from("http://127.0.0.1:7890/pkg/dot_ix.wasm")),
+            address: Some(0x6bb546),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+    }
+
+    #[test]
+    fn does_not_parse_jvm_frame() {
+        assert_eq!(
+            None,
+            JavascriptError::parse_frame("\tat com.example.App.run(App.java:21)")
+        );
+    }
+}
@@ -0,0 +1,388 @@
+use crate::{Format, Frame, GroupingKey, Stacktrace};
+
+const CALL_TRACE_HEADER: &str = "Call Trace:";
+const END_TRACE_PREFIX: &str = "---[ end ";
+const UNRELIABLE_PREFIX: &str = "? ";
+
+/// Lines that begin an oops, panic, or warning.
+const START_PREFIXES: [&str; 6] = [
+    "BUG: ",
+    "Kernel panic - not syncing",
+    "general protection fault",
+    "Unable to handle kernel ",
+    "WARNING: CPU: ",
+    "------------[ cut here ]------------",
+];
+
+/// Lines describing the oops, which are kept as metadata.
+const METADATA_PREFIXES: [&str; 14] = [
+    "#PF: ",
+    "PGD ",
+    "Oops: ",
+    "Internal error: ",
+    "CPU: ",
+    "Tainted: ",
+    "Hardware name: ",
+    "Workqueue: ",
+    "Code: ",
+    "Mem abort info:",
+    "Modules linked in:",
+    "Stack:",
+    "note: ",
+    END_TRACE_PREFIX,
+];
+
+/// Registers printed in a register dump, other than numbered registers such
+/// as `R8` or `x29`.
+const REGISTER_NAMES: [&str; 29] = [
+    "RIP", "EIP", "RSP", "ESP", "RBP", "EBP", "EFLAGS", "RAX", "RBX", "RCX", "RDX", "RSI", "RDI",
+    "EAX", "EBX", "ECX", "EDX", "ESI", "EDI", "ORIG_RAX", "FS", "GS", "CS", "DS", "ES", "SS", "PC",
+    "LR", "PSTATE",
+];
+
+/// Prefixes of numbered registers, e.g. `R8`, `CR2`, or `x29`.
+const NUMBERED_REGISTER_PREFIXES: [&str; 4] = ["R", "X", "CR", "DR"];
+
+/// A Linux kernel oops, panic, or warning.
+///
+/// The header and register dump are kept as metadata, and only the call trace
+/// is parsed into the stacktrace.
+///
+/// ```text
+/// BUG: kernel NULL pointer dereference, address: 0000000000000000
+/// Oops: 0002 [#1] PREEMPT SMP NOPTI
+/// CPU: 2 PID: 1234 Comm: insmod Tainted: G           O      6.1.0 #1
+/// RIP: 0010:my_init+0x15/0x30 [my_module]
+/// RSP: 0018:ffffb0a1c0d4bd08 EFLAGS: 00010246
+/// Call Trace:
+///  <TASK>
+///  ? __die_body.cold+0x1a/0x1f
+///  do_one_initcall+0x46/0x1e0
+///  do_init_module+0x52/0x220 [my_module]
+///  </TASK>
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KernelOops {
+    /// Lines describing the oops, e.g. `BUG: ..`, `CPU: ..`, and `Modules
+    /// linked in: ..`.
+    pub header: Vec<String>,
+    /// Registers and their values, e.g. `("RIP", "0010:my_init+0x15/0x30
+    /// [my_module]")`.
+    pub registers: Vec<(String, String)>,
+    /// Frames of the call trace, with `<IRQ>` and `</IRQ>` markers as header
+    /// sections.
+    pub stacktrace: Stacktrace,
+}
+
+impl KernelOops {
+    /// Parses the first kernel oops in the given string.
+    ///
+    /// `dmesg` timestamps such as `[  123.456789] ` are ignored. Returns `None`
+    /// if there is no oops header or call trace frame.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut header = Vec::new();
+        let mut registers = Vec::new();
+        let mut trace_lines = Vec::new();
+
+        for line_full in s.lines() {
+            let line = Self::strip_timestamp(line_full).trim();
+
+            if Self::parse_frame(line_full).is_some() || Self::is_context_marker(line) {
+                trace_lines.push(line_full);
+            } else if let Some(line_registers) = Self::parse_registers(line) {
+                registers.extend(
+                    line_registers
+                        .into_iter()
+                        .map(|(name, value)| (name.to_string(), value)),
+                );
+            } else if Self::is_start_line(line) || Self::is_metadata_line(line_full) {
+                header.push(line.to_string());
+                if line.starts_with(END_TRACE_PREFIX) {
+                    break;
+                }
+            }
+        }
+
+        if header.is_empty() && trace_lines.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            header,
+            registers,
+            stacktrace: Stacktrace::from_lines(s, trace_lines, Format::Kernel),
+        })
+    }
+
+    pub fn header(&self) -> &[String] {
+        &self.header
+    }
+
+    pub fn registers(&self) -> &[(String, String)] {
+        &self.registers
+    }
+
+    /// Returns the value of the given register, e.g. `RIP`.
+    pub fn register(&self, name: &str) -> Option<&str> {
+        self.registers
+            .iter()
+            .find(|(register_name, _value)| register_name == name)
+            .map(|(_name, value)| value.as_str())
+    }
+
+    pub fn stacktrace(&self) -> &Stacktrace {
+        &self.stacktrace
+    }
+
+    /// Returns whether the line begins an oops, `Call Trace:`, or a context
+    /// marker such as `<IRQ>`.
+    pub(crate) fn is_header_line(line: &str) -> bool {
+        let line = Self::strip_timestamp(line).trim();
+        line == CALL_TRACE_HEADER || Self::is_context_marker(line) || Self::is_start_line(line)
+    }
+
+    /// Returns whether the line describes the oops, e.g. `CPU: 2 PID: 1234 ..`
+    /// or `RAX: 0000000000000000 ..`.
+    pub(crate) fn is_metadata_line(line: &str) -> bool {
+        let line = Self::strip_timestamp(line).trim();
+        METADATA_PREFIXES
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+            || Self::parse_registers(line).is_some()
+    }
+
+    /// Parses `? do_one_initcall+0x46/0x1e0 [my_module]` into a `Frame`.
+    ///
+    /// Older kernels prefix the frame with its address, e.g.
+    /// `[<ffffffff8100f2c3>] ? dump_trace+0x1a/0x40`.
+    pub(crate) fn parse_frame(line: &str) -> Option<Frame> {
+        let (prefix, rest) = Self::split_frame_prefix(line)?;
+        let (symbol, offset, size, module) = Self::parse_symbol(rest.trim_end())?;
+
+        let address = Self::strip_timestamp(prefix)
+            .trim_start()
+            .strip_prefix("[<")
+            .and_then(|rest| rest.split_once(">]"))
+            .and_then(|(address, _rest)| u64::from_str_radix(address, 16).ok());
+        let reliable = !prefix.trim_end().ends_with('?');
+
+        // Kernel symbols are C functions, so `.cold` and `.isra.0` suffixes are part of
+        // the function name.
+        Some(Frame {
+            symbol: Some(symbol.to_string()),
+            function: Some(symbol.to_string()),
+            library: module.map(str::to_string),
+            address,
+            offset: Some(offset),
+            size: Some(size),
+            reliable: Some(reliable),
+            ..Frame::default()
+        })
+    }
+
+    /// Splits the timestamp, address, and `? ` prefix from the rest of a frame
+    /// line.
+    pub(crate) fn split_frame_prefix(line: &str) -> Option<(&str, &str)> {
+        let rest = Self::strip_timestamp(line).trim_start();
+        let rest = match rest.strip_prefix("[<") {
+            Some(rest) => rest.split_once(">]")?.1.trim_start(),
+            None => rest,
+        };
+        let rest = rest.strip_prefix(UNRELIABLE_PREFIX).unwrap_or(rest);
+        Self::parse_symbol(rest.trim_end())?;
+
+        Some(line.split_at(line.len() - rest.len()))
+    }
+
+    /// Returns the key that frames are grouped by: the module, followed by the
+    /// function.
+    ///
+    /// Frames are compared regardless of their offsets and reliability.
+    pub(crate) fn grouping_key(line: &str) -> Option<GroupingKey<'static>> {
+        let frame = Self::parse_frame(line)?;
+        let module = frame.library().unwrap_or_default();
+        let symbol = frame.symbol().unwrap_or_default();
+
+        Some(GroupingKey::from(format!("{module} {symbol}")))
+    }
+
+    /// Returns the symbol, offset, size, and module from
+    /// `do_init_module+0x52/0x220 [my_module]`.
+    fn parse_symbol(rest: &str) -> Option<(&str, u64, u64, Option<&str>)> {
+        let (symbol_offset_size, module) = match rest
+            .strip_suffix(']')
+            .and_then(|rest| rest.rsplit_once(" ["))
+        {
+            Some((symbol_offset_size, module)) => (symbol_offset_size, Some(module)),
+            None => (rest, None),
+        };
+        if symbol_offset_size.contains(char::is_whitespace) {
+            return None;
+        }
+
+        let (symbol, offset_size) = symbol_offset_size.rsplit_once('+')?;
+        let (offset, size) = offset_size.split_once('/')?;
+        let offset = u64::from_str_radix(offset.strip_prefix("0x")?, 16).ok()?;
+        let size = u64::from_str_radix(size.strip_prefix("0x")?, 16).ok()?;
+
+        (!symbol.is_empty()).then_some((symbol, offset, size, module))
+    }
+
+    /// Returns the registers and their values from a register dump line, e.g.
+    /// `RSP: 0018:ffffb0a1c0d4bd08 EFLAGS: 00010246` or `pc : my_init+0x15/0x30
+    /// [my_module]`.
+    fn parse_registers(line: &str) -> Option<Vec<(&str, String)>> {
+        let mut registers: Vec<(&str, String)> = Vec::new();
+        let mut tokens = line.split_whitespace().peekable();
+
+        while let Some(token) = tokens.next() {
+            let register_name = match token.strip_suffix(':') {
+                Some(name) if Self::is_register_name(name) => Some(name),
+                // arm64 pads the name, e.g. `pc : ..`.
+                _ if Self::is_register_name(token) && tokens.next_if_eq(&":").is_some() => {
+                    Some(token)
+                }
+                _ => None,
+            };
+
+            match (register_name, registers.last_mut()) {
+                (Some(name), _) => registers.push((name, String::new())),
+                (None, Some((_name, value))) => {
+                    if !value.is_empty() {
+                        value.push(' ');
+                    }
+                    value.push_str(token);
+                }
+                (None, None) => return None,
+            }
+        }
+
+        (!registers.is_empty()).then_some(registers)
+    }
+
+    fn is_register_name(name: &str) -> bool {
+        let name = name.to_ascii_uppercase();
+        REGISTER_NAMES.contains(&name.as_str())
+            || NUMBERED_REGISTER_PREFIXES.iter().any(|prefix| {
+                name.strip_prefix(prefix).is_some_and(|number| {
+                    !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+                })
+            })
+    }
+
+    fn is_start_line(line: &str) -> bool {
+        START_PREFIXES.iter().any(|prefix| line.starts_with(prefix))
+    }
+
+    /// Returns whether the line marks a change in context, e.g. `<IRQ>`,
+    /// `</IRQ>`, or `<TASK>`.
+    fn is_context_marker(line: &str) -> bool {
+        line.strip_prefix('<')
+            .and_then(|rest| rest.strip_suffix('>'))
+            .map(|name| name.strip_prefix('/').unwrap_or(name))
+            .is_some_and(|name| !name.is_empty() && name.chars().all(|c| c.is_ascii_uppercase()))
+    }
+
+    /// Strips the `dmesg` timestamp from a line, e.g. `[  123.456789] `.
+    fn strip_timestamp(line: &str) -> &str {
+        line.trim_start()
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once(']'))
+            .filter(|(timestamp, _rest)| {
+                let timestamp = timestamp.trim();
+                !timestamp.is_empty() && timestamp.chars().all(|c| c.is_ascii_digit() || c == '.')
+            })
+            .map(|(_timestamp, rest)| rest)
+            .unwrap_or(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Frame, SectionKind};
+
+    use super::KernelOops;
+
+    #[test]
+    fn parses_oops() {
+        let kernel_oops = KernelOops::parse(
+            "\
+            [  123.456789] BUG: kernel NULL pointer dereference, address: 0000000000000000\n\
+            [  123.456790] Oops: 0002 [#1] PREEMPT SMP NOPTI\n\
+            [  123.456791] CPU: 2 PID: 1234 Comm: insmod Tainted: G           O      6.1.0 #1\n\
+            [  123.456792] RIP: 0010:my_init+0x15/0x30 [my_module]\n\
+            [  123.456793] RSP: 0018:ffffb0a1c0d4bd08 EFLAGS: 00010246\n\
+            [  123.456794] Call Trace:\n\
+            [  123.456795]  <TASK>\n\
+            [  123.456796]  ? __die_body.cold+0x1a/0x1f\n\
+            [  123.456797]  do_one_initcall+0x46/0x1e0\n\
+            [  123.456798]  do_init_module+0x52/0x220 [my_module]\n\
+            [  123.456799]  </TASK>\n\
+            [  123.456800] Modules linked in: my_module(O+)\n\
+            [  123.456801] ---[ end trace 0000000000000000 ]---\n\
+            ",
+        )
+        .expect("Expected oops to be parsed.");
+
+        assert_eq!(
+            vec![
+                "BUG: kernel NULL pointer dereference, address: 0000000000000000",
+                "Oops: 0002 [#1] PREEMPT SMP NOPTI",
+                "CPU: 2 PID: 1234 Comm: insmod Tainted: G           O      6.1.0 #1",
+                "Modules linked in: my_module(O+)",
+                "---[ end trace 0000000000000000 ]---",
+            ],
+            kernel_oops.header()
+        );
+        assert_eq!(
+            Some("0010:my_init+0x15/0x30 [my_module]"),
+            kernel_oops.register("RIP")
+        );
+        assert_eq!(Some("00010246"), kernel_oops.register("EFLAGS"));
+
+        // Only the call trace is nested as sections.
+        let [task, task_end] = kernel_oops.stacktrace().sections.as_slice() else {
+            panic!("Expected two top level sections.");
+        };
+        assert_eq!(SectionKind::Header, task.kind());
+        assert_eq!(SectionKind::Header, task_end.kind());
+        assert_eq!(Some(false), task.child_sections()[0].frame().reliable());
+    }
+
+    #[test]
+    fn parses_arm64_registers() {
+        let kernel_oops = KernelOops::parse(
+            "\
+            Unable to handle kernel NULL pointer dereference at virtual address 0000000000000000\n\
+            pc : my_init+0x15/0x30 [my_module]\n\
+            x29: ffff800008e3bc50 x28: ffff0000c1d3a000\n\
+            ",
+        )
+        .expect("Expected oops to be parsed.");
+
+        assert_eq!(
+            Some("my_init+0x15/0x30 [my_module]"),
+            kernel_oops.register("pc")
+        );
+        assert_eq!(Some("ffff0000c1d3a000"), kernel_oops.register("x28"));
+    }
+
+    #[test]
+    fn parses_frame() {
+        let frame = KernelOops::parse_frame(
+            "[  123.456798] [<ffffffffc0a01015>] ? do_init_module+0x52/0x220 [my_module]",
+        );
+
+        let frame_expected = Frame {
+            symbol: Some(String::from("do_init_module")),
+            function: Some(String::from("do_init_module")),
+            library: Some(String::from("my_module")),
+            address: Some(0xffffffffc0a01015),
+            offset: Some(0x52),
+            size: Some(0x220),
+            reliable: Some(false),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+    }
+}
@@ -1,6 +1,67 @@
 //! Data types representing a stack trace.
 
-pub use crate::{section::Section, stacktrace::Stacktrace};
+pub use crate::{
+    android_crash::{AndroidCrash, AndroidCrashKind},
+    apple_crash_report::{AppleCrashReport, AppleThread},
+    beam_exception::BeamException,
+    debugger_backtrace::{Debugger, DebuggerBacktrace, DebuggerThread},
+    diagnostic::{Diagnostic, DiagnosticKind},
+    dotnet_exception::DotnetException,
+    format::Format,
+    format_detection::FormatDetection,
+    frame::Frame,
+    goroutine_dump::{Goroutine, GoroutineDump},
+    java_exception::JavaException,
+    java_thread_dump::{JavaLock, JavaThread, JavaThreadDump, JavaThreadGroup},
+    javascript_error::{JavascriptDialect, JavascriptError},
+    kernel_oops::KernelOops,
+    line_trivia::{BlankLine, EscapeSequence, LineEnding, LineTrivia},
+    line_trivia_ref::{BlankLineRef, EscapeSequenceRef, LineTriviaRef},
+    parse_error::ParseError,
+    parse_options::ParseOptions,
+    php_exception::PhpException,
+    python_exception::{PythonCause, PythonException},
+    ruby_exception::RubyException,
+    sanitizer_report::{Sanitizer, SanitizerReport, SanitizerStack},
+    section::Section,
+    section_kind::SectionKind,
+    section_ref::SectionRef,
+    source_span::SourceSpan,
+    stacktrace::Stacktrace,
+    stacktrace_ref::StacktraceRef,
+    style_span::{Color, Style, StyleSpan},
+};
 
+pub(crate) use crate::grouping_key::GroupingKey;
+
+mod android_crash;
+mod apple_crash_report;
+mod beam_exception;
+mod debugger_backtrace;
+mod diagnostic;
+mod dotnet_exception;
+mod format;
+mod format_detection;
+mod frame;
+mod goroutine_dump;
+mod grouping_key;
+mod java_exception;
+mod java_thread_dump;
+mod javascript_error;
+mod kernel_oops;
+mod line_trivia;
+mod line_trivia_ref;
+mod parse_error;
+mod parse_options;
+mod php_exception;
+mod python_exception;
+mod ruby_exception;
+mod sanitizer_report;
 mod section;
+mod section_kind;
+mod section_ref;
+mod section_tree;
+mod source_span;
 mod stacktrace;
+mod stacktrace_ref;
+mod style_span;
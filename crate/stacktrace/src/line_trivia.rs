@@ -0,0 +1,141 @@
+use std::fmt;
+
+/// Text that is removed from a line before it is parsed.
+///
+/// This is kept so that a `Stacktrace` can be written out as the text it was
+/// parsed from.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LineTrivia {
+    /// Log prefix of a continuation line.
+    ///
+    /// This is empty for the section's own line, whose log prefix is in
+    /// `slice_log_prefix`.
+    pub log_prefix: String,
+    /// ANSI escape sequences removed from the line.
+    pub escape_sequences: Vec<EscapeSequence>,
+    /// Line ending after the line.
+    pub line_ending: LineEnding,
+    /// Blank lines after the line, which are kept here rather than parsed as
+    /// sections.
+    pub blank_lines: Vec<BlankLine>,
+}
+
+/// A blank line, as it was in the stack trace string.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BlankLine {
+    /// Text of the line, including any whitespace, log prefix, and ANSI
+    /// escape sequences.
+    pub text: String,
+    /// Line ending after the line.
+    pub line_ending: LineEnding,
+}
+
+/// An ANSI escape sequence removed from a line, e.g. `\x1b[1;31m`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct EscapeSequence {
+    /// Byte index in the line without escape sequences, including the log
+    /// prefix, where the escape sequence was.
+    pub index: usize,
+    /// The escape sequence.
+    pub sequence: String,
+}
+
+/// Line ending after a line.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+    /// `\n`.
+    #[default]
+    Lf,
+    /// `\r\n`.
+    CrLf,
+    /// No line ending, for the last line of a string that doesn't end with a
+    /// newline.
+    None,
+}
+
+impl LineTrivia {
+    pub fn log_prefix(&self) -> &str {
+        &self.log_prefix
+    }
+
+    pub fn escape_sequences(&self) -> &[EscapeSequence] {
+        &self.escape_sequences
+    }
+
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    pub fn blank_lines(&self) -> &[BlankLine] {
+        &self.blank_lines
+    }
+
+    /// Writes the line made of the given parts, with its escape sequences, line
+    /// ending, and the blank lines after it.
+    ///
+    /// Escape sequences whose index is not in the line are written at the end
+    /// of the text before them.
+    pub(crate) fn write_line(&self, f: &mut fmt::Formatter<'_>, parts: &[&str]) -> fmt::Result {
+        if self.escape_sequences.is_empty() {
+            parts.iter().try_for_each(|part| f.write_str(part))?;
+        } else {
+            let line = parts.concat();
+            let mut index = 0;
+            for escape_sequence in self.escape_sequences.iter() {
+                if let Some(text) = line.get(index..escape_sequence.index) {
+                    f.write_str(text)?;
+                    index = escape_sequence.index;
+                }
+                f.write_str(&escape_sequence.sequence)?;
+            }
+            f.write_str(&line[index..])?;
+        }
+
+        f.write_str(self.line_ending.as_str())?;
+        self.blank_lines.iter().try_for_each(|blank_line| {
+            f.write_str(&blank_line.text)?;
+            f.write_str(blank_line.line_ending.as_str())
+        })
+    }
+}
+
+impl BlankLine {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+}
+
+impl EscapeSequence {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn sequence(&self) -> &str {
+        &self.sequence
+    }
+}
+
+impl LineEnding {
+    /// Returns the line ending of a line from `str::split_inclusive('\n')`.
+    pub(crate) fn of(line_with_ending: &str) -> Self {
+        if line_with_ending.ends_with("\r\n") {
+            Self::CrLf
+        } else if line_with_ending.ends_with('\n') {
+            Self::Lf
+        } else {
+            Self::None
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+            Self::None => "",
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::borrow::Cow;
+
+use crate::{BlankLine, EscapeSequence, LineEnding, LineTrivia};
+
+/// A `LineTrivia` whose text borrows from the stack trace string.
+///
+/// The log prefix is only copied when the line had ANSI escape sequences.
+///
+/// Use [`LineTriviaRef::into_owned`] to convert this into a `LineTrivia`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LineTriviaRef<'s> {
+    /// Log prefix of a continuation line.
+    ///
+    /// This is empty for the section's own line, whose log prefix is in
+    /// `slice_log_prefix`.
+    pub log_prefix: Cow<'s, str>,
+    /// ANSI escape sequences removed from the line.
+    pub escape_sequences: Vec<EscapeSequenceRef<'s>>,
+    /// Line ending after the line.
+    pub line_ending: LineEnding,
+    /// Blank lines after the line.
+    pub blank_lines: Vec<BlankLineRef<'s>>,
+}
+
+/// A `BlankLine` that borrows from the stack trace string.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BlankLineRef<'s> {
+    /// Text of the line, including any whitespace, log prefix, and ANSI
+    /// escape sequences.
+    pub text: &'s str,
+    /// Line ending after the line.
+    pub line_ending: LineEnding,
+}
+
+/// An `EscapeSequence` that borrows from the stack trace string.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct EscapeSequenceRef<'s> {
+    /// Byte index in the line without escape sequences, including the log
+    /// prefix, where the escape sequence was.
+    pub index: usize,
+    /// The escape sequence.
+    pub sequence: &'s str,
+}
+
+impl<'s> LineTriviaRef<'s> {
+    pub fn log_prefix(&self) -> &str {
+        &self.log_prefix
+    }
+
+    pub fn escape_sequences(&self) -> &[EscapeSequenceRef<'s>] {
+        &self.escape_sequences
+    }
+
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    pub fn blank_lines(&self) -> &[BlankLineRef<'s>] {
+        &self.blank_lines
+    }
+
+    /// Returns an owned `LineTrivia` with the same text.
+    pub fn into_owned(self) -> LineTrivia {
+        LineTrivia {
+            log_prefix: self.log_prefix.into_owned(),
+            escape_sequences: self
+                .escape_sequences
+                .into_iter()
+                .map(EscapeSequenceRef::into_owned)
+                .collect(),
+            line_ending: self.line_ending,
+            blank_lines: self
+                .blank_lines
+                .into_iter()
+                .map(BlankLineRef::into_owned)
+                .collect(),
+        }
+    }
+}
+
+impl<'s> BlankLineRef<'s> {
+    pub fn text(&self) -> &'s str {
+        self.text
+    }
+
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Returns an owned `BlankLine` with the same text.
+    pub fn into_owned(self) -> BlankLine {
+        BlankLine {
+            text: self.text.to_string(),
+            line_ending: self.line_ending,
+        }
+    }
+}
+
+impl<'s> EscapeSequenceRef<'s> {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn sequence(&self) -> &'s str {
+        self.sequence
+    }
+
+    /// Returns an owned `EscapeSequence` with the same text.
+    pub fn into_owned(self) -> EscapeSequence {
+        EscapeSequence {
+            index: self.index,
+            sequence: self.sequence.to_string(),
+        }
+    }
+}
+
+impl<'s> From<LineTriviaRef<'s>> for LineTrivia {
+    fn from(line_trivia_ref: LineTriviaRef<'s>) -> Self {
+        line_trivia_ref.into_owned()
+    }
+}
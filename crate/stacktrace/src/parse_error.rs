@@ -0,0 +1,16 @@
+use crate::Diagnostic;
+
+/// Error when a string cannot be parsed as a stack trace.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ParseError {
+    /// The string has no non-blank lines.
+    #[error("stack trace is empty")]
+    Empty,
+    /// None of the lines are recognised as a header, frame, or continuation
+    /// line.
+    #[error("no lines were recognised as part of a stack trace")]
+    Unrecognised {
+        /// Diagnostics for each line.
+        diagnostics: Vec<Diagnostic>,
+    },
+}
@@ -0,0 +1,101 @@
+use crate::Format;
+
+/// Options for parsing a stack trace with [`Stacktrace::parse_str`].
+///
+/// Options that are `None` use the defaults for the format, from
+/// [`ParseOptions::for_format`].
+///
+/// [`Stacktrace::parse_str`]: crate::Stacktrace::parse_str
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Format of the stack trace.
+    ///
+    /// If this is `None`, the format is detected with
+    /// [`Stacktrace::detect_format`].
+    ///
+    /// [`Stacktrace::detect_format`]: crate::Stacktrace::detect_format
+    pub format: Option<Format>,
+    /// Characters other than alphanumerics that are part of a word, e.g. `_`,
+    /// or `$` in `FilterChainProxy$VirtualFilterChain`.
+    ///
+    /// The slice common with previous frames always ends with a word.
+    pub word_chars: Option<Vec<char>>,
+    /// Separators that the slice common with previous frames may end before,
+    /// e.g. `::`, `.`, `/`, or `$`.
+    ///
+    /// If this is empty, every character that is not a word character is a
+    /// separator.
+    pub segment_separators: Option<Vec<String>>,
+    /// Minimum length in bytes of the slice common with previous frames.
+    ///
+    /// Lines whose common slice is shorter than this are not nested under the
+    /// previous frame.
+    pub min_common_prefix_len: Option<usize>,
+}
+
+impl ParseOptions {
+    /// Returns the default options for the given format.
+    ///
+    /// Java and JavaScript symbols may contain `$`, and Ruby and Elixir
+    /// function names may end with `?` or `!`, so these are word characters
+    /// for those formats.
+    pub fn for_format(format: Format) -> Self {
+        let word_chars = match format {
+            Format::Java | Format::JavaThreadDump | Format::Android | Format::Javascript => {
+                vec!['_', '$']
+            }
+            Format::Ruby | Format::Beam => vec!['_', '?', '!'],
+            Format::Generic
+            | Format::Rust
+            | Format::Python
+            | Format::Go
+            | Format::Dotnet
+            | Format::Php
+            | Format::Sanitizer
+            | Format::Kernel
+            | Format::Apple
+            | Format::Debugger => vec!['_'],
+        };
+
+        Self {
+            format: Some(format),
+            word_chars: Some(word_chars),
+            segment_separators: Some(Vec::new()),
+            min_common_prefix_len: Some(0),
+        }
+    }
+
+    /// Returns these options, with options that are `None` filled in from the
+    /// defaults for the given format.
+    pub fn with_format_defaults(&self, format: Format) -> Self {
+        let defaults = Self::for_format(format);
+
+        Self {
+            format: self.format.or(defaults.format),
+            word_chars: self.word_chars.clone().or(defaults.word_chars),
+            segment_separators: self
+                .segment_separators
+                .clone()
+                .or(defaults.segment_separators),
+            min_common_prefix_len: self
+                .min_common_prefix_len
+                .or(defaults.min_common_prefix_len),
+        }
+    }
+
+    pub fn format(&self) -> Option<Format> {
+        self.format
+    }
+
+    pub fn word_chars(&self) -> &[char] {
+        self.word_chars.as_deref().unwrap_or_default()
+    }
+
+    pub fn segment_separators(&self) -> &[String] {
+        self.segment_separators.as_deref().unwrap_or_default()
+    }
+
+    pub fn min_common_prefix_len(&self) -> usize {
+        self.min_common_prefix_len.unwrap_or_default()
+    }
+}
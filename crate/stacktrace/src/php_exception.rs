@@ -0,0 +1,343 @@
+use crate::{Format, Frame, GroupingKey, Stacktrace};
+
+const STACK_TRACE_HEADER: &str = "Stack trace:";
+const MAIN_FRAME: &str = "{main}";
+const INTERNAL_FUNCTION_LOCATION: &str = "[internal function]";
+const THROWN_IN_PREFIX: &str = "thrown in ";
+const UNCAUGHT_PREFIX: &str = "Uncaught ";
+const NEXT_PREFIX: &str = "Next ";
+
+/// A PHP exception, along with the exception it was chained from.
+///
+/// ```text
+/// PHP Fatal error:  Uncaught RuntimeException: Failed to save in /var/www/src/Repository/UserRepository.php:42
+/// Stack trace:
+/// #0 /var/www/src/Controller/UserController.php(18): App\Repository\UserRepository->save(Object(App\Entity\User))
+/// #1 /var/www/public/index.php(12): App\Controller\UserController->create()
+/// #2 {main}
+///   thrown in /var/www/src/Repository/UserRepository.php on line 42
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PhpException {
+    /// Class of the exception, e.g. `RuntimeException`.
+    pub exception_class: String,
+    /// Message of the exception.
+    pub message: Option<String>,
+    /// Path to the file the exception was thrown in.
+    pub file_path: Option<String>,
+    /// Line the exception was thrown on.
+    pub line: Option<u32>,
+    /// Frames of the exception.
+    pub stacktrace: Stacktrace,
+    /// Exception that this exception was created with as its previous
+    /// exception, printed before this one.
+    pub previous: Option<Box<PhpException>>,
+}
+
+impl PhpException {
+    /// Parses the last PHP exception in the given string, chained to the
+    /// exceptions printed before it with `Next `.
+    ///
+    /// Returns `None` if there is no exception header.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut lines = s.lines().peekable();
+        let mut exception = None;
+
+        while let Some(line) = lines.next() {
+            let Some((exception_class, message, location)) = Self::parse_header(line) else {
+                continue;
+            };
+
+            lines.next_if(|line| line.trim() == STACK_TRACE_HEADER);
+            let mut frame_lines = Vec::new();
+            while let Some(frame_line) = lines.next_if(|line| Self::parse_frame(line).is_some()) {
+                frame_lines.push(frame_line);
+            }
+
+            exception = Some(Self {
+                exception_class: exception_class.to_string(),
+                message: message.map(str::to_string),
+                file_path: Some(location.0.to_string()),
+                line: Some(location.1),
+                stacktrace: Stacktrace::from_lines(s, frame_lines, Format::Php),
+                previous: exception.map(Box::new),
+            });
+        }
+
+        exception
+    }
+
+    pub fn exception_class(&self) -> &str {
+        &self.exception_class
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    pub fn file_path(&self) -> Option<&str> {
+        self.file_path.as_deref()
+    }
+
+    pub fn line(&self) -> Option<u32> {
+        self.line
+    }
+
+    pub fn stacktrace(&self) -> &Stacktrace {
+        &self.stacktrace
+    }
+
+    pub fn previous(&self) -> Option<&PhpException> {
+        self.previous.as_deref()
+    }
+
+    /// Returns an iterator over this exception and each exception it was
+    /// chained from.
+    pub fn chain(&self) -> impl Iterator<Item = &PhpException> {
+        std::iter::successors(Some(self), |exception| exception.previous())
+    }
+
+    /// Returns whether the line is an exception header, or `Stack trace:`.
+    pub(crate) fn is_header_line(line: &str) -> bool {
+        line.trim() == STACK_TRACE_HEADER || Self::parse_header(line).is_some()
+    }
+
+    /// Returns whether the line belongs to the exception header before it, i.e.
+    /// `Stack trace:`.
+    pub(crate) fn is_header_continuation_line(line: &str) -> bool {
+        line.trim() == STACK_TRACE_HEADER
+    }
+
+    /// Returns whether the line notes where the exception was thrown, e.g.
+    /// `  thrown in /var/www/x.php on line 12`.
+    pub(crate) fn is_continuation_line(line: &str) -> bool {
+        line.trim()
+            .strip_prefix(THROWN_IN_PREFIX)
+            .is_some_and(|rest| rest.contains(" on line "))
+    }
+
+    /// Parses `#0 /var/www/x.php(12): Foo\Bar->baz('a')` into a `Frame`.
+    ///
+    /// `#1 [internal function]: ..` and `#2 {main}` frames are also
+    /// recognised.
+    pub(crate) fn parse_frame(line: &str) -> Option<Frame> {
+        let (prefix_len, index) = Frame::parse_index_prefix(line)?;
+        if !line[..prefix_len].trim_start().starts_with('#') {
+            return None;
+        }
+
+        Self::parse_location_and_call(&line[prefix_len..]).map(|frame| Frame {
+            index: Some(index),
+            ..frame
+        })
+    }
+
+    /// Returns the key that frames are grouped by: the file path, followed by
+    /// the class and method.
+    ///
+    /// The line does not include the frame index.
+    pub(crate) fn grouping_key(line: &str) -> Option<GroupingKey<'static>> {
+        Self::parse_location_and_call(line).map(|frame| frame.file_and_module_key())
+    }
+
+    /// Parses `/var/www/x.php(12): Foo\Bar->baz('a')`, the frame after its
+    /// index, into a `Frame`.
+    fn parse_location_and_call(rest: &str) -> Option<Frame> {
+        let rest = rest.trim();
+        if rest == MAIN_FRAME {
+            return Some(Frame {
+                symbol: Some(MAIN_FRAME.to_string()),
+                function: Some(MAIN_FRAME.to_string()),
+                ..Frame::default()
+            });
+        }
+
+        let (location, call) = rest.split_once(": ")?;
+        let (file_path, line_number) = if location == INTERNAL_FUNCTION_LOCATION {
+            (None, None)
+        } else {
+            let (file_path, line_number) = location.strip_suffix(')')?.rsplit_once('(')?;
+            (Some(file_path), Some(line_number.parse::<u32>().ok()?))
+        };
+
+        let (symbol, arguments) = call.strip_suffix(')')?.split_once('(')?;
+        let (class, function) = match symbol.split_once("->") {
+            Some((class, function)) => (Some(class), function),
+            None => match symbol.rsplit_once("::") {
+                Some((class, function)) => (Some(class), function),
+                None => (None, symbol),
+            },
+        };
+        let module_path = class
+            .map(|class| {
+                class
+                    .split('\\')
+                    .filter(|segment| !segment.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(Frame {
+            symbol: Some(symbol.to_string()),
+            module_path,
+            function: Some(function.to_string()),
+            arguments: Some(arguments.to_string()),
+            file_path: file_path.map(str::to_string),
+            line: line_number,
+            ..Frame::default()
+        })
+    }
+
+    /// Returns the class, message, and location from `PHP Fatal error:
+    /// Uncaught Exception: message in /var/www/x.php:12`.
+    ///
+    /// Headers without `Uncaught ` or `Next ` are only recognised when the
+    /// location is a `.php` file.
+    fn parse_header(line: &str) -> Option<(&str, Option<&str>, (&str, u32))> {
+        let line = line.trim();
+        let (rest, has_prefix) = match line
+            .strip_prefix(NEXT_PREFIX)
+            .or_else(|| line.split_once(UNCAUGHT_PREFIX).map(|(_before, rest)| rest))
+        {
+            Some(rest) => (rest, true),
+            None => (line, false),
+        };
+
+        let (exception, location) = rest.rsplit_once(" in ")?;
+        let (file_path, line_number, _column) = Frame::parse_location(location)?;
+        if !has_prefix && !file_path.ends_with(".php") {
+            return None;
+        }
+
+        let (exception_class, message) = match exception.split_once(": ") {
+            Some((exception_class, message)) => (exception_class, Some(message)),
+            None => (exception, None),
+        };
+        let is_class_name = !exception_class.is_empty()
+            && exception_class
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '\\'));
+
+        is_class_name.then_some((exception_class, message, (file_path, line_number)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Format, Frame, SectionKind, Stacktrace};
+
+    use super::PhpException;
+
+    #[test]
+    fn parses_exception() {
+        let php_exception = PhpException::parse(
+            "\
+            PHP Fatal error:  Uncaught RuntimeException: Failed to save in /var/www/src/Repository/UserRepository.php:42\n\
+            Stack trace:\n\
+            #0 /var/www/src/Controller/UserController.php(18): App\\Repository\\UserRepository->save(Object(App\\Entity\\User))\n\
+            #1 [internal function]: App\\Controller\\UserController->create()\n\
+            #2 /var/www/public/index.php(12): call_user_func(Array)\n\
+            #3 {main}\n\
+            \x20 thrown in /var/www/src/Repository/UserRepository.php on line 42\n\
+            ",
+        )
+        .expect("Expected exception to be parsed.");
+
+        assert_eq!("RuntimeException", php_exception.exception_class());
+        assert_eq!(Some("Failed to save"), php_exception.message());
+        assert_eq!(
+            Some("/var/www/src/Repository/UserRepository.php"),
+            php_exception.file_path()
+        );
+        assert_eq!(Some(42), php_exception.line());
+        assert_eq!(None, php_exception.previous());
+    }
+
+    #[test]
+    fn parses_chained_exceptions() {
+        let php_exception = PhpException::parse(
+            "\
+            PDOException: Connection refused in /var/www/src/Db.php:10\n\
+            Stack trace:\n\
+            #0 {main}\n\
+            \n\
+            Next App\\Exception\\StorageException: Failed to connect in /var/www/src/Storage.php:25\n\
+            Stack trace:\n\
+            #0 {main}\n\
+            ",
+        )
+        .expect("Expected exception to be parsed.");
+
+        let exception_classes = php_exception
+            .chain()
+            .map(PhpException::exception_class)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec!["App\\Exception\\StorageException", "PDOException"],
+            exception_classes
+        );
+    }
+
+    #[test]
+    fn groups_frames_by_whole_file_path() {
+        let stacktrace = Stacktrace::from_format(
+            "\
+            PHP Fatal error:  Uncaught RuntimeException: Failed in /var/www/src/Repo.php:10\n\
+            Stack trace:\n\
+            #0 /var/www/src/Repo.php(20): App\\Repo->load()\n\
+            #1 /var/www/src/Repo.php(30): App\\Repo->find()\n\
+            #2 /var/www/public/index.php(12): App\\Repo->find()\n\
+            #3 {main}\n\
+            ",
+            Format::Php,
+        );
+
+        // `Stack trace:` belongs to the exception header, so the frames are nested
+        // within the header.
+        let [header] = stacktrace.sections.as_slice() else {
+            panic!("Expected one top level section.");
+        };
+        assert_eq!(SectionKind::Header, header.kind());
+        assert_eq!(["Stack trace:"], header.continuation_lines());
+
+        // Frames in different files are not grouped, even though their paths share a
+        // directory.
+        let [repo, index, main] = header.child_sections() else {
+            panic!("Expected three frames within the header.");
+        };
+        let repo_child_functions = repo
+            .child_sections()
+            .iter()
+            .map(|section| section.frame().function())
+            .collect::<Vec<_>>();
+        assert_eq!(Some("load"), repo.frame().function());
+        assert_eq!(vec![Some("find")], repo_child_functions);
+        assert_eq!(Some("/var/www/public/index.php"), index.frame().file_path());
+        assert!(index.child_sections().is_empty());
+        assert_eq!(Some("{main}"), main.frame().function());
+    }
+
+    #[test]
+    fn parses_frame() {
+        let frame = PhpException::parse_frame(
+            "#0 /var/www/src/Controller/UserController.php(18): App\\Repository\\UserRepository->save(Object(App\\Entity\\User))",
+        );
+
+        let frame_expected = Frame {
+            index: Some(0),
+            symbol: Some(String::from("App\\Repository\\UserRepository->save")),
+            module_path: vec![
+                String::from("App"),
+                String::from("Repository"),
+                String::from("UserRepository"),
+            ],
+            function: Some(String::from("save")),
+            arguments: Some(String::from("Object(App\\Entity\\User)")),
+            file_path: Some(String::from("/var/www/src/Controller/UserController.php")),
+            line: Some(18),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+    }
+}
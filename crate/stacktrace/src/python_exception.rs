@@ -0,0 +1,422 @@
+use crate::{Format, Frame, GroupingKey, Stacktrace};
+
+const TRACEBACK_HEADER: &str = "Traceback (most recent call last):";
+const DIRECT_CAUSE_SEPARATOR: &str =
+    "The above exception was the direct cause of the following exception:";
+const CONTEXT_SEPARATOR: &str =
+    "During handling of the above exception, another exception occurred:";
+const FILE_PREFIX: &str = "File \"";
+
+/// A Python exception, along with the exception it was chained from.
+///
+/// ```text
+/// Traceback (most recent call last):
+///   File "main.py", line 6, in <module>
+///     main()
+///   File "main.py", line 2, in main
+///     raise ValueError("bad value")
+/// ValueError: bad value
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PythonException {
+    /// Type of the exception, e.g. `ValueError`.
+    pub exception_type: String,
+    /// Message of the exception, e.g. `bad value`.
+    pub message: Option<String>,
+    /// Frames of this exception, most recent call last.
+    pub stacktrace: Stacktrace,
+    /// Exception printed before this one, which this exception was chained
+    /// from.
+    pub cause: Option<PythonCause>,
+}
+
+/// An exception that another Python exception was chained from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PythonCause {
+    /// The exception was raised with `raise .. from ..`.
+    ///
+    /// `The above exception was the direct cause of the following exception:`
+    Direct(Box<PythonException>),
+    /// The exception was raised while handling this exception.
+    ///
+    /// `During handling of the above exception, another exception occurred:`
+    Context(Box<PythonException>),
+}
+
+impl PythonCause {
+    pub fn exception(&self) -> &PythonException {
+        match self {
+            Self::Direct(exception) | Self::Context(exception) => exception,
+        }
+    }
+}
+
+impl PythonException {
+    /// Parses the last Python exception in the given string, chained to the
+    /// exceptions printed before it.
+    ///
+    /// Returns `None` if there is no `Traceback (most recent call last):`
+    /// line.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut lines = s.lines().peekable();
+        let mut exception = None;
+        let mut separator = None;
+
+        while let Some(line) = lines.next() {
+            match line.trim() {
+                TRACEBACK_HEADER => {}
+                DIRECT_CAUSE_SEPARATOR | CONTEXT_SEPARATOR => {
+                    separator = Some(line.trim());
+                    continue;
+                }
+                _ => continue,
+            }
+
+            let mut frame_lines = Vec::new();
+            while let Some(frame_line) = lines
+                .next_if(|line| line.starts_with(char::is_whitespace) && !line.trim().is_empty())
+            {
+                frame_lines.push(frame_line);
+            }
+
+            let (exception_type, message) = lines
+                .next_if(|line| !line.trim().is_empty())
+                .map(Self::parse_exception_line)
+                .unwrap_or_default();
+
+            let cause = exception
+                .take()
+                .zip(separator.take())
+                .map(|(cause, separator)| match separator {
+                    DIRECT_CAUSE_SEPARATOR => PythonCause::Direct(Box::new(cause)),
+                    _ => PythonCause::Context(Box::new(cause)),
+                });
+
+            exception = Some(Self {
+                exception_type: exception_type.to_string(),
+                message: message.map(str::to_string),
+                stacktrace: Stacktrace::from_lines(s, frame_lines, Format::Python),
+                cause,
+            });
+        }
+
+        exception
+    }
+
+    pub fn exception_type(&self) -> &str {
+        &self.exception_type
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    pub fn stacktrace(&self) -> &Stacktrace {
+        &self.stacktrace
+    }
+
+    pub fn cause(&self) -> Option<&PythonCause> {
+        self.cause.as_ref()
+    }
+
+    /// Returns an iterator over this exception and each exception it was
+    /// chained from.
+    pub fn chain(&self) -> impl Iterator<Item = &PythonException> {
+        std::iter::successors(Some(self), |exception| {
+            exception.cause().map(PythonCause::exception)
+        })
+    }
+
+    /// Returns whether the line begins a traceback, or separates chained
+    /// tracebacks.
+    pub(crate) fn is_header_line(line: &str) -> bool {
+        line.trim() == TRACEBACK_HEADER || Self::is_chain_separator_line(line)
+    }
+
+    /// Returns whether the line separates chained tracebacks, e.g. `During
+    /// handling of the above exception, another exception occurred:`.
+    pub(crate) fn is_chain_separator_line(line: &str) -> bool {
+        matches!(line.trim(), DIRECT_CAUSE_SEPARATOR | CONTEXT_SEPARATOR)
+    }
+
+    /// Returns whether the line is the exception that ends the traceback
+    /// begun by `header_line`.
+    pub(crate) fn is_footer_line(header_line: &str, line: &str) -> bool {
+        header_line.trim() == TRACEBACK_HEADER && Self::is_exception_line(line)
+    }
+
+    /// Returns whether the line is the exception at the end of a traceback,
+    /// e.g. `ValueError: bad value` or `KeyboardInterrupt`.
+    pub(crate) fn is_exception_line(line: &str) -> bool {
+        let (exception_type, _message) = Self::parse_exception_line(line);
+
+        !line.starts_with(char::is_whitespace)
+            && exception_type.starts_with(|c: char| c.is_alphabetic() || c == '_')
+            && exception_type
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.'))
+    }
+
+    /// Returns whether the line is a frame, e.g. `  File "main.py", line 2, in
+    /// main`.
+    pub(crate) fn is_frame_line(line: &str) -> bool {
+        line.trim_start().starts_with(FILE_PREFIX)
+    }
+
+    /// Returns whether the line is the echoed source of the frame on
+    /// `frame_line`, e.g. `    raise ValueError("bad value")`.
+    ///
+    /// Python 3.11 also adds a line of `^` markers under the source, which is
+    /// part of the frame as well.
+    pub(crate) fn is_continuation_line(frame_line: &str, line: &str) -> bool {
+        Self::is_frame_line(frame_line)
+            && !Self::is_frame_line(line)
+            && !line.trim().is_empty()
+            && Self::indent_len(line) > Self::indent_len(frame_line)
+    }
+
+    /// Parses a `File "main.py", line 2, in main` line into a `Frame`.
+    pub(crate) fn parse_frame(line: &str) -> Option<Frame> {
+        let rest = line.trim().strip_prefix(FILE_PREFIX)?;
+        let (file_path, rest) = rest.split_once("\", line ")?;
+        let (line_number, function) = match rest.split_once(", in ") {
+            Some((line_number, function)) => (line_number, Some(function)),
+            None => (rest, None),
+        };
+        let line_number = line_number.parse::<u32>().ok()?;
+
+        Some(Frame {
+            symbol: function.map(str::to_string),
+            function: function.map(str::to_string),
+            file_path: Some(file_path.to_string()),
+            line: Some(line_number),
+            ..Frame::default()
+        })
+    }
+
+    /// Returns the key that frames are grouped by: the file path, followed by
+    /// the function.
+    ///
+    /// Frames in different files are not grouped, even though every frame line
+    /// begins with `File "`.
+    pub(crate) fn grouping_key(line: &str) -> Option<GroupingKey<'static>> {
+        let Frame {
+            file_path,
+            function,
+            ..
+        } = Self::parse_frame(line)?;
+
+        Some(GroupingKey::new(
+            [file_path.unwrap_or_default()],
+            function.unwrap_or_default(),
+        ))
+    }
+
+    /// Returns the frame with its source filled in from the echoed source
+    /// line.
+    pub(crate) fn frame_with_source_line(mut frame: Frame, source_line: &str) -> Frame {
+        let source_line = source_line.trim();
+        let is_marker_line = source_line.chars().all(|c| matches!(c, '^' | '~'));
+        if frame.source.is_none() && !is_marker_line {
+            frame.source = Some(source_line.to_string());
+        }
+
+        frame
+    }
+
+    /// Returns the exception type and message from `ValueError: bad value`.
+    fn parse_exception_line(line: &str) -> (&str, Option<&str>) {
+        let line = line.trim();
+        match line.split_once(": ") {
+            Some((exception_type, message)) => (exception_type, Some(message)),
+            None => (line.trim_end_matches(':'), None),
+        }
+    }
+
+    fn indent_len(line: &str) -> usize {
+        line.len() - line.trim_start().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Format, Frame, SectionKind, Stacktrace};
+
+    use super::{PythonCause, PythonException};
+
+    #[test]
+    fn parses_traceback() {
+        let s = "\
+            Traceback (most recent call last):\n\
+            \x20 File \"main.py\", line 6, in <module>\n\
+            \x20   main()\n\
+            \x20 File \"main.py\", line 2, in main\n\
+            \x20   raise ValueError(\"bad value\")\n\
+            ValueError: bad value\n\
+            ";
+        let python_exception = PythonException::parse(s);
+
+        let python_exception_expected = PythonException {
+            exception_type: String::from("ValueError"),
+            message: Some(String::from("bad value")),
+            stacktrace: Stacktrace::from_substring(
+                s,
+                "\
+                \x20 File \"main.py\", line 6, in <module>\n\
+                \x20   main()\n\
+                \x20 File \"main.py\", line 2, in main\n\
+                \x20   raise ValueError(\"bad value\")\n\
+                ",
+            ),
+            cause: None,
+        };
+        assert_eq!(Some(&python_exception_expected), python_exception.as_ref());
+
+        let section = &python_exception_expected.stacktrace.sections[0];
+        assert_eq!(["    main()"], section.continuation_lines());
+        assert_eq!(Some("main()"), section.frame().source());
+    }
+
+    #[test]
+    fn parses_tracebacks_as_headers_with_exception_lines_as_footers() {
+        let s = "\
+            Traceback (most recent call last):\n\
+            \x20 File \"/app/main.py\", line 10, in <module>\n\
+            \x20   main()\n\
+            \x20 File \"/app/main.py\", line 6, in main\n\
+            \x20   load()\n\
+            \x20 File \"/app/config.py\", line 3, in load\n\
+            \x20   return config[\"key\"]\n\
+            KeyError: 'key'\n\
+            \n\
+            During handling of the above exception, another exception occurred:\n\
+            \n\
+            Traceback (most recent call last):\n\
+            \x20 File \"/app/main.py\", line 12, in <module>\n\
+            \x20   raise ConfigError(\"missing key\")\n\
+            ConfigError: missing key\n\
+            ";
+
+        [Format::Python, Format::Generic]
+            .into_iter()
+            .for_each(|format| {
+                let stacktrace = Stacktrace::from_format(s, format);
+
+                let headers = stacktrace
+                    .sections
+                    .iter()
+                    .map(|section| (section.kind(), section.slice_remainder()))
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    vec![
+                        (SectionKind::Header, "Traceback (most recent call last):"),
+                        (SectionKind::Header, "Traceback (most recent call last):"),
+                    ],
+                    headers,
+                    "{format:?}"
+                );
+
+                // Frames in the same file are grouped, and frames in other files are not.
+                let [main, config, footer] = stacktrace.sections[0].child_sections() else {
+                    panic!("Expected two frames and a footer within the first traceback.");
+                };
+                let main_child_functions = main
+                    .child_sections()
+                    .iter()
+                    .map(|section| section.frame().function())
+                    .collect::<Vec<_>>();
+                assert_eq!(Some("<module>"), main.frame().function());
+                assert_eq!(vec![Some("main")], main_child_functions);
+                assert_eq!(Some("/app/config.py"), config.frame().file_path());
+
+                // The separator belongs to the exception line, and blank lines are trivia.
+                assert_eq!(SectionKind::Footer, footer.kind());
+                assert_eq!("KeyError: 'key'", footer.slice_remainder());
+                assert_eq!(
+                    vec![String::from(
+                        "During handling of the above exception, another exception occurred:"
+                    )],
+                    footer.continuation_lines()
+                );
+                let blank_line_counts = footer
+                    .line_trivia()
+                    .iter()
+                    .map(|line_trivia| line_trivia.blank_lines().len())
+                    .collect::<Vec<_>>();
+                assert_eq!(vec![1, 1], blank_line_counts);
+
+                let [_frame, footer] = stacktrace.sections[1].child_sections() else {
+                    panic!("Expected a frame and a footer within the second traceback.");
+                };
+                assert_eq!(SectionKind::Footer, footer.kind());
+                assert_eq!("ConfigError: missing key", footer.slice_remainder());
+
+                assert_eq!(s, stacktrace.to_string());
+            });
+    }
+
+    #[test]
+    fn parses_frame_with_source() {
+        let frame = PythonException::parse_frame("  File \"main.py\", line 2, in main")
+            .map(|frame| {
+                PythonException::frame_with_source_line(frame, "    raise ValueError(\"bad\")")
+            })
+            .map(|frame| PythonException::frame_with_source_line(frame, "    ^^^^^^^^^^^^^^^^"));
+
+        let frame_expected = Frame {
+            symbol: Some(String::from("main")),
+            function: Some(String::from("main")),
+            file_path: Some(String::from("main.py")),
+            line: Some(2),
+            source: Some(String::from("raise ValueError(\"bad\")")),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+    }
+
+    #[test]
+    fn parses_chained_exceptions() {
+        let python_exception = PythonException::parse(
+            "\
+            Traceback (most recent call last):\n\
+            \x20 File \"app.py\", line 3, in load\n\
+            \x20   return config[\"key\"]\n\
+            KeyError: 'key'\n\
+            \n\
+            The above exception was the direct cause of the following exception:\n\
+            \n\
+            Traceback (most recent call last):\n\
+            \x20 File \"app.py\", line 5, in load\n\
+            \x20   raise ConfigError(\"missing key\") from e\n\
+            ConfigError: missing key\n\
+            \n\
+            During handling of the above exception, another exception occurred:\n\
+            \n\
+            Traceback (most recent call last):\n\
+            \x20 File \"app.py\", line 9, in main\n\
+            \x20   log.fail()\n\
+            AttributeError: 'NoneType' object has no attribute 'fail'\n\
+            ",
+        )
+        .expect("Expected exception to be parsed.");
+
+        let exception_types = python_exception
+            .chain()
+            .map(PythonException::exception_type)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec!["AttributeError", "ConfigError", "KeyError"],
+            exception_types
+        );
+        assert!(matches!(
+            python_exception.cause(),
+            Some(PythonCause::Context(_))
+        ));
+        assert!(matches!(
+            python_exception
+                .cause()
+                .and_then(|cause| cause.exception().cause()),
+            Some(PythonCause::Direct(_))
+        ));
+    }
+}
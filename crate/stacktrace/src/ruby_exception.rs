@@ -0,0 +1,224 @@
+use crate::{Format, Frame, GroupingKey, Stacktrace};
+
+const TRACEBACK_HEADER: &str = "Traceback (most recent call last):";
+const FROM_PREFIX: &str = "from ";
+const IN_SEPARATOR: &str = ":in ";
+
+/// A Ruby exception and its backtrace.
+///
+/// ```text
+/// app/models/user.rb:42:in 'User#save': undefined method 'id' for nil (NoMethodError)
+///         from app/controllers/users_controller.rb:12:in 'UsersController#create'
+///         from bin/rails:4:in '<main>'
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RubyException {
+    /// Class of the exception, e.g. `NoMethodError`.
+    pub exception_class: Option<String>,
+    /// Message of the exception.
+    pub message: Option<String>,
+    /// Frames of the exception.
+    pub stacktrace: Stacktrace,
+}
+
+impl RubyException {
+    /// Parses the first Ruby backtrace in the given string.
+    ///
+    /// Both the default order and the reversed order printed by Ruby 2.5 to 2.7
+    /// are supported. Returns `None` if there are no Ruby frames.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut lines = s
+            .lines()
+            .skip_while(|line| Self::parse_frame(line).is_none())
+            .peekable();
+
+        let mut exception_class = None;
+        let mut message = None;
+        let mut frame_lines = Vec::new();
+        while let Some(frame_line) = lines.next_if(|line| Self::parse_frame(line).is_some()) {
+            if let Some((line_message, line_exception_class)) = Self::parse_message(frame_line) {
+                message = Some(line_message.to_string());
+                exception_class = line_exception_class.map(str::to_string);
+            }
+            frame_lines.push(frame_line);
+        }
+
+        if frame_lines.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            exception_class,
+            message,
+            stacktrace: Stacktrace::from_lines(s, frame_lines, Format::Ruby),
+        })
+    }
+
+    pub fn exception_class(&self) -> Option<&str> {
+        self.exception_class.as_deref()
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    pub fn stacktrace(&self) -> &Stacktrace {
+        &self.stacktrace
+    }
+
+    /// Returns whether the line begins a reversed backtrace, printed by Ruby
+    /// 2.5 to 2.7.
+    pub(crate) fn is_header_line(line: &str) -> bool {
+        line.trim() == TRACEBACK_HEADER
+    }
+
+    /// Parses `app/models/user.rb:42:in 'User#save'` into a `Frame`.
+    ///
+    /// Older versions of Ruby quote the label as `` `save' ``, and backtraces
+    /// may prefix frames with `from ` or `2: from `.
+    pub(crate) fn parse_frame(line: &str) -> Option<Frame> {
+        let (location, label, _rest) = Self::split_frame(line)?;
+        let (file_path, line_number, column) = Frame::parse_location(location)?;
+        if column.is_some() {
+            return None;
+        }
+
+        // `block (2 levels) in User#save` is within `User#save`.
+        let method = match label.split_once(" in ") {
+            Some((_block, method)) => method,
+            None => label,
+        };
+        let (module_path, function) = match method.rsplit_once('#') {
+            Some((module, function)) => (Frame::split_symbol(module), function),
+            None => {
+                let mut segments = Frame::split_symbol(method);
+                let function = segments.pop().unwrap_or(method);
+                (segments, function)
+            }
+        };
+
+        Some(Frame {
+            symbol: Some(label.to_string()),
+            module_path: module_path.into_iter().map(str::to_string).collect(),
+            function: Some(function.to_string()),
+            file_path: Some(file_path.to_string()),
+            line: Some(line_number),
+            ..Frame::default()
+        })
+    }
+
+    /// Returns the key that frames are grouped by: the file path, followed by
+    /// the class or module and method.
+    ///
+    /// Frames in the same file are grouped by class, rather than by line
+    /// number.
+    pub(crate) fn grouping_key(line: &str) -> Option<GroupingKey<'static>> {
+        Self::parse_frame(line).map(|frame| frame.file_and_module_key())
+    }
+
+    /// Splits a frame line into its location, label, and the text after the
+    /// label.
+    fn split_frame(line: &str) -> Option<(&str, &str, &str)> {
+        let (_slice_frame_index, line) = Frame::split_index_prefix(line);
+        let line = line.trim();
+        let line = line.strip_prefix(FROM_PREFIX).unwrap_or(line);
+
+        let (location, label_and_rest) = line.split_once(IN_SEPARATOR)?;
+        let (label, rest) = label_and_rest.strip_prefix(['`', '\''])?.split_once('\'')?;
+
+        Some((location, label, rest))
+    }
+
+    /// Returns the message and exception class from the text after a frame,
+    /// e.g. `: undefined method 'id' for nil (NoMethodError)`.
+    fn parse_message(line: &str) -> Option<(&str, Option<&str>)> {
+        let (_location, _label, rest) = Self::split_frame(line)?;
+        let message = rest.strip_prefix(": ")?;
+
+        match message
+            .strip_suffix(')')
+            .and_then(|message| message.rsplit_once(" ("))
+        {
+            Some((message, exception_class)) => Some((message, Some(exception_class))),
+            None => Some((message, None)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Frame;
+
+    use super::RubyException;
+
+    #[test]
+    fn parses_exception() {
+        let ruby_exception = RubyException::parse(
+            "\
+            app/models/user.rb:42:in 'User#save': undefined method 'id' for nil (NoMethodError)\n\
+            \tfrom app/models/user.rb:30:in 'block in User#validate'\n\
+            \tfrom app/controllers/users_controller.rb:12:in 'UsersController#create'\n\
+            \tfrom bin/rails:4:in '<main>'\n\
+            ",
+        )
+        .expect("Expected exception to be parsed.");
+
+        assert_eq!(Some("NoMethodError"), ruby_exception.exception_class());
+        assert_eq!(
+            Some("undefined method 'id' for nil"),
+            ruby_exception.message()
+        );
+
+        // Frames are grouped by path and class, even though their line numbers differ.
+        // Frames in other files are not grouped with them, even though their paths
+        // share a directory.
+        let [user, users_controller, main] = ruby_exception.stacktrace().sections.as_slice() else {
+            panic!("Expected three top level sections.");
+        };
+        let user_child_functions = user
+            .child_sections()
+            .iter()
+            .map(|section| section.frame().function())
+            .collect::<Vec<_>>();
+        assert_eq!(Some("save"), user.frame().function());
+        assert_eq!(vec![Some("validate")], user_child_functions);
+        assert_eq!(Some("create"), users_controller.frame().function());
+        assert!(users_controller.child_sections().is_empty());
+        assert_eq!(Some("<main>"), main.frame().function());
+    }
+
+    #[test]
+    fn parses_reversed_backtrace() {
+        let ruby_exception = RubyException::parse(
+            "\
+            Traceback (most recent call last):\n\
+            \t1: from app.rb:5:in `<main>'\n\
+            app.rb:2:in `fail!': failed (RuntimeError)\n\
+            ",
+        )
+        .expect("Expected exception to be parsed.");
+
+        assert_eq!(Some("RuntimeError"), ruby_exception.exception_class());
+        assert_eq!(Some("failed"), ruby_exception.message());
+        assert_eq!(1, ruby_exception.stacktrace().sections.len());
+    }
+
+    #[test]
+    fn parses_frame() {
+        let frame = RubyException::parse_frame(
+            "\tfrom lib/active_record/persistence.rb:619:in 'block (2 levels) in ActiveRecord::Persistence#save'",
+        );
+
+        let frame_expected = Frame {
+            symbol: Some(String::from(
+                "block (2 levels) in ActiveRecord::Persistence#save",
+            )),
+            module_path: vec![String::from("ActiveRecord"), String::from("Persistence")],
+            function: Some(String::from("save")),
+            file_path: Some(String::from("lib/active_record/persistence.rb")),
+            line: Some(619),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+    }
+}
@@ -0,0 +1,466 @@
+use crate::{DebuggerBacktrace, Format, Frame, GroupingKey, Stacktrace};
+
+const SUMMARY_PREFIX: &str = "SUMMARY: ";
+const RUNTIME_ERROR_SEPARATOR: &str = ": runtime error: ";
+const BUILD_ID_SEPARATOR: &str = " (BuildId: ";
+
+/// A report printed by a sanitizer, with each of its labelled stacks.
+///
+/// ```text
+/// ==4321==ERROR: AddressSanitizer: heap-use-after-free on address 0x602000000010
+/// READ of size 4 at 0x602000000010 thread T0
+///     #0 0x4f5a2b in main /src/app.c:12:3
+///     #1 0x7f3c21 in __libc_start_main (/lib/x86_64-linux-gnu/libc.so.6+0x21b96)
+///
+/// freed by thread T0 here:
+///     #0 0x4c0a1d in free (/app/a.out+0x4c0a1d)
+///     #1 0x4f5a01 in main /src/app.c:10:3
+///
+/// SUMMARY: AddressSanitizer: heap-use-after-free /src/app.c:12:3 in main
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SanitizerReport {
+    /// Which sanitizer printed the report.
+    pub sanitizer: Sanitizer,
+    /// Kind of the error, e.g. `heap-use-after-free` or `data race`.
+    pub kind: Option<String>,
+    /// Summary line, without the `SUMMARY: ` prefix.
+    pub summary: Option<String>,
+    /// Each stack in the report, in the order they are printed.
+    pub stacks: Vec<SanitizerStack>,
+}
+
+/// Which sanitizer printed a report.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Sanitizer {
+    /// `AddressSanitizer`, ASan.
+    #[default]
+    Address,
+    /// `LeakSanitizer`, LSan.
+    Leak,
+    /// `MemorySanitizer`, MSan.
+    Memory,
+    /// `ThreadSanitizer`, TSan.
+    Thread,
+    /// `UndefinedBehaviorSanitizer`, UBSan.
+    UndefinedBehavior,
+}
+
+/// A labelled stack within a `SanitizerReport`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SanitizerStack {
+    /// Line printed before the stack, e.g. `freed by thread T0 here`.
+    ///
+    /// The trailing `:` is removed.
+    pub label: Option<String>,
+    /// Frames of the stack.
+    pub stacktrace: Stacktrace,
+}
+
+impl SanitizerReport {
+    /// Parses the first sanitizer report in the given string.
+    ///
+    /// The report ends at its `SUMMARY: ` line. Returns `None` if there is
+    /// no sanitizer error or summary line.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut sanitizer = None;
+        let mut kind = None;
+        let mut summary = None;
+        let mut stacks = Vec::new();
+
+        let mut label = None;
+        let mut stack_label = None;
+        let mut frame_lines = Vec::new();
+        for line in s.lines() {
+            if Self::parse_frame(line).is_some() {
+                if frame_lines.is_empty() {
+                    stack_label = label.take();
+                }
+                frame_lines.push(line);
+                continue;
+            }
+
+            if !frame_lines.is_empty() {
+                stacks.push(SanitizerStack {
+                    label: stack_label.take().map(str::to_string),
+                    stacktrace: Stacktrace::from_lines(s, frame_lines.drain(..), Format::Sanitizer),
+                });
+            }
+
+            if let Some((line_sanitizer, description)) = Self::parse_header(line) {
+                sanitizer.get_or_insert(line_sanitizer);
+                kind.get_or_insert(Self::kind_from_description(description));
+            } else if let Some((line_sanitizer, line_summary)) = Self::parse_summary(line) {
+                sanitizer.get_or_insert(line_sanitizer);
+                summary = Some(line_summary);
+                break;
+            }
+
+            let line = Self::strip_pid_prefix(line.trim());
+            if !line.is_empty() {
+                label = Some(line.strip_suffix(':').unwrap_or(line));
+            }
+        }
+
+        if !frame_lines.is_empty() {
+            stacks.push(SanitizerStack {
+                label: stack_label.map(str::to_string),
+                stacktrace: Stacktrace::from_lines(s, frame_lines, Format::Sanitizer),
+            });
+        }
+
+        // UBSan reports only name the kind in the summary, e.g.
+        // `SUMMARY: UndefinedBehaviorSanitizer: undefined-behavior a.c:5:12 in`.
+        let kind = kind.or_else(|| {
+            summary
+                .and_then(|summary| summary.split_once(": "))
+                .and_then(|(_sanitizer, rest)| rest.split_whitespace().next())
+        });
+
+        Some(Self {
+            sanitizer: sanitizer?,
+            kind: kind.map(str::to_string),
+            summary: summary.map(str::to_string),
+            stacks,
+        })
+    }
+
+    pub fn sanitizer(&self) -> Sanitizer {
+        self.sanitizer
+    }
+
+    pub fn kind(&self) -> Option<&str> {
+        self.kind.as_deref()
+    }
+
+    pub fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    pub fn stacks(&self) -> &[SanitizerStack] {
+        &self.stacks
+    }
+
+    /// Returns whether the line is a sanitizer error, summary, or stack label,
+    /// e.g. `freed by thread T0 here:`.
+    pub(crate) fn is_header_line(line: &str) -> bool {
+        Self::parse_header(line).is_some()
+            || Self::parse_summary(line).is_some()
+            || Self::is_stack_label_line(line)
+    }
+
+    /// Parses `#0 0x4f5a2b in main /src/app.c:12:3` or `#0 0x4c0a1d in free
+    /// (/app/a.out+0x4c0a1d)` into a `Frame`.
+    ///
+    /// ThreadSanitizer frames have no address, e.g. `#0 Thread1 race.c:4
+    /// (a.out+0xa360)`.
+    pub(crate) fn parse_frame(line: &str) -> Option<Frame> {
+        let (prefix_len, index) = Frame::parse_index_prefix(line)?;
+        if !line[..prefix_len].trim_start().starts_with('#') {
+            return None;
+        }
+        let rest = line[prefix_len..].trim();
+        let rest = match rest.rsplit_once(BUILD_ID_SEPARATOR) {
+            Some((rest, _build_id)) => rest,
+            None => rest,
+        };
+
+        let (address, rest) = match rest.split_once(' ') {
+            Some((address, rest)) if address.starts_with("0x") => (
+                Some(Frame::parse_address(address)?),
+                rest.strip_prefix("in ").unwrap_or(rest),
+            ),
+            _ => (None, rest),
+        };
+        let (rest, library) = match Self::split_module_offset(rest) {
+            Some((rest, library)) => (rest, Some(library)),
+            None => (rest, None),
+        };
+        let (rest, location) = match rest
+            .rsplit_once(' ')
+            .and_then(|(rest, location)| Some((rest, Frame::parse_location(location)?)))
+        {
+            Some((rest, location)) => (rest, Some(location)),
+            None => (rest, None),
+        };
+
+        // gdb frames have arguments and ` at ` before the location, e.g. `#1
+        // 0x5555 in load (id=7) at a.cc:42`, and PHP frames have no address.
+        let is_gdb_frame = rest.ends_with(" at") || rest.contains(" at ");
+        let has_location = library.is_some() || location.is_some();
+        if is_gdb_frame || (!has_location && (address.is_none() || rest.contains(' '))) {
+            return None;
+        }
+
+        let (function, arguments) = DebuggerBacktrace::split_trailing_arguments(rest);
+        let symbol = Some(function).filter(|function| !function.is_empty());
+        let mut segments = symbol.map(Frame::split_symbol).unwrap_or_default();
+        let function = segments.pop();
+        let (file_path, line, column) = match location {
+            Some((file_path, line, column)) => (Some(file_path.to_string()), Some(line), column),
+            None => (None, None, None),
+        };
+
+        Some(Frame {
+            index: Some(index),
+            symbol: symbol.map(str::to_string),
+            module_path: segments.into_iter().map(str::to_string).collect(),
+            function: function.map(str::to_string),
+            arguments: arguments.map(str::to_string),
+            library: library.map(str::to_string),
+            file_path,
+            line,
+            column,
+            address,
+            ..Frame::default()
+        })
+    }
+
+    /// Returns the key that frames are grouped by: the function, or the
+    /// module if the function is unknown.
+    pub(crate) fn grouping_key(line: &str) -> Option<GroupingKey<'static>> {
+        let frame = Self::parse_frame(line)?;
+        let key = frame
+            .symbol()
+            .or_else(|| frame.library())
+            .unwrap_or_default();
+
+        Some(GroupingKey::from(key.to_string()))
+    }
+
+    /// Splits `free (/app/a.out+0x4c0a1d)` into `free` and `/app/a.out`.
+    fn split_module_offset(rest: &str) -> Option<(&str, &str)> {
+        let (rest, module_offset) = match rest.rsplit_once(" (") {
+            Some(rest_and_module_offset) => rest_and_module_offset,
+            None => ("", rest.strip_prefix('(')?),
+        };
+        let (module, offset) = module_offset.strip_suffix(')')?.rsplit_once("+0x")?;
+        u64::from_str_radix(offset, 16).ok()?;
+
+        Some((rest, module))
+    }
+
+    /// Returns the sanitizer and error description from `==4321==ERROR:
+    /// AddressSanitizer: heap-use-after-free on address ..`, `WARNING:
+    /// ThreadSanitizer: data race (pid=9337)`, or `a.c:5:12: runtime error:
+    /// signed integer overflow: ..`.
+    fn parse_header(line: &str) -> Option<(Sanitizer, &str)> {
+        let line = Self::strip_pid_prefix(line.trim());
+        if let Some((_location, description)) = line.split_once(RUNTIME_ERROR_SEPARATOR) {
+            return Some((Sanitizer::UndefinedBehavior, description));
+        }
+
+        let rest = line
+            .strip_prefix("ERROR: ")
+            .or_else(|| line.strip_prefix("WARNING: "))?;
+        let (sanitizer_name, description) = rest.split_once(": ")?;
+
+        Some((Sanitizer::from_name(sanitizer_name)?, description))
+    }
+
+    /// Returns the sanitizer and summary from `SUMMARY: AddressSanitizer:
+    /// heap-use-after-free /src/app.c:12:3 in main`.
+    fn parse_summary(line: &str) -> Option<(Sanitizer, &str)> {
+        let summary = line.trim().strip_prefix(SUMMARY_PREFIX)?;
+        let (sanitizer_name, _rest) = summary.split_once(": ")?;
+
+        Some((Sanitizer::from_name(sanitizer_name)?, summary))
+    }
+
+    /// Returns whether the line labels the stack after it, e.g. `READ of size
+    /// 4 at 0x602000000010 thread T0` or `previously allocated by thread T0
+    /// here:`.
+    fn is_stack_label_line(line: &str) -> bool {
+        let line = line.trim();
+        let is_access = line.starts_with("READ of size ") || line.starts_with("WRITE of size ");
+        let is_labelled_stack = line.ends_with(':')
+            && [
+                " here:",
+                " allocated from:",
+                " by thread T",
+                " by main thread",
+            ]
+            .iter()
+            .any(|label_part| line.contains(label_part));
+
+        is_access || is_labelled_stack
+    }
+
+    /// Returns the kind from an error description, e.g. `heap-use-after-free`
+    /// from `heap-use-after-free on address 0x602000000010 at pc ..`.
+    fn kind_from_description(description: &str) -> &str {
+        [" on ", " (", ": "]
+            .iter()
+            .filter_map(|separator| description.find(separator))
+            .min()
+            .map(|kind_len| &description[..kind_len])
+            .unwrap_or(description)
+    }
+
+    /// Strips the `==4321==` process ID prefix from a line.
+    fn strip_pid_prefix(line: &str) -> &str {
+        line.strip_prefix("==")
+            .and_then(|rest| rest.split_once("=="))
+            .filter(|(pid, _rest)| pid.chars().all(|c| c.is_ascii_digit()))
+            .map(|(_pid, rest)| rest)
+            .unwrap_or(line)
+    }
+}
+
+impl Sanitizer {
+    /// Returns the name the sanitizer prints, e.g. `AddressSanitizer`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Address => "AddressSanitizer",
+            Self::Leak => "LeakSanitizer",
+            Self::Memory => "MemorySanitizer",
+            Self::Thread => "ThreadSanitizer",
+            Self::UndefinedBehavior => "UndefinedBehaviorSanitizer",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [
+            Self::Address,
+            Self::Leak,
+            Self::Memory,
+            Self::Thread,
+            Self::UndefinedBehavior,
+        ]
+        .into_iter()
+        .find(|sanitizer| sanitizer.name() == name)
+    }
+}
+
+impl SanitizerStack {
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    pub fn stacktrace(&self) -> &Stacktrace {
+        &self.stacktrace
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Frame;
+
+    use super::{Sanitizer, SanitizerReport};
+
+    #[test]
+    fn parses_address_sanitizer_report() {
+        let sanitizer_report = SanitizerReport::parse(
+            "\
+            =================================================================\n\
+            ==4321==ERROR: AddressSanitizer: heap-use-after-free on address 0x602000000010 at pc 0x4f5a2b\n\
+            READ of size 4 at 0x602000000010 thread T0\n\
+            \x20   #0 0x4f5a2b in main /src/app.c:12:3\n\
+            \x20   #1 0x7f3c21 in __libc_start_main (/lib/x86_64-linux-gnu/libc.so.6+0x21b96)\n\
+            \n\
+            0x602000000010 is located 0 bytes inside of 4-byte region [0x602000000010,0x602000000014)\n\
+            freed by thread T0 here:\n\
+            \x20   #0 0x4c0a1d in free (/app/a.out+0x4c0a1d)\n\
+            \x20   #1 0x4f5a01 in main /src/app.c:10:3\n\
+            \n\
+            previously allocated by thread T0 here:\n\
+            \x20   #0 0x4c0b2e in malloc (/app/a.out+0x4c0b2e)\n\
+            \x20   #1 0x4f59f4 in main /src/app.c:9:15\n\
+            \n\
+            SUMMARY: AddressSanitizer: heap-use-after-free /src/app.c:12:3 in main\n\
+            Shadow bytes around the buggy address:\n\
+            ",
+        )
+        .expect("Expected report to be parsed.");
+
+        assert_eq!(Sanitizer::Address, sanitizer_report.sanitizer());
+        assert_eq!(Some("heap-use-after-free"), sanitizer_report.kind());
+        assert_eq!(
+            Some("AddressSanitizer: heap-use-after-free /src/app.c:12:3 in main"),
+            sanitizer_report.summary()
+        );
+
+        let labels = sanitizer_report
+            .stacks()
+            .iter()
+            .map(|stack| stack.label())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                Some("READ of size 4 at 0x602000000010 thread T0"),
+                Some("freed by thread T0 here"),
+                Some("previously allocated by thread T0 here"),
+            ],
+            labels
+        );
+        assert_eq!(2, sanitizer_report.stacks()[1].stacktrace().sections.len());
+    }
+
+    #[test]
+    fn parses_thread_sanitizer_report() {
+        let sanitizer_report = SanitizerReport::parse(
+            "\
+            WARNING: ThreadSanitizer: data race (pid=9337)\n\
+            \x20 Write of size 4 at 0x7f1ab2c00000 by thread T1:\n\
+            \x20   #0 Thread1 race.c:4 (a.out+0xa360)\n\
+            \n\
+            \x20 Previous write of size 4 at 0x7f1ab2c00000 by main thread:\n\
+            \x20   #0 main race.c:10 (a.out+0xa3b4)\n\
+            \n\
+            SUMMARY: ThreadSanitizer: data race race.c:4 in Thread1\n\
+            ",
+        )
+        .expect("Expected report to be parsed.");
+
+        assert_eq!(Sanitizer::Thread, sanitizer_report.sanitizer());
+        assert_eq!(Some("data race"), sanitizer_report.kind());
+        assert_eq!(2, sanitizer_report.stacks().len());
+        assert_eq!(
+            Some("Write of size 4 at 0x7f1ab2c00000 by thread T1"),
+            sanitizer_report.stacks()[0].label()
+        );
+    }
+
+    #[test]
+    fn parses_undefined_behavior_sanitizer_report() {
+        let sanitizer_report = SanitizerReport::parse(
+            "\
+            app.c:5:12: runtime error: signed integer overflow: 2147483647 + 1 cannot be represented in type 'int'\n\
+            \x20   #0 0x4f5a2b in main /src/app.c:5:12\n\
+            SUMMARY: UndefinedBehaviorSanitizer: undefined-behavior app.c:5:12 in\n\
+            ",
+        )
+        .expect("Expected report to be parsed.");
+
+        assert_eq!(Sanitizer::UndefinedBehavior, sanitizer_report.sanitizer());
+        assert_eq!(Some("signed integer overflow"), sanitizer_report.kind());
+        assert_eq!(1, sanitizer_report.stacks().len());
+    }
+
+    #[test]
+    fn parses_frame() {
+        let frame = SanitizerReport::parse_frame(
+            "    #2 0x4f5a2b in app::Store::load(int) /src/store.cc:42:5 (BuildId: 8f3a)",
+        );
+
+        let frame_expected = Frame {
+            index: Some(2),
+            symbol: Some(String::from("app::Store::load")),
+            module_path: vec![String::from("app"), String::from("Store")],
+            function: Some(String::from("load")),
+            arguments: Some(String::from("int")),
+            file_path: Some(String::from("/src/store.cc")),
+            line: Some(42),
+            column: Some(5),
+            address: Some(0x4f5a2b),
+            ..Frame::default()
+        };
+        assert_eq!(Some(frame_expected), frame);
+        assert_eq!(
+            None,
+            SanitizerReport::parse_frame(
+                "#1  0x0000555555555161 in app::Store::load (id=7) at src/store.cc:42"
+            )
+        );
+    }
+}
@@ -1,15 +1,65 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use crate::{section_tree::SectionTree, Frame, LineTrivia, SectionKind, SourceSpan, StyleSpan};
+
 /// A hierarchical structure of frames that have the same leading characters.
 ///
-/// Currently this doesn't cater for stack traces where each frame is prefixed
-/// with line numbers.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// Leading frame indices such as `  0: ` or `#3 ` are held separately in
+/// `slice_frame_index`, and are not used when comparing frames. For gdb and
+/// lldb backtraces, this includes the frame address.
+///
+/// Log prefixes such as `2026-10-17T10:00:01.123Z ERROR [pod-abc] ` are held
+/// separately in `slice_log_prefix`, and are also not used when comparing
+/// frames.
+///
+/// `Clone`, `Debug`, `PartialEq`, `Hash`, and `Drop` visit child sections
+/// iteratively, so deeply nested sections don't overflow the stack.
 pub struct Section {
     /// Identifier for the section.
     pub id: u32,
+    /// What the line of this section represents.
+    pub kind: SectionKind,
+    /// Slice of the line that was added by the logger, e.g.
+    /// `2026-10-17T10:00:01.123Z ERROR [pod-abc] `.
+    pub slice_log_prefix: String,
+    /// Slice of the line that holds the frame index, e.g. `  0: ` or `#3
+    /// 0x0000555555555161 in `.
+    pub slice_frame_index: String,
     /// Slice of the line that is common with previous frames.
     pub slice_common_with_previous_frames: String,
     /// Slice of the line that is not common with ancestors.
     pub slice_remainder: String,
+    /// Lines after this section's line that belong to the same frame, e.g.
+    /// `      at src/main.rs:12`.
+    ///
+    /// For header sections, these are lines that describe the header, e.g. the
+    /// register dump of a kernel oops.
+    ///
+    /// Log prefixes are not included.
+    pub continuation_lines: Vec<String>,
+    /// Styles from ANSI escape sequences in the section's text.
+    ///
+    /// The text is the log prefix, frame index, common slice, and remainder,
+    /// followed by each continuation line, separated by `\n`. This is empty if
+    /// the lines had no escape sequences.
+    pub style_spans: Vec<StyleSpan>,
+    /// Where the section's slices are in the stack trace string.
+    ///
+    /// For sections of a format's stack trace, such as
+    /// `JavaException::stacktrace`, this is relative to the string that was
+    /// parsed.
+    pub source_span: SourceSpan,
+    /// Text removed from the section's line and each continuation line before
+    /// parsing, such as line endings and the blank lines after them.
+    ///
+    /// For sections of a format's stack trace, a frame that shares its line
+    /// with other frames, such as in an Erlang error term, ends with `\n`.
+    pub line_trivia: Vec<LineTrivia>,
+    /// Structured information parsed from the line.
+    pub frame: Frame,
     /// Child `Section`s of this section.
     pub child_sections: Vec<Section>,
 }
@@ -19,6 +69,18 @@ impl Section {
         self.id
     }
 
+    pub fn kind(&self) -> SectionKind {
+        self.kind
+    }
+
+    pub fn slice_log_prefix(&self) -> &str {
+        &self.slice_log_prefix
+    }
+
+    pub fn slice_frame_index(&self) -> &str {
+        &self.slice_frame_index
+    }
+
     pub fn slice_common_with_previous_frames(&self) -> &str {
         &self.slice_common_with_previous_frames
     }
@@ -27,7 +89,200 @@ impl Section {
         &self.slice_remainder
     }
 
+    pub fn continuation_lines(&self) -> &[String] {
+        &self.continuation_lines
+    }
+
+    pub fn style_spans(&self) -> &[StyleSpan] {
+        &self.style_spans
+    }
+
+    pub fn source_span(&self) -> &SourceSpan {
+        &self.source_span
+    }
+
+    pub fn line_trivia(&self) -> &[LineTrivia] {
+        &self.line_trivia
+    }
+
+    pub fn frame(&self) -> &Frame {
+        &self.frame
+    }
+
     pub fn child_sections(&self) -> &[Section] {
         &self.child_sections
     }
+
+    /// Returns whether this section and its child sections are equal to the
+    /// other section's, other than their `source_span` and `line_trivia`.
+    ///
+    /// This is true for a section parsed from part of a stack trace and the
+    /// same section parsed on its own.
+    pub fn eq_ignoring_source(&self, other: &Section) -> bool {
+        self.eq_tree_ignoring_source(other)
+    }
+
+    /// Writes the section's line and continuation lines as they were parsed,
+    /// without its child sections.
+    pub(crate) fn write_lines(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let line_trivia_default = LineTrivia::default();
+        let line_trivia = |line_index: usize| {
+            self.line_trivia
+                .get(line_index)
+                .unwrap_or(&line_trivia_default)
+        };
+
+        line_trivia(0).write_line(
+            f,
+            &[
+                &self.slice_log_prefix,
+                &self.slice_frame_index,
+                &self.slice_common_with_previous_frames,
+                &self.slice_remainder,
+            ],
+        )?;
+        self.continuation_lines.iter().enumerate().try_for_each(
+            |(continuation_line_index, continuation_line)| {
+                let line_trivia = line_trivia(continuation_line_index + 1);
+                line_trivia.write_line(f, &[&line_trivia.log_prefix, continuation_line])
+            },
+        )
+    }
+}
+
+impl SectionTree for Section {
+    type Fields<'f> = (
+        u32,
+        SectionKind,
+        &'f str,
+        &'f str,
+        &'f str,
+        &'f str,
+        &'f [String],
+        &'f [StyleSpan],
+        &'f SourceSpan,
+        &'f [LineTrivia],
+        &'f Frame,
+    );
+    type FieldsIgnoringSource<'f> = (
+        u32,
+        SectionKind,
+        &'f str,
+        &'f str,
+        &'f str,
+        &'f str,
+        &'f [String],
+        &'f [StyleSpan],
+        &'f Frame,
+    );
+
+    const NAME: &'static str = "Section";
+
+    fn fields(&self) -> Self::Fields<'_> {
+        (
+            self.id,
+            self.kind,
+            &self.slice_log_prefix,
+            &self.slice_frame_index,
+            &self.slice_common_with_previous_frames,
+            &self.slice_remainder,
+            &self.continuation_lines,
+            &self.style_spans,
+            &self.source_span,
+            &self.line_trivia,
+            &self.frame,
+        )
+    }
+
+    fn fields_ignoring_source(&self) -> Self::FieldsIgnoringSource<'_> {
+        (
+            self.id,
+            self.kind,
+            &self.slice_log_prefix,
+            &self.slice_frame_index,
+            &self.slice_common_with_previous_frames,
+            &self.slice_remainder,
+            &self.continuation_lines,
+            &self.style_spans,
+            &self.frame,
+        )
+    }
+
+    fn debug_fields(&self) -> Vec<(&'static str, &dyn fmt::Debug)> {
+        vec![
+            ("id", &self.id),
+            ("kind", &self.kind),
+            ("slice_log_prefix", &self.slice_log_prefix),
+            ("slice_frame_index", &self.slice_frame_index),
+            (
+                "slice_common_with_previous_frames",
+                &self.slice_common_with_previous_frames,
+            ),
+            ("slice_remainder", &self.slice_remainder),
+            ("continuation_lines", &self.continuation_lines),
+            ("style_spans", &self.style_spans),
+            ("source_span", &self.source_span),
+            ("line_trivia", &self.line_trivia),
+            ("frame", &self.frame),
+        ]
+    }
+
+    fn child_sections(&self) -> &[Self] {
+        &self.child_sections
+    }
+
+    fn child_sections_mut(&mut self) -> &mut Vec<Self> {
+        &mut self.child_sections
+    }
+
+    fn clone_without_children(&self) -> Self {
+        Self {
+            id: self.id,
+            kind: self.kind,
+            slice_log_prefix: self.slice_log_prefix.clone(),
+            slice_frame_index: self.slice_frame_index.clone(),
+            slice_common_with_previous_frames: self.slice_common_with_previous_frames.clone(),
+            slice_remainder: self.slice_remainder.clone(),
+            continuation_lines: self.continuation_lines.clone(),
+            style_spans: self.style_spans.clone(),
+            source_span: self.source_span.clone(),
+            line_trivia: self.line_trivia.clone(),
+            frame: self.frame.clone(),
+            child_sections: Vec::new(),
+        }
+    }
+}
+
+impl Drop for Section {
+    /// Drops child sections iteratively, so that deeply nested sections don't
+    /// overflow the stack.
+    fn drop(&mut self) {
+        self.drop_child_sections();
+    }
+}
+
+impl Clone for Section {
+    fn clone(&self) -> Self {
+        self.clone_tree()
+    }
+}
+
+impl fmt::Debug for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_tree(f)
+    }
+}
+
+impl PartialEq for Section {
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_tree(other)
+    }
+}
+
+impl Eq for Section {}
+
+impl Hash for Section {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash_tree(state)
+    }
 }
@@ -0,0 +1,15 @@
+/// What the line of a `Section` represents.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SectionKind {
+    /// A stack frame, e.g. `at com.example.App.run(App.java:21)`.
+    #[default]
+    Frame,
+    /// A line that introduces the frames after it, e.g. `Caused by: ...`.
+    ///
+    /// The frames are nested within this section, and are not compared with
+    /// frames in other headers.
+    Header,
+    /// A line that ends the frames of the header it is nested in, e.g. the
+    /// exception line at the end of a Python traceback.
+    Footer,
+}
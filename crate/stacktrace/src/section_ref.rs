@@ -0,0 +1,342 @@
+use std::{
+    borrow::Cow,
+    fmt,
+    hash::{Hash, Hasher},
+    mem,
+};
+
+use crate::{
+    section_tree::SectionTree, Format, Frame, LineTriviaRef, Section, SectionKind, SourceSpan,
+    StyleSpan,
+};
+
+/// A `Section` whose slices borrow from the stack trace string.
+///
+/// Slices are only copied when the line had ANSI escape sequences, as the
+/// line without them is not part of the stack trace string. The same goes
+/// for the log prefixes in `line_trivia`. The `frame` is parsed from the
+/// slices when it is accessed, rather than stored.
+///
+/// Use [`SectionRef::into_owned`] to convert this into a `Section`.
+pub struct SectionRef<'s> {
+    /// Identifier for the section.
+    pub id: u32,
+    /// What the line of this section represents.
+    pub kind: SectionKind,
+    /// Slice of the line that was added by the logger, e.g.
+    /// `2026-10-17T10:00:01.123Z ERROR [pod-abc] `.
+    pub slice_log_prefix: Cow<'s, str>,
+    /// Slice of the line that holds the frame index, e.g. `  0: ` or `#3
+    /// 0x0000555555555161 in `.
+    pub slice_frame_index: Cow<'s, str>,
+    /// Slice of the line that is common with previous frames.
+    pub slice_common_with_previous_frames: Cow<'s, str>,
+    /// Slice of the line that is not common with ancestors.
+    pub slice_remainder: Cow<'s, str>,
+    /// Lines after this section's line that belong to the same frame, e.g.
+    /// `      at src/main.rs:12`.
+    ///
+    /// Log prefixes are not included.
+    pub continuation_lines: Vec<Cow<'s, str>>,
+    /// Styles from ANSI escape sequences in the section's text.
+    ///
+    /// See [`Section::style_spans`] for what the offsets are relative to.
+    pub style_spans: Vec<StyleSpan>,
+    /// Where the section's slices are in the stack trace string.
+    pub source_span: SourceSpan,
+    /// Text removed from the section's line and each continuation line before
+    /// parsing, such as line endings and the blank lines after them.
+    pub line_trivia: Vec<LineTriviaRef<'s>>,
+    /// Format the section was parsed in, used to parse its `frame` on access.
+    pub format: Format,
+    /// Child `SectionRef`s of this section.
+    pub child_sections: Vec<SectionRef<'s>>,
+}
+
+impl<'s> SectionRef<'s> {
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn kind(&self) -> SectionKind {
+        self.kind
+    }
+
+    pub fn slice_log_prefix(&self) -> &str {
+        &self.slice_log_prefix
+    }
+
+    pub fn slice_frame_index(&self) -> &str {
+        &self.slice_frame_index
+    }
+
+    pub fn slice_common_with_previous_frames(&self) -> &str {
+        &self.slice_common_with_previous_frames
+    }
+
+    pub fn slice_remainder(&self) -> &str {
+        &self.slice_remainder
+    }
+
+    pub fn continuation_lines(&self) -> &[Cow<'s, str>] {
+        &self.continuation_lines
+    }
+
+    pub fn style_spans(&self) -> &[StyleSpan] {
+        &self.style_spans
+    }
+
+    pub fn source_span(&self) -> &SourceSpan {
+        &self.source_span
+    }
+
+    pub fn line_trivia(&self) -> &[LineTriviaRef<'s>] {
+        &self.line_trivia
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Returns structured information parsed from the section's line and
+    /// continuation lines.
+    ///
+    /// This is parsed on each call rather than stored, so that sections don't
+    /// hold copies of the text in the frame.
+    pub fn frame(&self) -> Frame {
+        if self.kind != SectionKind::Frame {
+            return Frame::default();
+        }
+
+        let line = [
+            self.slice_frame_index.as_ref(),
+            &self.slice_common_with_previous_frames,
+            &self.slice_remainder,
+        ]
+        .concat();
+        self.continuation_lines.iter().fold(
+            self.format.parse_frame(&line),
+            |frame, continuation_line| {
+                self.format
+                    .frame_with_continuation_line(frame, &line, continuation_line)
+            },
+        )
+    }
+
+    pub fn child_sections(&self) -> &[SectionRef<'s>] {
+        &self.child_sections
+    }
+
+    /// Returns an owned `Section` with the same slices and child sections.
+    ///
+    /// Child sections are converted iteratively, so deeply nested sections
+    /// don't overflow the stack.
+    pub fn into_owned(mut self) -> Section {
+        let child_sections = mem::take(&mut self.child_sections);
+        let mut open_sections = vec![(self.take_without_children(), child_sections.into_iter())];
+
+        loop {
+            let child_section = open_sections
+                .last_mut()
+                .and_then(|(_section, child_sections)| child_sections.next());
+            match child_section {
+                Some(mut child_section) => {
+                    let grandchild_sections = mem::take(&mut child_section.child_sections);
+                    open_sections.push((
+                        child_section.take_without_children(),
+                        grandchild_sections.into_iter(),
+                    ));
+                }
+                None => {
+                    let (section, _child_sections) = open_sections
+                        .pop()
+                        .expect("Expected the section being converted to be open.");
+                    match open_sections.last_mut() {
+                        Some((parent, _child_sections)) => parent.child_sections.push(section),
+                        None => return section,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Moves this section's values into an owned `Section` without child
+    /// sections.
+    fn take_without_children(&mut self) -> Section {
+        // The frame is parsed from the slices, so it is parsed before they are taken.
+        let frame = self.frame();
+
+        Section {
+            id: self.id,
+            kind: self.kind,
+            slice_log_prefix: mem::take(&mut self.slice_log_prefix).into_owned(),
+            slice_frame_index: mem::take(&mut self.slice_frame_index).into_owned(),
+            slice_common_with_previous_frames: mem::take(
+                &mut self.slice_common_with_previous_frames,
+            )
+            .into_owned(),
+            slice_remainder: mem::take(&mut self.slice_remainder).into_owned(),
+            continuation_lines: mem::take(&mut self.continuation_lines)
+                .into_iter()
+                .map(Cow::into_owned)
+                .collect(),
+            style_spans: mem::take(&mut self.style_spans),
+            source_span: mem::take(&mut self.source_span),
+            line_trivia: mem::take(&mut self.line_trivia)
+                .into_iter()
+                .map(LineTriviaRef::into_owned)
+                .collect(),
+            frame,
+            child_sections: Vec::new(),
+        }
+    }
+}
+
+impl<'s> From<SectionRef<'s>> for Section {
+    fn from(section_ref: SectionRef<'s>) -> Self {
+        section_ref.into_owned()
+    }
+}
+
+impl<'s> SectionTree for SectionRef<'s> {
+    type Fields<'f>
+        = (
+        u32,
+        SectionKind,
+        &'f str,
+        &'f str,
+        &'f str,
+        &'f str,
+        &'f [Cow<'s, str>],
+        &'f [StyleSpan],
+        &'f SourceSpan,
+        &'f [LineTriviaRef<'s>],
+        Format,
+    )
+    where
+        Self: 'f;
+    type FieldsIgnoringSource<'f>
+        = (
+        u32,
+        SectionKind,
+        &'f str,
+        &'f str,
+        &'f str,
+        &'f str,
+        &'f [Cow<'s, str>],
+        &'f [StyleSpan],
+        Format,
+    )
+    where
+        Self: 'f;
+
+    const NAME: &'static str = "SectionRef";
+
+    fn fields(&self) -> Self::Fields<'_> {
+        (
+            self.id,
+            self.kind,
+            &self.slice_log_prefix,
+            &self.slice_frame_index,
+            &self.slice_common_with_previous_frames,
+            &self.slice_remainder,
+            &self.continuation_lines,
+            &self.style_spans,
+            &self.source_span,
+            &self.line_trivia,
+            self.format,
+        )
+    }
+
+    fn fields_ignoring_source(&self) -> Self::FieldsIgnoringSource<'_> {
+        (
+            self.id,
+            self.kind,
+            &self.slice_log_prefix,
+            &self.slice_frame_index,
+            &self.slice_common_with_previous_frames,
+            &self.slice_remainder,
+            &self.continuation_lines,
+            &self.style_spans,
+            self.format,
+        )
+    }
+
+    fn debug_fields(&self) -> Vec<(&'static str, &dyn fmt::Debug)> {
+        vec![
+            ("id", &self.id),
+            ("kind", &self.kind),
+            ("slice_log_prefix", &self.slice_log_prefix),
+            ("slice_frame_index", &self.slice_frame_index),
+            (
+                "slice_common_with_previous_frames",
+                &self.slice_common_with_previous_frames,
+            ),
+            ("slice_remainder", &self.slice_remainder),
+            ("continuation_lines", &self.continuation_lines),
+            ("style_spans", &self.style_spans),
+            ("source_span", &self.source_span),
+            ("line_trivia", &self.line_trivia),
+            ("format", &self.format),
+        ]
+    }
+
+    fn child_sections(&self) -> &[Self] {
+        &self.child_sections
+    }
+
+    fn child_sections_mut(&mut self) -> &mut Vec<Self> {
+        &mut self.child_sections
+    }
+
+    fn clone_without_children(&self) -> Self {
+        Self {
+            id: self.id,
+            kind: self.kind,
+            slice_log_prefix: self.slice_log_prefix.clone(),
+            slice_frame_index: self.slice_frame_index.clone(),
+            slice_common_with_previous_frames: self.slice_common_with_previous_frames.clone(),
+            slice_remainder: self.slice_remainder.clone(),
+            continuation_lines: self.continuation_lines.clone(),
+            style_spans: self.style_spans.clone(),
+            source_span: self.source_span.clone(),
+            line_trivia: self.line_trivia.clone(),
+            format: self.format,
+            child_sections: Vec::new(),
+        }
+    }
+}
+
+impl Drop for SectionRef<'_> {
+    /// Drops child sections iteratively, so that deeply nested sections don't
+    /// overflow the stack.
+    fn drop(&mut self) {
+        self.drop_child_sections();
+    }
+}
+
+impl Clone for SectionRef<'_> {
+    fn clone(&self) -> Self {
+        self.clone_tree()
+    }
+}
+
+impl fmt::Debug for SectionRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_tree(f)
+    }
+}
+
+impl PartialEq for SectionRef<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_tree(other)
+    }
+}
+
+impl Eq for SectionRef<'_> {}
+
+impl Hash for SectionRef<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash_tree(state)
+    }
+}
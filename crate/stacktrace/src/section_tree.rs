@@ -0,0 +1,321 @@
+use std::{
+    fmt::{self, Write},
+    hash::{Hash, Hasher},
+    mem,
+};
+
+/// A section with child sections of its own type, i.e. `Section` and
+/// `SectionRef`.
+///
+/// Sections are nested as deeply as frames have lines in common, so these
+/// methods visit child sections with an explicit stack rather than recursing,
+/// which would overflow the stack for deeply nested sections.
+pub(crate) trait SectionTree: Sized {
+    /// Name of the type in `Debug` output.
+    const NAME: &'static str;
+
+    /// Values that sections are compared and hashed by, other than their
+    /// child sections.
+    type Fields<'f>: Eq + Hash
+    where
+        Self: 'f;
+
+    /// `Fields` without the source span and line trivia.
+    type FieldsIgnoringSource<'f>: Eq
+    where
+        Self: 'f;
+
+    fn fields(&self) -> Self::Fields<'_>;
+
+    fn fields_ignoring_source(&self) -> Self::FieldsIgnoringSource<'_>;
+
+    /// Returns the name and value of each field other than `child_sections`,
+    /// in declaration order.
+    fn debug_fields(&self) -> Vec<(&'static str, &dyn fmt::Debug)>;
+
+    fn child_sections(&self) -> &[Self];
+
+    fn child_sections_mut(&mut self) -> &mut Vec<Self>;
+
+    /// Returns a copy of this section without its child sections.
+    fn clone_without_children(&self) -> Self;
+
+    /// Returns a copy of this section and its child sections.
+    fn clone_tree(&self) -> Self {
+        let mut open_sections = vec![(self.clone_without_children(), self.child_sections().iter())];
+
+        loop {
+            let child_section = open_sections
+                .last_mut()
+                .and_then(|(_section, child_sections)| child_sections.next());
+            match child_section {
+                Some(child_section) => open_sections.push((
+                    child_section.clone_without_children(),
+                    child_section.child_sections().iter(),
+                )),
+                None => {
+                    let (section, _child_sections) = open_sections
+                        .pop()
+                        .expect("Expected the section being cloned to be open.");
+                    match open_sections.last_mut() {
+                        Some((parent, _child_sections)) => {
+                            parent.child_sections_mut().push(section)
+                        }
+                        None => return section,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns whether this section and its child sections are equal to the
+    /// other section's.
+    fn eq_tree(&self, other: &Self) -> bool {
+        self.eq_tree_by(other, |section, other| section.fields() == other.fields())
+    }
+
+    /// Returns whether this section and its child sections are equal to the
+    /// other section's, other than their source spans and line trivia.
+    fn eq_tree_ignoring_source(&self, other: &Self) -> bool {
+        self.eq_tree_by(other, |section, other| {
+            section.fields_ignoring_source() == other.fields_ignoring_source()
+        })
+    }
+
+    /// Returns whether this section and its child sections are equal to the
+    /// other section's, comparing each pair of sections with `fields_eq`.
+    fn eq_tree_by<F>(&self, other: &Self, fields_eq: F) -> bool
+    where
+        F: Fn(&Self, &Self) -> bool,
+    {
+        let mut section_pairs = vec![(self, other)];
+        while let Some((section, other)) = section_pairs.pop() {
+            if section.child_sections().len() != other.child_sections().len()
+                || !fields_eq(section, other)
+            {
+                return false;
+            }
+            section_pairs.extend(section.child_sections().iter().zip(other.child_sections()));
+        }
+
+        true
+    }
+
+    /// Hashes this section and its child sections, in the same order as a
+    /// derived `Hash` would.
+    fn hash_tree<H: Hasher>(&self, state: &mut H) {
+        let mut sections = vec![self];
+        while let Some(section) = sections.pop() {
+            section.fields().hash(state);
+            section.child_sections().len().hash(state);
+            sections.extend(section.child_sections().iter().rev());
+        }
+    }
+
+    /// Writes this section and its child sections in the same format as a
+    /// derived `Debug` would.
+    fn fmt_tree(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let alternate = f.alternate();
+        let mut steps = vec![DebugStep::Section(self, 0)];
+        while let Some(step) = steps.pop() {
+            match step {
+                DebugStep::Section(section, depth) => {
+                    let field_indent = depth * 8 + 4;
+                    if alternate {
+                        writeln!(f, "{} {{", Self::NAME)?;
+                        for (name, value) in section.debug_fields() {
+                            write!(f, "{:field_indent$}{name}: ", "")?;
+                            let mut indented = Indented {
+                                f,
+                                indent: field_indent,
+                            };
+                            write!(indented, "{value:#?}")?;
+                            f.write_str(",\n")?;
+                        }
+                        write!(f, "{:field_indent$}child_sections: [", "")?;
+                    } else {
+                        write!(f, "{} {{ ", Self::NAME)?;
+                        for (name, value) in section.debug_fields() {
+                            write!(f, "{name}: {value:?}, ")?;
+                        }
+                        f.write_str("child_sections: [")?;
+                    }
+
+                    let child_sections = section.child_sections();
+                    steps.push(DebugStep::Close(depth, !child_sections.is_empty()));
+                    child_sections.iter().enumerate().rev().for_each(
+                        |(child_index, child_section)| {
+                            steps.push(DebugStep::ChildEnd);
+                            steps.push(DebugStep::Section(child_section, depth + 1));
+                            steps.push(DebugStep::ChildStart(depth + 1, child_index == 0));
+                        },
+                    );
+                }
+                DebugStep::ChildStart(depth, is_first) => {
+                    if alternate {
+                        write!(f, "\n{:indent$}", "", indent = depth * 8)?;
+                    } else if !is_first {
+                        f.write_str(", ")?;
+                    }
+                }
+                DebugStep::ChildEnd => {
+                    if alternate {
+                        f.write_str(",")?;
+                    }
+                }
+                DebugStep::Close(depth, has_child_sections) => {
+                    if alternate {
+                        if has_child_sections {
+                            write!(f, "\n{:indent$}", "", indent = depth * 8 + 4)?;
+                        }
+                        write!(f, "],\n{:indent$}}}", "", indent = depth * 8)?;
+                    } else {
+                        f.write_str("] }")?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Drops this section's child sections iteratively.
+    ///
+    /// Each child section's own child sections are taken out before it is
+    /// dropped, so its `Drop` has nothing left to recurse into.
+    fn drop_child_sections(&mut self) {
+        let mut child_sections = mem::take(self.child_sections_mut());
+        while let Some(mut section) = child_sections.pop() {
+            child_sections.append(section.child_sections_mut());
+        }
+    }
+}
+
+/// Part of a section's `Debug` output that is yet to be written.
+enum DebugStep<'s, S> {
+    /// The section at the given depth, up to its child sections.
+    Section(&'s S, usize),
+    /// Indentation before the child section at the given depth.
+    ChildStart(usize, bool),
+    /// Separator after a child section.
+    ChildEnd,
+    /// End of the child sections and the section at the given depth.
+    Close(usize, bool),
+}
+
+/// Writer that indents each line after the first, for multi-line field values.
+struct Indented<'f, 'w> {
+    f: &'f mut fmt::Formatter<'w>,
+    indent: usize,
+}
+
+impl Write for Indented<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut lines = s.split('\n');
+        if let Some(line) = lines.next() {
+            self.f.write_str(line)?;
+        }
+        lines.try_for_each(|line| write!(self.f, "\n{:indent$}{line}", "", indent = self.indent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Format, Stacktrace};
+
+    use super::SectionTree;
+
+    /// Mirror of `Section` with derived `Debug`, to compare output with.
+    mod derived {
+        use crate::{Frame, LineTrivia, SectionKind, SourceSpan, StyleSpan};
+
+        #[derive(Debug)]
+        #[allow(dead_code)]
+        pub struct Section {
+            pub id: u32,
+            pub kind: SectionKind,
+            pub slice_log_prefix: String,
+            pub slice_frame_index: String,
+            pub slice_common_with_previous_frames: String,
+            pub slice_remainder: String,
+            pub continuation_lines: Vec<String>,
+            pub style_spans: Vec<StyleSpan>,
+            pub source_span: SourceSpan,
+            pub line_trivia: Vec<LineTrivia>,
+            pub frame: Frame,
+            pub child_sections: Vec<Section>,
+        }
+
+        impl From<&crate::Section> for Section {
+            fn from(section: &crate::Section) -> Self {
+                Self {
+                    id: section.id,
+                    kind: section.kind,
+                    slice_log_prefix: section.slice_log_prefix.clone(),
+                    slice_frame_index: section.slice_frame_index.clone(),
+                    slice_common_with_previous_frames: section
+                        .slice_common_with_previous_frames
+                        .clone(),
+                    slice_remainder: section.slice_remainder.clone(),
+                    continuation_lines: section.continuation_lines.clone(),
+                    style_spans: section.style_spans.clone(),
+                    source_span: section.source_span.clone(),
+                    line_trivia: section.line_trivia.clone(),
+                    frame: section.frame.clone(),
+                    child_sections: section.child_sections.iter().map(Self::from).collect(),
+                }
+            }
+        }
+    }
+
+    fn stacktrace() -> Stacktrace {
+        Stacktrace::from_format(
+            "\
+            app::main\n\
+            app::server::run\n\
+            \x20     at src/server.rs:12\n\
+            app::server::handle\n\
+            app::client::send\n\
+            core::ops::call",
+            Format::Rust,
+        )
+    }
+
+    #[test]
+    fn debug_output_matches_derived_debug_output() {
+        let stacktrace = stacktrace();
+        let sections_derived = stacktrace
+            .sections
+            .iter()
+            .map(derived::Section::from)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            format!("{sections_derived:?}"),
+            format!("{:?}", stacktrace.sections)
+        );
+        assert_eq!(
+            format!("{sections_derived:#?}"),
+            format!("{:#?}", stacktrace.sections)
+        );
+    }
+
+    #[test]
+    fn clone_tree_is_equal_to_original() {
+        let stacktrace = stacktrace();
+        let [main, _core] = stacktrace.sections.as_slice() else {
+            panic!("Expected two top level sections.");
+        };
+
+        let main_clone = main.clone_tree();
+
+        assert!(main.eq_tree(&main_clone));
+        assert!(!main.eq_tree(&main.child_sections()[0]));
+
+        let mut main_without_grandchild = main.clone();
+        main_without_grandchild.child_sections[0]
+            .child_sections
+            .clear();
+        assert_ne!(main, &main_without_grandchild);
+    }
+}
@@ -0,0 +1,39 @@
+use std::ops::Range;
+
+/// Where a section came from in the stack trace string.
+///
+/// Byte ranges include ANSI escape sequences that were removed before parsing,
+/// so they can be used to highlight the original text.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SourceSpan {
+    /// Line number of the section's line, starting from `1`.
+    pub line_number: usize,
+    /// Byte range of the section's line and continuation lines, which the
+    /// section's `frame` is parsed from.
+    ///
+    /// This includes the log prefix, and excludes the last line ending.
+    pub lines: Range<usize>,
+    /// Byte range of the slice of the line that is common with previous
+    /// frames.
+    pub common: Range<usize>,
+    /// Byte range of the slice of the line that is not common with ancestors.
+    pub remainder: Range<usize>,
+}
+
+impl SourceSpan {
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    pub fn lines(&self) -> Range<usize> {
+        self.lines.clone()
+    }
+
+    pub fn common(&self) -> Range<usize> {
+        self.common.clone()
+    }
+
+    pub fn remainder(&self) -> Range<usize> {
+        self.remainder.clone()
+    }
+}
@@ -1,123 +1,833 @@
-use std::{cmp::Ordering, iter::Peekable, str::Lines};
+use std::{borrow::Cow, cmp::Ordering, fmt, io, iter::Peekable, ops::Range};
 
-use crate::Section;
+use crate::{
+    AndroidCrash, BlankLineRef, Diagnostic, EscapeSequenceRef, Format, FormatDetection,
+    GroupingKey, LineEnding, LineTriviaRef, ParseError, ParseOptions, Section, SectionKind,
+    SectionRef, SourceSpan, StacktraceRef, StyleSpan,
+};
+
+/// Log levels that may be in a log prefix, e.g. `ERROR` or `[ERROR]`.
+const LOG_LEVELS: [&str; 9] = [
+    "TRACE", "DEBUG", "INFO", "NOTICE", "WARN", "WARNING", "ERROR", "FATAL", "CRITICAL",
+];
+
+/// Tokens that separate parts of a log prefix, e.g. `-` in `10:00:01 - ERROR`.
+const LOG_PREFIX_SEPARATORS: [&str; 2] = ["-", "|"];
 
 /// Parses a stack trace string into a structured stack trace.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Stacktrace {
     pub sections: Vec<Section>,
+    /// Problems with individual lines, such as unrecognised lines.
+    ///
+    /// This is only filled in by [`Stacktrace::parse_str`].
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 impl Stacktrace {
-    fn parse(
-        lines: &mut Peekable<Lines>,
+    /// Returns the format that the stack trace is most likely in.
+    ///
+    /// See [`Stacktrace::detect_format_with_confidence`] for how the format is
+    /// chosen.
+    pub fn detect_format(s: &str) -> Format {
+        Self::detect_format_with_confidence(s).format
+    }
+
+    /// Returns the format that the stack trace is most likely in, along with
+    /// its confidence.
+    ///
+    /// Each format is scored by [`Format::confidence`], and the most confident
+    /// format is returned. When formats are equally confident, the one listed
+    /// first in [`Format::ALL`] is returned. If no format recognises any line,
+    /// [`Format::Generic`] is returned with a confidence of `0.0`.
+    ///
+    /// ANSI escape sequences and log prefixes are removed before the lines are
+    /// recognised.
+    pub fn detect_format_with_confidence(s: &str) -> FormatDetection {
+        let s = Self::without_escapes_and_log_prefixes(s);
+        let s = s.as_ref();
+
+        Format::ALL
+            .into_iter()
+            .map(|format| FormatDetection {
+                format,
+                confidence: format.confidence(s),
+            })
+            .fold(
+                FormatDetection {
+                    format: Format::Generic,
+                    confidence: 0.0,
+                },
+                |format_detection_best, format_detection| {
+                    if format_detection.confidence > format_detection_best.confidence {
+                        format_detection
+                    } else {
+                        format_detection_best
+                    }
+                },
+            )
+    }
+
+    /// Parses a stack trace string, returning an error if no line is
+    /// recognised.
+    ///
+    /// Unlike `Stacktrace::from`, problems with individual lines, such as
+    /// unrecognised lines, truncated frames, and frames in a different format,
+    /// are recorded in `diagnostics`.
+    pub fn parse_str(s: &str, parse_options: &ParseOptions) -> Result<Self, ParseError> {
+        let s_stripped = Self::without_escapes_and_log_prefixes(s);
+        if s_stripped.trim().is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        let format = parse_options
+            .format
+            .unwrap_or_else(|| Self::detect_format(s));
+        let (diagnostics, recognised_count) = Diagnostic::collect(&s_stripped, format);
+        if recognised_count == 0 {
+            return Err(ParseError::Unrecognised { diagnostics });
+        }
+
+        let parse_options = parse_options.with_format_defaults(format);
+        Ok(Self {
+            diagnostics,
+            ..Self::from_format_with_options(s, format, &parse_options)
+        })
+    }
+
+    /// Parses a stack trace string in the given format.
+    ///
+    /// `Stacktrace::from` detects the format with [`Stacktrace::detect_format`]
+    /// before calling this.
+    ///
+    /// If most lines begin with a log prefix, such as
+    /// `2026-10-17T10:00:01.123Z ERROR [pod-abc] `, the prefix is removed
+    /// before parsing, and kept in each section's `slice_log_prefix`.
+    ///
+    /// Logcat prefixes of Android crashes, such as `E AndroidRuntime: `, are
+    /// also kept in `slice_log_prefix`.
+    ///
+    /// ANSI escape sequences, such as colours from `color-eyre`, are removed
+    /// before the log prefix, and the colours are kept in each section's
+    /// `style_spans`.
+    pub fn from_format(s: &str, format: Format) -> Self {
+        Self::from_format_with_options(s, format, &ParseOptions::for_format(format))
+    }
+
+    /// Returns whether the stack traces have the same sections, other than
+    /// their `source_span` and `line_trivia`.
+    ///
+    /// See [`Section::eq_ignoring_source`].
+    pub fn eq_ignoring_source(&self, other: &Stacktrace) -> bool {
+        self.sections.len() == other.sections.len()
+            && self
+                .sections
+                .iter()
+                .zip(other.sections.iter())
+                .all(|(section, other_section)| section.eq_ignoring_source(other_section))
+    }
+
+    /// Returns the id of the section whose lines contain the given byte offset
+    /// in the stack trace string.
+    ///
+    /// Returns `None` if the offset is in a line ending, or past the end of the
+    /// string.
+    pub fn section_id_at(&self, offset: usize) -> Option<u32> {
+        let mut sections = self.sections.iter().collect::<Vec<_>>();
+        while let Some(section) = sections.pop() {
+            if section.source_span.lines.contains(&offset) {
+                return Some(section.id);
+            }
+            sections.extend(section.child_sections.iter());
+        }
+
+        None
+    }
+
+    /// Writes the stack trace as the text it was parsed from.
+    ///
+    /// This is the same as the `Display` output, which includes log prefixes,
+    /// ANSI escape sequences, and line endings.
+    pub fn write_to<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "{self}")
+    }
+
+    /// Parses a stack trace string in the given format, with options that have
+    /// been filled in with the format's defaults.
+    fn from_format_with_options(s: &str, format: Format, parse_options: &ParseOptions) -> Self {
+        Self::ref_from_format_with_options(s, format, parse_options).into_owned()
+    }
+
+    /// Parses a stack trace string in the given format into sections that
+    /// borrow from the string, with options that have been filled in with the
+    /// format's defaults.
+    pub(crate) fn ref_from_format_with_options<'s>(
+        s: &'s str,
+        format: Format,
+        parse_options: &ParseOptions,
+    ) -> StacktraceRef<'s> {
+        let lines_and_style_spans = s.lines().map(StyleSpan::strip_escapes).collect::<Vec<_>>();
+        let log_prefixes_and_lines = Self::split_log_prefixes(
+            lines_and_style_spans
+                .iter()
+                .map(|(line, _style_spans, _escape_ranges)| line.as_ref()),
+            format,
+        );
+        let lines_unstripped = s.split_inclusive('\n').zip(s.lines()).scan(
+            0,
+            |line_start, (line_with_ending, line)| {
+                let line_range = *line_start..*line_start + line.len();
+                *line_start += line_with_ending.len();
+                Some((line, line_range, LineEnding::of(line_with_ending)))
+            },
+        );
+        let lines = log_prefixes_and_lines
+            .into_iter()
+            .zip(lines_and_style_spans.iter())
+            .zip(lines_unstripped)
+            .enumerate()
+            .map(
+                |(line_index, (((log_prefix, line), line_stripped), line_unstripped))| {
+                    let (line_stripped, style_spans, escape_ranges) = line_stripped;
+                    let (line_unstripped, line_range, line_ending) = line_unstripped;
+                    // Lines without escape sequences are part of `s`, so their slices are
+                    // borrowed from it.
+                    let line_input = match line_stripped {
+                        Cow::Borrowed(line_input) => Some(*line_input),
+                        Cow::Owned(_) => None,
+                    };
+                    StrippedLine {
+                        log_prefix,
+                        line,
+                        style_spans,
+                        line_input,
+                        line_unstripped,
+                        line_number: line_index + 1,
+                        line_start: line_range.start,
+                        line_end: line_range.end,
+                        line_ending,
+                        escape_ranges,
+                    }
+                },
+            );
+
+        Self::ref_from_stripped_lines(lines, format, parse_options)
+    }
+
+    /// Builds a `Stacktrace` from the given lines, which are slices of `s` in
+    /// the order they appear in it.
+    ///
+    /// Source spans are relative to `s`, so [`Stacktrace::section_id_at`] can
+    /// be used with offsets in it. A line that is followed by more text on the
+    /// same line of `s`, such as one of several frames logged on one line, is
+    /// written out with a `\n` line ending.
+    pub(crate) fn from_lines<'s, I>(s: &'s str, lines: I, format: Format) -> Self
+    where
+        I: IntoIterator<Item = &'s str>,
+    {
+        let mut line_number = 1;
+        let mut line_number_offset = 0;
+        let lines = lines.into_iter().map(move |line| {
+            let line_start = (line.as_ptr() as usize)
+                .checked_sub(s.as_ptr() as usize)
+                .filter(|line_start| line_start + line.len() <= s.len())
+                .expect("Expected frame line to be a slice of the stack trace string.");
+            let line_end = line_start + line.len();
+            line_number += s[line_number_offset..line_start].matches('\n').count();
+            line_number_offset = line_start;
+
+            let rest = &s[line_end..];
+            let line_ending = if rest.starts_with("\r\n") {
+                LineEnding::CrLf
+            } else if rest.is_empty() {
+                LineEnding::None
+            } else {
+                LineEnding::Lf
+            };
+
+            StrippedLine {
+                log_prefix: "",
+                line,
+                style_spans: &[],
+                line_input: Some(line),
+                line_unstripped: line,
+                line_number,
+                line_start,
+                line_end,
+                line_ending,
+                escape_ranges: &[],
+            }
+        });
+
+        Self::ref_from_stripped_lines(lines, format, &ParseOptions::for_format(format)).into_owned()
+    }
+
+    /// Builds a `StacktraceRef` from lines whose escape sequences and log
+    /// prefixes have been removed.
+    fn ref_from_stripped_lines<'l, 's, I>(
+        lines: I,
+        format: Format,
+        parse_options: &ParseOptions,
+    ) -> StacktraceRef<'s>
+    where
+        I: IntoIterator<Item = StrippedLine<'l, 's>>,
+    {
+        let mut lines = lines.into_iter().peekable();
+        let sections = Self::parse_headers(&mut lines, format, parse_options, &mut 0);
+
+        StacktraceRef { sections }
+    }
+
+    /// Splits the log prefix from each line, if most non-blank lines have one.
+    ///
+    /// A log prefix is a timestamp, optionally with log levels and bracketed
+    /// tags, e.g. `2026-10-17T10:00:01.123Z ERROR [pod-abc] `. Lines without a
+    /// log prefix have an empty prefix.
+    ///
+    /// For Android crashes, the logcat prefix after the log prefix, e.g. `E
+    /// AndroidRuntime: `, is part of the log prefix.
+    fn split_log_prefixes<'s, I>(lines: I, format: Format) -> Vec<(&'s str, &'s str)>
+    where
+        I: IntoIterator<Item = &'s str>,
+    {
+        let lines = lines
+            .into_iter()
+            .map(|line| (Self::log_prefix_len(line), line))
+            .collect::<Vec<_>>();
+
+        let line_count = lines
+            .iter()
+            .filter(|(_log_prefix_len, line)| !line.trim().is_empty())
+            .count();
+        let log_prefix_count = lines
+            .iter()
+            .filter(|(log_prefix_len, _line)| log_prefix_len.is_some())
+            .count();
+        let is_log = log_prefix_count * 2 > line_count;
+
+        lines
+            .into_iter()
+            .map(|(log_prefix_len, line)| {
+                let log_prefix_len = match log_prefix_len {
+                    Some(log_prefix_len) if is_log => log_prefix_len,
+                    _ => 0,
+                };
+                let logcat_prefix_len = match format {
+                    Format::Android => {
+                        let (logcat_prefix, _rest) =
+                            AndroidCrash::split_logcat_prefix(&line[log_prefix_len..]);
+                        logcat_prefix.len()
+                    }
+                    _ => 0,
+                };
+
+                line.split_at(log_prefix_len + logcat_prefix_len)
+            })
+            .collect()
+    }
+
+    /// Returns the string with ANSI escape sequences removed, and log prefixes
+    /// removed if most lines have one.
+    fn without_escapes_and_log_prefixes(s: &str) -> Cow<'_, str> {
+        let lines = s
+            .lines()
+            .map(|line| StyleSpan::strip_escapes(line).0)
+            .collect::<Vec<_>>();
+        let log_prefixes_and_lines =
+            Self::split_log_prefixes(lines.iter().map(|line| line.as_ref()), Format::Generic);
+
+        let has_escapes = s.contains('\x1b');
+        let has_log_prefixes = log_prefixes_and_lines
+            .iter()
+            .any(|(log_prefix, _line)| !log_prefix.is_empty());
+        if !has_escapes && !has_log_prefixes {
+            Cow::Borrowed(s)
+        } else {
+            Cow::Owned(
+                log_prefixes_and_lines
+                    .into_iter()
+                    .map(|(_log_prefix, line)| line)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+        }
+    }
+
+    /// Returns the style spans of a section's text, which is its line followed
+    /// by its continuation lines, separated by `\n`.
+    fn section_style_spans(
+        stripped_line: StrippedLine<'_, '_>,
+        continuation_lines: &[StrippedLine<'_, '_>],
+    ) -> Vec<StyleSpan> {
+        let mut style_spans = stripped_line.style_spans.to_vec();
+        let mut text_len = stripped_line.log_prefix.len() + stripped_line.line.len();
+
+        // Continuation lines are kept without their log prefix.
+        continuation_lines.iter().for_each(|continuation_line| {
+            style_spans.extend(StyleSpan::offset_spans(
+                continuation_line.style_spans,
+                continuation_line.log_prefix.len(),
+                text_len + 1,
+            ));
+            text_len += 1 + continuation_line.line.len();
+        });
+
+        style_spans
+    }
+
+    /// Consumes the continuation lines after the section's line, returning
+    /// them along with the text removed from each of the section's lines
+    /// before parsing.
+    ///
+    /// Blank lines after each line are consumed as well, and kept in that
+    /// line's trivia.
+    fn take_section_lines<'l, 's, I>(
+        lines: &mut Peekable<I>,
+        stripped_line: StrippedLine<'l, 's>,
+        is_continuation_line: impl Fn(&str) -> bool,
+    ) -> (Vec<StrippedLine<'l, 's>>, Vec<LineTriviaRef<'s>>)
+    where
+        I: Iterator<Item = StrippedLine<'l, 's>>,
+    {
+        // The section's own log prefix is kept in `slice_log_prefix`.
+        let mut line_trivia = vec![LineTriviaRef {
+            log_prefix: Cow::Borrowed(""),
+            ..stripped_line.line_trivia()
+        }];
+        let mut continuation_lines = Vec::new();
+
+        loop {
+            if let Some(line_trivia) = line_trivia.last_mut() {
+                while let Some(blank_line) = lines.next_if(|line| line.is_blank()) {
+                    line_trivia.blank_lines.push(blank_line.blank_line());
+                }
+            }
+
+            match lines.next_if(|continuation_line| is_continuation_line(continuation_line.line)) {
+                Some(continuation_line) => {
+                    continuation_lines.push(continuation_line);
+                    line_trivia.push(continuation_line.line_trivia());
+                }
+                None => return (continuation_lines, line_trivia),
+            }
+        }
+    }
+
+    /// Returns where the section is in the stack trace string, given the
+    /// ranges of its common and remainder slices in the line after the log
+    /// prefix.
+    fn section_source_span(
+        stripped_line: StrippedLine<'_, '_>,
+        continuation_lines: &[StrippedLine<'_, '_>],
+        common: Range<usize>,
+        remainder: Range<usize>,
+    ) -> SourceSpan {
+        let lines_end = continuation_lines
+            .last()
+            .map_or(stripped_line.line_end, |continuation_line| {
+                continuation_line.line_end
+            });
+
+        SourceSpan {
+            line_number: stripped_line.line_number,
+            lines: stripped_line.line_start..lines_end,
+            common: stripped_line.source_range(common),
+            remainder: stripped_line.source_range(remainder),
+        }
+    }
+
+    /// Returns the length of the log prefix at the start of the line, if any.
+    ///
+    /// The prefix must contain a timestamp, so that frame indices and
+    /// bracketed frame addresses are not mistaken for log prefixes. Only one
+    /// whitespace character after the prefix is included, so the indentation of
+    /// the line is kept.
+    fn log_prefix_len(line: &str) -> Option<usize> {
+        let mut rest = line;
+        let mut has_timestamp = false;
+
+        loop {
+            let rest_trimmed = match rest.len() == line.len() {
+                true => rest,
+                false => rest.trim_start(),
+            };
+            let token_len = match rest_trimmed.strip_prefix('[') {
+                Some(tag) => tag.find(']').map(|tag_len| tag_len + "[]".len()),
+                None => rest_trimmed.find(char::is_whitespace),
+            };
+            let Some(token_len) = token_len else {
+                break;
+            };
+
+            let token = &rest_trimmed[..token_len];
+            let is_timestamp = Self::is_log_timestamp(token);
+            let is_log_token = is_timestamp
+                || token.starts_with('[')
+                || LOG_LEVELS.contains(&token.strip_suffix(':').unwrap_or(token))
+                || LOG_PREFIX_SEPARATORS.contains(&token);
+            let rest_after_token = &rest_trimmed[token_len..];
+            let Some(whitespace) = rest_after_token
+                .chars()
+                .next()
+                .filter(|c| is_log_token && c.is_whitespace())
+            else {
+                break;
+            };
+
+            has_timestamp |= is_timestamp;
+            rest = &rest_after_token[whitespace.len_utf8()..];
+        }
+
+        has_timestamp.then_some(line.len() - rest.len())
+    }
+
+    /// Returns whether the token is a date or time, e.g. `2026-10-17`,
+    /// `10:00:01,123`, or `2026-10-17T10:00:01.123Z`.
+    fn is_log_timestamp(token: &str) -> bool {
+        let is_timestamp_chars = token.starts_with(|c: char| c.is_ascii_digit())
+            && token
+                .chars()
+                .all(|c| c.is_ascii_digit() || "-:.,/TZ+".contains(c));
+        let is_date = token.matches('-').count() >= 2 || token.matches('/').count() >= 2;
+        let is_time = token.contains(':')
+            && token
+                .split(':')
+                .all(|part| part.starts_with(|c: char| c.is_ascii_digit()));
+
+        is_timestamp_chars && (is_date || is_time)
+    }
+
+    /// Parses header lines, with the frames after each header nested within
+    /// it.
+    ///
+    /// Frames before the first header are parsed as top level sections. A
+    /// footer line after a header's frames, such as a Python traceback's
+    /// exception line, is nested within the header after its frames.
+    fn parse_headers<'l, 's, I>(
+        lines: &mut Peekable<I>,
+        format: Format,
+        parse_options: &ParseOptions,
         next_id: &mut u32,
-        previous_section_info: Option<PreviousSectionInfo<'_>>,
-    ) -> Vec<Section> {
+    ) -> Vec<SectionRef<'s>>
+    where
+        I: Iterator<Item = StrippedLine<'l, 's>>,
+    {
+        let mut sections = Vec::new();
+
+        while let Some(stripped_line) = lines.peek().copied() {
+            let line = stripped_line.line;
+            if !format.is_header_line(line) {
+                sections.extend(Self::parse(lines, format, parse_options, next_id, None));
+                continue;
+            }
+
+            // consume the line because we are starting a new `Section`.
+            lines.next();
+
+            // Lines such as a kernel oops' register dump describe the header, so they
+            // shouldn't be parsed as frames.
+            let mut section = Self::parse_header_or_footer(
+                lines,
+                stripped_line,
+                SectionKind::Header,
+                format,
+                next_id,
+                |continuation_line| format.is_header_continuation_line(continuation_line),
+            );
+            section.child_sections = Self::parse(lines, format, parse_options, next_id, Some(line));
+
+            if let Some(footer_line) =
+                lines.next_if(|footer_line| format.is_footer_line(line, footer_line.line))
+            {
+                section.child_sections.push(Self::parse_header_or_footer(
+                    lines,
+                    footer_line,
+                    SectionKind::Footer,
+                    format,
+                    next_id,
+                    |continuation_line| format.is_footer_continuation_line(continuation_line),
+                ));
+            }
+            sections.push(section);
+        }
+
+        sections
+    }
+
+    /// Parses a header or footer line, and the lines after it that belong to
+    /// it, into a section without child sections.
+    fn parse_header_or_footer<'l, 's, I>(
+        lines: &mut Peekable<I>,
+        stripped_line: StrippedLine<'l, 's>,
+        kind: SectionKind,
+        format: Format,
+        next_id: &mut u32,
+        is_continuation_line: impl Fn(&str) -> bool,
+    ) -> SectionRef<'s>
+    where
+        I: Iterator<Item = StrippedLine<'l, 's>>,
+    {
+        let line = stripped_line.line;
+        let (continuation_lines, line_trivia) =
+            Self::take_section_lines(lines, stripped_line, is_continuation_line);
+        let style_spans = Self::section_style_spans(stripped_line, &continuation_lines);
+        let source_span =
+            Self::section_source_span(stripped_line, &continuation_lines, 0..0, 0..line.len());
+
+        let section_id = *next_id;
+        *next_id += 1;
+
+        SectionRef {
+            id: section_id,
+            kind,
+            slice_log_prefix: stripped_line.log_prefix_slice(),
+            slice_frame_index: Cow::Borrowed(""),
+            slice_common_with_previous_frames: Cow::Borrowed(""),
+            slice_remainder: stripped_line.line_slice(0..line.len()),
+            continuation_lines: continuation_lines
+                .into_iter()
+                .map(|continuation_line| {
+                    continuation_line.line_slice(0..continuation_line.line.len())
+                })
+                .collect(),
+            style_spans,
+            source_span,
+            line_trivia,
+            format,
+            child_sections: Vec::new(),
+        }
+    }
+
+    /// Parses frames until a header line, or a footer line of the header on
+    /// `header_line`, nesting each frame within the previous frame when it
+    /// has a longer slice in common with it.
+    ///
+    /// Sections that are still receiving child sections are held on an
+    /// explicit stack instead of the call stack, so deeply nested stack traces
+    /// are bounded only by memory.
+    fn parse<'l, 's, I>(
+        lines: &mut Peekable<I>,
+        format: Format,
+        parse_options: &ParseOptions,
+        next_id: &mut u32,
+        header_line: Option<&str>,
+    ) -> Vec<SectionRef<'s>>
+    where
+        I: Iterator<Item = StrippedLine<'l, 's>>,
+    {
         let mut sections = Vec::new();
+        let mut open_sections: Vec<OpenSection<'l, 's>> = Vec::new();
 
-        while let Some(line) = lines.peek() {
-            let slice_common_with_ancestors =
-                Self::parse_slice_common_with_ancestors(previous_section_info, line);
+        while let Some(stripped_line) = lines.peek().copied() {
+            let line_full = stripped_line.line;
+
+            // Frames after a header are not compared with frames before it.
+            let is_footer_line = header_line
+                .is_some_and(|header_line| format.is_footer_line(header_line, line_full));
+            if format.is_header_line(line_full) || is_footer_line {
+                break;
+            }
+
+            // Frame indices differ on every line, so we group on the rest of the line.
+            let (slice_frame_index, line) = format.split_frame_prefix(line_full);
+            let grouping_key = format.grouping_key(line);
+
+            let previous_section_info = open_sections.last().map(OpenSection::section_info);
+            let line_before_location = &line[..format.location_start(line).unwrap_or(line.len())];
+            let slice_common_with_ancestors = Self::parse_slice_common_with_ancestors(
+                parse_options,
+                previous_section_info.map(PreviousSectionInfo::previous_line),
+                line_before_location,
+            );
+            let grouping_key_common_len = Self::grouping_key_common_len(
+                parse_options,
+                previous_section_info.map(PreviousSectionInfo::previous_grouping_key),
+                &grouping_key,
+            );
 
             // if the slice common with ancestors is shorter than or equal to the previous
             // line's slice common length, then this line should be a subsection of the
-            // parent section
-            let should_return_early = Self::line_is_better_suited_as_child_section_of_parent(
+            // parent section, so the parent section is complete.
+            let parent_is_complete = Self::line_is_better_suited_as_child_section_of_parent(
                 previous_section_info,
-                &slice_common_with_ancestors,
+                grouping_key_common_len,
             );
-            if should_return_early {
-                return sections;
+            if parent_is_complete {
+                Self::close_section(&mut sections, &mut open_sections);
+                continue;
             }
 
-            let slice_remainder = match slice_common_with_ancestors.len() == line.len() {
-                true => String::new(),
-                false => line[slice_common_with_ancestors.len()..].to_string(),
-            };
-
-            let current_line = line.to_string();
+            let slice_frame_index_len = slice_frame_index.len();
+            let slice_common_end = slice_frame_index_len + slice_common_with_ancestors.len();
 
             // consume the line because we are starting a new `Section`.
             lines.next();
 
+            // Lines such as `at src/main.rs:12` belong to this frame, so they shouldn't be
+            // compared with the following frames.
+            let (continuation_lines, line_trivia) =
+                Self::take_section_lines(lines, stripped_line, |continuation_line| {
+                    format.is_continuation_line(line_full, continuation_line)
+                });
+            let style_spans = Self::section_style_spans(stripped_line, &continuation_lines);
+            let source_span = Self::section_source_span(
+                stripped_line,
+                &continuation_lines,
+                slice_frame_index_len..slice_common_end,
+                slice_common_end..line_full.len(),
+            );
+
             let section_id = *next_id;
             *next_id += 1;
 
-            let child_sections = Self::parse(
-                lines,
-                next_id,
-                Some(PreviousSectionInfo {
-                    previous_line: current_line.as_str(),
-                    slice_common_len: slice_common_with_ancestors.len(),
-                }),
-            );
-
-            let section = Section {
+            let section = SectionRef {
                 id: section_id,
-                slice_common_with_previous_frames: slice_common_with_ancestors,
-                slice_remainder,
-                child_sections,
+                kind: SectionKind::Frame,
+                slice_log_prefix: stripped_line.log_prefix_slice(),
+                slice_frame_index: stripped_line.line_slice(0..slice_frame_index_len),
+                slice_common_with_previous_frames: stripped_line
+                    .line_slice(slice_frame_index_len..slice_common_end),
+                slice_remainder: stripped_line.line_slice(slice_common_end..line_full.len()),
+                continuation_lines: continuation_lines
+                    .into_iter()
+                    .map(|continuation_line| {
+                        continuation_line.line_slice(0..continuation_line.line.len())
+                    })
+                    .collect(),
+                style_spans,
+                source_span,
+                line_trivia,
+                format,
+                child_sections: Vec::new(),
             };
-            sections.push(section);
+            open_sections.push(OpenSection {
+                section,
+                line,
+                grouping_key,
+                slice_common_len: grouping_key_common_len,
+            });
+        }
+
+        while !open_sections.is_empty() {
+            Self::close_section(&mut sections, &mut open_sections);
         }
 
         sections
     }
 
-    fn parse_slice_common_with_ancestors(
-        previous_section_info: Option<PreviousSectionInfo<'_>>,
-        line: &&str,
-    ) -> String {
-        let slice_common_with_ancestors = previous_section_info
-            .map(PreviousSectionInfo::previous_line)
+    /// Moves the innermost open section into its parent, or into `sections` if
+    /// it is a top level section.
+    fn close_section<'s>(
+        sections: &mut Vec<SectionRef<'s>>,
+        open_sections: &mut Vec<OpenSection<'_, 's>>,
+    ) {
+        if let Some(OpenSection { section, .. }) = open_sections.pop() {
+            match open_sections.last_mut() {
+                Some(parent) => parent.section.child_sections.push(section),
+                None => sections.push(section),
+            }
+        }
+    }
+
+    fn parse_slice_common_with_ancestors<'l>(
+        parse_options: &ParseOptions,
+        previous_line: Option<&str>,
+        line: &'l str,
+    ) -> &'l str {
+        let slice_common_with_ancestors = previous_line
             .and_then(|previous_line| {
-                line.chars()
+                line.char_indices()
                     .zip(previous_line.chars())
-                    .take_while(|(line_char, previous_line_char)| line_char == previous_line_char)
-                    .enumerate()
+                    .take_while(|((_, line_char), previous_line_char)| {
+                        line_char == previous_line_char
+                    })
                     .last()
-                    .map(|(commona_char_index, _line_and_previous_line_char)| commona_char_index)
-                    .and_then(|common_char_index| {
-                        Self::beginning_of_closest_separator(line, common_char_index)
+                    // Byte index after the last common character.
+                    .map(|((common_char_index, common_char), _previous_line_char)| {
+                        common_char_index + common_char.len_utf8()
+                    })
+                    .and_then(|common_len| {
+                        Self::beginning_of_closest_separator(parse_options, line, common_len)
                     })
-                    .map(|slice_common_end_index| line[..slice_common_end_index].to_string())
+                    .filter(|slice_common_end_index| {
+                        *slice_common_end_index >= parse_options.min_common_prefix_len()
+                    })
+                    .map(|slice_common_end_index| &line[..slice_common_end_index])
             })
             .unwrap_or_default();
         slice_common_with_ancestors
     }
 
-    fn beginning_of_closest_separator(line: &str, common_char_index: usize) -> Option<usize> {
-        line[..=common_char_index]
-            .rfind(Self::is_separator)
-            .and_then(|separator_index| {
-                let line_until_separator_end = &line[..separator_index];
-                line_until_separator_end
-                    .rfind(Self::is_word_character)
-                    // `+ 1` because we want the index after the last word character.
-                    .map(|previous_word_index| previous_word_index + 1)
-            })
+    /// Returns the length of the grouping key that is common with the previous
+    /// section's grouping key.
+    ///
+    /// Whole segments are only in common when they are equal, and the prefix
+    /// segment is only compared when every whole segment is in common.
+    fn grouping_key_common_len(
+        parse_options: &ParseOptions,
+        previous_grouping_key: Option<&GroupingKey<'_>>,
+        grouping_key: &GroupingKey<'_>,
+    ) -> usize {
+        previous_grouping_key.map_or(0, |previous_grouping_key| {
+            let (whole_segments_common_len, is_all_common) =
+                grouping_key.whole_segments_common_len(previous_grouping_key);
+            let prefix_segment_common_len = if is_all_common {
+                Self::parse_slice_common_with_ancestors(
+                    parse_options,
+                    Some(previous_grouping_key.prefix_segment()),
+                    grouping_key.prefix_segment(),
+                )
+                .len()
+            } else {
+                0
+            };
+
+            whole_segments_common_len + prefix_segment_common_len
+        })
     }
 
-    fn is_separator(c: char) -> bool {
-        !Self::is_word_character(c)
+    fn beginning_of_closest_separator(
+        parse_options: &ParseOptions,
+        line: &str,
+        common_len: usize,
+    ) -> Option<usize> {
+        let line_common = &line[..common_len];
+        let separator_index = match parse_options.segment_separators() {
+            [] => line_common.rfind(|c| Self::is_separator(parse_options, c)),
+            segment_separators => segment_separators
+                .iter()
+                .filter(|segment_separator| !segment_separator.is_empty())
+                .filter_map(|segment_separator| line_common.rfind(segment_separator.as_str()))
+                .max(),
+        };
+
+        separator_index.and_then(|separator_index| {
+            line[..separator_index]
+                .char_indices()
+                .rev()
+                .find(|(_, c)| Self::is_word_character(parse_options, *c))
+                // We want the index after the last word character.
+                .map(|(previous_word_index, c)| previous_word_index + c.len_utf8())
+        })
     }
 
-    fn is_word_character(c: char) -> bool {
-        char::is_alphanumeric(c) || c == '_'
+    fn is_separator(parse_options: &ParseOptions, c: char) -> bool {
+        !Self::is_word_character(parse_options, c)
+    }
+
+    fn is_word_character(parse_options: &ParseOptions, c: char) -> bool {
+        char::is_alphanumeric(c) || parse_options.word_chars().contains(&c)
     }
 
     fn line_is_better_suited_as_child_section_of_parent(
         previous_section_info: Option<PreviousSectionInfo<'_>>,
-        slice_common_with_ancestors: &str,
+        slice_common_len: usize,
     ) -> bool {
         previous_section_info
             .map(PreviousSectionInfo::slice_common_len)
             .as_ref()
             .map(|previous_line_slice_common_len| {
-                slice_common_with_ancestors
-                    .len()
-                    .cmp(previous_line_slice_common_len)
+                slice_common_len.cmp(previous_line_slice_common_len)
             })
             .map(|comparison| match comparison {
                 Ordering::Less | Ordering::Equal => true,
@@ -127,18 +837,134 @@ impl Stacktrace {
     }
 }
 
+impl fmt::Display for Stacktrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Sections are in the same order as their lines, so they are written depth
+        // first. This uses an explicit stack so deeply nested sections don't overflow
+        // the call stack.
+        let mut sections = self.sections.iter().rev().collect::<Vec<_>>();
+        while let Some(section) = sections.pop() {
+            section.write_lines(f)?;
+            sections.extend(section.child_sections.iter().rev());
+        }
+
+        Ok(())
+    }
+}
+
 impl<'s> From<&'s str> for Stacktrace {
     fn from(s: &'s str) -> Self {
-        let mut lines = s.lines().peekable();
-        let sections = Stacktrace::parse(&mut lines, &mut 0, None);
+        Self::from_format(s, Self::detect_format(s))
+    }
+}
+
+/// A line with its ANSI escape sequences and log prefix removed.
+#[derive(Clone, Copy, Debug)]
+struct StrippedLine<'l, 's> {
+    /// The log prefix, e.g. `2026-10-17T10:00:01.123Z ERROR [pod-abc] `.
+    log_prefix: &'l str,
+    /// The rest of the line.
+    line: &'l str,
+    /// Styles of the line, including the log prefix.
+    style_spans: &'l [StyleSpan],
+    /// The line in the stack trace string, if it had no escape sequences.
+    ///
+    /// This is the log prefix followed by the rest of the line.
+    line_input: Option<&'s str>,
+    /// The line in the stack trace string, including escape sequences.
+    line_unstripped: &'s str,
+    /// Line number in the stack trace string, starting from `1`.
+    line_number: usize,
+    /// Byte index of the start of the line in the stack trace string.
+    line_start: usize,
+    /// Byte index of the end of the line in the stack trace string, excluding
+    /// the line ending.
+    line_end: usize,
+    /// Line ending after the line.
+    line_ending: LineEnding,
+    /// Byte ranges of the escape sequences that were removed from the line.
+    escape_ranges: &'l [Range<usize>],
+}
+
+impl<'s> StrippedLine<'_, 's> {
+    /// Returns the log prefix, borrowed from the stack trace string if
+    /// possible.
+    fn log_prefix_slice(self) -> Cow<'s, str> {
+        match self.line_input {
+            Some(line_input) => Cow::Borrowed(&line_input[..self.log_prefix.len()]),
+            None => Cow::Owned(self.log_prefix.to_string()),
+        }
+    }
+
+    /// Returns the given range of the line after the log prefix, borrowed from
+    /// the stack trace string if possible.
+    fn line_slice(self, range: Range<usize>) -> Cow<'s, str> {
+        match self.line_input {
+            Some(line_input) => Cow::Borrowed(&line_input[self.log_prefix.len()..][range]),
+            None => Cow::Owned(self.line[range].to_string()),
+        }
+    }
 
-        Self { sections }
+    /// Returns the byte range in the stack trace string of the given range of
+    /// the line after the log prefix.
+    ///
+    /// Escape sequences before and after the range are not included.
+    fn source_range(self, range: Range<usize>) -> Range<usize> {
+        let start = self.log_prefix.len() + range.start;
+        let end = self.log_prefix.len() + range.end;
+        let source_start =
+            self.line_start + StyleSpan::unstripped_index(self.escape_ranges, start, true);
+        let source_end = match start == end {
+            true => source_start,
+            false => self.line_start + StyleSpan::unstripped_index(self.escape_ranges, end, false),
+        };
+
+        source_start..source_end
+    }
+
+    /// Returns whether the line is blank, apart from its log prefix.
+    fn is_blank(self) -> bool {
+        self.line.trim().is_empty()
+    }
+
+    /// Returns the line as a blank line, borrowed from the stack trace string.
+    fn blank_line(self) -> BlankLineRef<'s> {
+        BlankLineRef {
+            text: self.line_unstripped,
+            line_ending: self.line_ending,
+        }
+    }
+
+    /// Returns the text removed from the line before parsing, borrowed from
+    /// the stack trace string if possible.
+    fn line_trivia(self) -> LineTriviaRef<'s> {
+        let mut escapes_len = 0;
+        let escape_sequences = self
+            .escape_ranges
+            .iter()
+            .map(|escape_range| {
+                let index = escape_range.start - escapes_len;
+                escapes_len += escape_range.len();
+                EscapeSequenceRef {
+                    index,
+                    sequence: &self.line_unstripped[escape_range.clone()],
+                }
+            })
+            .collect();
+
+        LineTriviaRef {
+            log_prefix: self.log_prefix_slice(),
+            escape_sequences,
+            line_ending: self.line_ending,
+            blank_lines: Vec::new(),
+        }
     }
 }
 
 #[derive(Clone, Copy, Debug)]
 struct PreviousSectionInfo<'s> {
     previous_line: &'s str,
+    previous_grouping_key: &'s GroupingKey<'s>,
     slice_common_len: usize,
 }
 
@@ -147,14 +973,75 @@ impl<'s> PreviousSectionInfo<'s> {
         self.previous_line
     }
 
+    fn previous_grouping_key(self) -> &'s GroupingKey<'s> {
+        self.previous_grouping_key
+    }
+
     fn slice_common_len(self) -> usize {
         self.slice_common_len
     }
 }
 
+/// A frame section that may still receive child sections.
+#[derive(Debug)]
+struct OpenSection<'l, 's> {
+    section: SectionRef<'s>,
+    /// The section's line, without the frame index.
+    line: &'l str,
+    grouping_key: GroupingKey<'l>,
+    /// Length of the grouping key that is common with the parent section.
+    slice_common_len: usize,
+}
+
+impl OpenSection<'_, '_> {
+    fn section_info(&self) -> PreviousSectionInfo<'_> {
+        PreviousSectionInfo {
+            previous_line: self.line,
+            previous_grouping_key: &self.grouping_key,
+            slice_common_len: self.slice_common_len,
+        }
+    }
+}
+
+#[cfg(test)]
+impl Stacktrace {
+    /// Parses `substring`, which is part of `s`, with source spans relative to
+    /// `s`.
+    ///
+    /// This is the stack trace expected from a format's parser when the
+    /// substring is its frames.
+    pub(crate) fn from_substring(s: &str, substring: &str) -> Self {
+        let offset = s
+            .find(substring)
+            .expect("Expected substring to be part of the string.");
+        let line_offset = s[..offset].matches('\n').count();
+        let shift = |range: &mut Range<usize>| *range = range.start + offset..range.end + offset;
+
+        let mut stacktrace = Self::from(substring);
+        let mut sections = stacktrace.sections.iter_mut().collect::<Vec<_>>();
+        while let Some(section) = sections.pop() {
+            let source_span = &mut section.source_span;
+            source_span.line_number += line_offset;
+            shift(&mut source_span.lines);
+            shift(&mut source_span.common);
+            shift(&mut source_span.remainder);
+            sections.extend(section.child_sections.iter_mut());
+        }
+
+        stacktrace
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::Section;
+    use std::hash::{Hash, Hasher};
+
+    use proptest::prelude::*;
+
+    use crate::{
+        Color, Diagnostic, DiagnosticKind, Format, Frame, JavascriptError, LineTrivia, ParseError,
+        ParseOptions, Section, SectionKind, SourceSpan, StacktraceRef, Style, StyleSpan,
+    };
 
     use super::Stacktrace;
 
@@ -170,15 +1057,42 @@ mod tests {
         let stacktrace_expected = Stacktrace {
             sections: vec![Section {
                 id: 0,
+                kind: SectionKind::Frame,
+                slice_log_prefix: String::new(),
+                slice_frame_index: String::new(),
                 slice_common_with_previous_frames: String::new(),
                 slice_remainder: String::from("a::b::Class.method_one"),
+                continuation_lines: Vec::new(),
+                style_spans: Vec::new(),
+                source_span: SourceSpan {
+                    line_number: 1,
+                    lines: 0..22,
+                    common: 0..0,
+                    remainder: 0..22,
+                },
+                line_trivia: vec![LineTrivia::default()],
+                frame: Frame::from("a::b::Class.method_one"),
                 child_sections: vec![Section {
                     id: 1,
+                    kind: SectionKind::Frame,
+                    slice_log_prefix: String::new(),
+                    slice_frame_index: String::new(),
                     slice_common_with_previous_frames: String::from("a::b::Class"),
                     slice_remainder: String::from(".method_two"),
+                    continuation_lines: Vec::new(),
+                    style_spans: Vec::new(),
+                    source_span: SourceSpan {
+                        line_number: 2,
+                        lines: 23..45,
+                        common: 23..34,
+                        remainder: 34..45,
+                    },
+                    line_trivia: vec![LineTrivia::default()],
+                    frame: Frame::from("a::b::Class.method_two"),
                     child_sections: Vec::new(),
                 }],
             }],
+            diagnostics: Vec::new(),
         };
         assert_eq!(stacktrace_expected, stacktrace)
     }
@@ -198,34 +1112,100 @@ mod tests {
         let stacktrace_expected = Stacktrace {
             sections: vec![Section {
                 id: 0,
+                kind: SectionKind::Frame,
+                slice_log_prefix: String::new(),
+                slice_frame_index: String::new(),
                 slice_common_with_previous_frames: String::new(),
                 slice_remainder: String::from("a::b::Class.method_one"),
+                continuation_lines: Vec::new(),
+                style_spans: Vec::new(),
+                source_span: SourceSpan {
+                    line_number: 1,
+                    lines: 0..22,
+                    common: 0..0,
+                    remainder: 0..22,
+                },
+                line_trivia: vec![LineTrivia::default()],
+                frame: Frame::from("a::b::Class.method_one"),
                 child_sections: vec![
                     Section {
                         id: 1,
+                        kind: SectionKind::Frame,
+                        slice_log_prefix: String::new(),
+                        slice_frame_index: String::new(),
                         slice_common_with_previous_frames: String::from("a::b"),
                         slice_remainder: String::from("::c::Class.method_one"),
+                        continuation_lines: Vec::new(),
+                        style_spans: Vec::new(),
+                        source_span: SourceSpan {
+                            line_number: 2,
+                            lines: 23..48,
+                            common: 23..27,
+                            remainder: 27..48,
+                        },
+                        line_trivia: vec![LineTrivia::default()],
+                        frame: Frame::from("a::b::c::Class.method_one"),
                         child_sections: Vec::new(),
                     },
                     Section {
                         id: 2,
+                        kind: SectionKind::Frame,
+                        slice_log_prefix: String::new(),
+                        slice_frame_index: String::new(),
                         slice_common_with_previous_frames: String::from("a::b::Class"),
                         slice_remainder: String::from(".method_two"),
+                        continuation_lines: Vec::new(),
+                        style_spans: Vec::new(),
+                        source_span: SourceSpan {
+                            line_number: 3,
+                            lines: 49..71,
+                            common: 49..60,
+                            remainder: 60..71,
+                        },
+                        line_trivia: vec![LineTrivia::default()],
+                        frame: Frame::from("a::b::Class.method_two"),
                         child_sections: vec![],
                     },
                     Section {
                         id: 3,
+                        kind: SectionKind::Frame,
+                        slice_log_prefix: String::new(),
+                        slice_frame_index: String::new(),
                         slice_common_with_previous_frames: String::from("a::b"),
                         slice_remainder: String::from("::c::Class.method_one"),
+                        continuation_lines: Vec::new(),
+                        style_spans: Vec::new(),
+                        source_span: SourceSpan {
+                            line_number: 4,
+                            lines: 72..97,
+                            common: 72..76,
+                            remainder: 76..97,
+                        },
+                        line_trivia: vec![LineTrivia::default()],
+                        frame: Frame::from("a::b::c::Class.method_one"),
                         child_sections: vec![Section {
                             id: 4,
+                            kind: SectionKind::Frame,
+                            slice_log_prefix: String::new(),
+                            slice_frame_index: String::new(),
                             slice_common_with_previous_frames: String::from("a::b::c::Class"),
                             slice_remainder: String::from(".method_two"),
+                            continuation_lines: Vec::new(),
+                            style_spans: Vec::new(),
+                            source_span: SourceSpan {
+                                line_number: 5,
+                                lines: 98..123,
+                                common: 98..112,
+                                remainder: 112..123,
+                            },
+                            line_trivia: vec![LineTrivia::default()],
+                            frame: Frame::from("a::b::c::Class.method_two"),
                             child_sections: Vec::new(),
                         }],
                     },
                 ],
             }],
+            diagnostics: Vec::new(),
         };
         assert_eq!(stacktrace_expected, stacktrace)
     }
@@ -244,23 +1224,954 @@ mod tests {
             sections: vec![
                 Section {
                     id: 0,
+                    kind: SectionKind::Frame,
+                    slice_log_prefix: String::new(),
+                    slice_frame_index: String::new(),
                     slice_common_with_previous_frames: String::new(),
                     slice_remainder: String::from("__wbg_get_imports/imports.wbg.__wbg_new_abda76e883b18a5f@http://127.0.0.1:7890/pkg/dot_ix.js:489:13"),
-                    child_sections: vec![]
+                    continuation_lines: Vec::new(),
+                    style_spans: Vec::new(),
+                    source_span: SourceSpan {
+                        line_number: 1,
+                        lines: 0..99,
+                        common: 0..0,
+                        remainder: 0..99,
+                    },
+                    line_trivia: vec![LineTrivia::default()],
+                    frame: JavascriptError::parse_frame("__wbg_get_imports/imports.wbg.__wbg_new_abda76e883b18a5f@http://127.0.0.1:7890/pkg/dot_ix.js:489:13").unwrap_or_default(),
+                    child_sections: Vec::new(),
                 },
                 Section {
                     id: 1,
+                    kind: SectionKind::Frame,
+                    slice_log_prefix: String::new(),
+                    slice_frame_index: String::new(),
                     slice_common_with_previous_frames: String::new(),
                     slice_remainder: String::from("dot_ix_playground.wasm.__wbg_new_abda76e883b18a5f externref shim@http://127.0.0.1:7890/pkg/dot_ix.wasm:wasm-function[25993]:0x6bb546"),
+                    continuation_lines: Vec::new(),
+                    style_spans: Vec::new(),
+                    source_span: SourceSpan {
+                        line_number: 2,
+                        lines: 100..232,
+                        common: 100..100,
+                        remainder: 100..232,
+                    },
+                    line_trivia: vec![LineTrivia::default()],
+                    frame: JavascriptError::parse_frame("dot_ix_playground.wasm.__wbg_new_abda76e883b18a5f externref shim@http://127.0.0.1:7890/pkg/dot_ix.wasm:wasm-function[25993]:0x6bb546").unwrap_or_default(),
                     child_sections: vec![Section {
                         id: 2,
+                        kind: SectionKind::Frame,
+                        slice_log_prefix: String::new(),
+                        slice_frame_index: String::new(),
                         slice_common_with_previous_frames: String::from("dot_ix_playground.wasm"),
                         slice_remainder: String::from(".console_error_panic_hook::Error::new::h8adb78d6eba1ab93@http://127.0.0.1:7890/pkg/dot_ix.wasm:wasm-function[16925]:0x636d40"),
+                        continuation_lines: Vec::new(),
+                        style_spans: Vec::new(),
+                        source_span: SourceSpan {
+                            line_number: 3,
+                            lines: 233..379,
+                            common: 233..255,
+                            remainder: 255..379,
+                        },
+                        line_trivia: vec![LineTrivia::default()],
+                        frame: JavascriptError::parse_frame("dot_ix_playground.wasm.console_error_panic_hook::Error::new::h8adb78d6eba1ab93@http://127.0.0.1:7890/pkg/dot_ix.wasm:wasm-function[16925]:0x636d40").unwrap_or_default(),
                         child_sections: Vec::new()
                     }]
                 },
-            ]
+            ],
+            diagnostics: Vec::new(),
+        };
+        assert_eq!(stacktrace_expected, stacktrace)
+    }
+
+    #[test]
+    fn parses_multiple_section_stacktrace_frame_indices() {
+        let stacktrace = Stacktrace::from(
+            "\
+            \x20  6: std::sys_common::backtrace::_print\n\
+            \x20  7: std::sys_common::backtrace::print\n\
+            \x20  8: std::panicking::default_hook\n\
+            ",
+        );
+
+        let stacktrace_expected = Stacktrace {
+            sections: vec![Section {
+                id: 0,
+                kind: SectionKind::Frame,
+                slice_log_prefix: String::new(),
+                slice_frame_index: String::from("   6: "),
+                slice_common_with_previous_frames: String::new(),
+                slice_remainder: String::from("std::sys_common::backtrace::_print"),
+                continuation_lines: Vec::new(),
+                style_spans: Vec::new(),
+                source_span: SourceSpan {
+                    line_number: 1,
+                    lines: 0..40,
+                    common: 6..6,
+                    remainder: 6..40,
+                },
+                line_trivia: vec![LineTrivia::default()],
+                frame: Frame::from("   6: std::sys_common::backtrace::_print"),
+                child_sections: vec![
+                    Section {
+                        id: 1,
+                        kind: SectionKind::Frame,
+                        slice_log_prefix: String::new(),
+                        slice_frame_index: String::from("   7: "),
+                        slice_common_with_previous_frames: String::from(
+                            "std::sys_common::backtrace",
+                        ),
+                        slice_remainder: String::from("::print"),
+                        continuation_lines: Vec::new(),
+                        style_spans: Vec::new(),
+                        source_span: SourceSpan {
+                            line_number: 2,
+                            lines: 41..80,
+                            common: 47..73,
+                            remainder: 73..80,
+                        },
+                        line_trivia: vec![LineTrivia::default()],
+                        frame: Frame::from("   7: std::sys_common::backtrace::print"),
+                        child_sections: Vec::new(),
+                    },
+                    Section {
+                        id: 2,
+                        kind: SectionKind::Frame,
+                        slice_log_prefix: String::new(),
+                        slice_frame_index: String::from("   8: "),
+                        slice_common_with_previous_frames: String::from("std"),
+                        slice_remainder: String::from("::panicking::default_hook"),
+                        continuation_lines: Vec::new(),
+                        style_spans: Vec::new(),
+                        source_span: SourceSpan {
+                            line_number: 3,
+                            lines: 81..115,
+                            common: 87..90,
+                            remainder: 90..115,
+                        },
+                        line_trivia: vec![LineTrivia::default()],
+                        frame: Frame::from("   8: std::panicking::default_hook"),
+                        child_sections: Vec::new(),
+                    },
+                ],
+            }],
+            diagnostics: Vec::new(),
+        };
+        assert_eq!(stacktrace_expected, stacktrace)
+    }
+
+    #[test]
+    fn parses_multiple_section_stacktrace_continuation_lines() {
+        let stacktrace = Stacktrace::from(
+            "\
+            \x20  8: std::panicking::default_hook::{{closure}}\n\
+            \x20            at src/libstd/panicking.rs:196\n\
+            \x20  9: std::panicking::default_hook\n\
+            \x20            at src/libstd/panicking.rs:210\n\
+            ",
+        );
+
+        let stacktrace_expected = Stacktrace {
+            sections: vec![Section {
+                id: 0,
+                kind: SectionKind::Frame,
+                slice_log_prefix: String::new(),
+                slice_frame_index: String::from("   8: "),
+                slice_common_with_previous_frames: String::new(),
+                slice_remainder: String::from("std::panicking::default_hook::{{closure}}"),
+                continuation_lines: vec![String::from(
+                    "             at src/libstd/panicking.rs:196",
+                )],
+                style_spans: Vec::new(),
+                source_span: SourceSpan {
+                    line_number: 1,
+                    lines: 0..91,
+                    common: 6..6,
+                    remainder: 6..47,
+                },
+                line_trivia: vec![LineTrivia::default(); 2],
+                frame: Frame::from("   8: std::panicking::default_hook::{{closure}}")
+                    .with_continuation_line("             at src/libstd/panicking.rs:196"),
+                child_sections: vec![Section {
+                    id: 1,
+                    kind: SectionKind::Frame,
+                    slice_log_prefix: String::new(),
+                    slice_frame_index: String::from("   9: "),
+                    slice_common_with_previous_frames: String::from("std::panicking"),
+                    slice_remainder: String::from("::default_hook"),
+                    continuation_lines: vec![String::from(
+                        "             at src/libstd/panicking.rs:210",
+                    )],
+                    style_spans: Vec::new(),
+                    source_span: SourceSpan {
+                        line_number: 3,
+                        lines: 92..170,
+                        common: 98..112,
+                        remainder: 112..126,
+                    },
+                    line_trivia: vec![LineTrivia::default(); 2],
+                    frame: Frame::from("   9: std::panicking::default_hook")
+                        .with_continuation_line("             at src/libstd/panicking.rs:210"),
+                    child_sections: Vec::new(),
+                }],
+            }],
+            diagnostics: Vec::new(),
         };
         assert_eq!(stacktrace_expected, stacktrace)
     }
+
+    #[test]
+    fn does_not_attach_v8_frames_as_rust_continuation_lines() {
+        let stacktrace = Stacktrace::from_format(
+            "\
+            app::main\n\
+            \x20     at src/main.rs:3\n\
+            foo::bar\n\
+            \x20   at /app/a.js:1:2\n\
+            \x20   at /app/b.js:3:4\n\
+            ",
+            Format::Generic,
+        );
+
+        let mut lines_and_continuation_lines = Vec::new();
+        let mut sections = stacktrace.sections.iter().rev().collect::<Vec<_>>();
+        while let Some(section) = sections.pop() {
+            let line = format!(
+                "{}{}",
+                section.slice_common_with_previous_frames(),
+                section.slice_remainder()
+            );
+            lines_and_continuation_lines.push((line, section.continuation_lines().to_vec()));
+            sections.extend(section.child_sections().iter().rev());
+        }
+        assert_eq!(
+            vec![
+                (
+                    String::from("app::main"),
+                    vec![String::from("      at src/main.rs:3")]
+                ),
+                (String::from("foo::bar"), Vec::new()),
+                (String::from("    at /app/a.js:1:2"), Vec::new()),
+                (String::from("    at /app/b.js:3:4"), Vec::new()),
+            ],
+            lines_and_continuation_lines
+        );
+    }
+
+    #[test]
+    fn parses_multiple_section_stacktrace_headers() {
+        let stacktrace = Stacktrace::from(
+            "\
+            Exception in thread \"main\" java.lang.IllegalStateException\n\
+            \x20       at com.example.adder.app.App.run(App.java:21)\n\
+            \x20       at com.example.adder.app.App.main(App.java:14)\n\
+            Caused by: com.example.adder.AdderException\n\
+            \x20       at com.example.adder.app.App.add(App.java:13)\n\
+            \x20       ... 2 more\n\
+            ",
+        );
+
+        let stacktrace_expected = Stacktrace {
+            sections: vec![
+                Section {
+                    id: 0,
+                    kind: SectionKind::Header,
+                    slice_log_prefix: String::new(),
+                    slice_frame_index: String::new(),
+                    slice_common_with_previous_frames: String::new(),
+                    slice_remainder: String::from(
+                        "Exception in thread \"main\" java.lang.IllegalStateException",
+                    ),
+                    continuation_lines: Vec::new(),
+                    style_spans: Vec::new(),
+                    source_span: SourceSpan {
+                        line_number: 1,
+                        lines: 0..58,
+                        common: 0..0,
+                        remainder: 0..58,
+                    },
+                    line_trivia: vec![LineTrivia::default()],
+                    frame: Frame::default(),
+                    child_sections: vec![Section {
+                        id: 1,
+                        kind: SectionKind::Frame,
+                        slice_log_prefix: String::new(),
+                        slice_frame_index: String::new(),
+                        slice_common_with_previous_frames: String::new(),
+                        slice_remainder: String::from(
+                            "        at com.example.adder.app.App.run(App.java:21)",
+                        ),
+                        continuation_lines: Vec::new(),
+                        style_spans: Vec::new(),
+                        source_span: SourceSpan {
+                            line_number: 2,
+                            lines: 59..112,
+                            common: 59..59,
+                            remainder: 59..112,
+                        },
+                        line_trivia: vec![LineTrivia::default()],
+                        frame: Frame::from("        at com.example.adder.app.App.run(App.java:21)"),
+                        child_sections: vec![Section {
+                            id: 2,
+                            kind: SectionKind::Frame,
+                            slice_log_prefix: String::new(),
+                            slice_frame_index: String::new(),
+                            slice_common_with_previous_frames: String::from(
+                                "        at com.example.adder.app.App",
+                            ),
+                            slice_remainder: String::from(".main(App.java:14)"),
+                            continuation_lines: Vec::new(),
+                            style_spans: Vec::new(),
+                            source_span: SourceSpan {
+                                line_number: 3,
+                                lines: 113..167,
+                                common: 113..149,
+                                remainder: 149..167,
+                            },
+                            line_trivia: vec![LineTrivia::default()],
+                            frame: Frame::from(
+                                "        at com.example.adder.app.App.main(App.java:14)",
+                            ),
+                            child_sections: Vec::new(),
+                        }],
+                    }],
+                },
+                Section {
+                    id: 3,
+                    kind: SectionKind::Header,
+                    slice_log_prefix: String::new(),
+                    slice_frame_index: String::new(),
+                    slice_common_with_previous_frames: String::new(),
+                    slice_remainder: String::from("Caused by: com.example.adder.AdderException"),
+                    continuation_lines: Vec::new(),
+                    style_spans: Vec::new(),
+                    source_span: SourceSpan {
+                        line_number: 4,
+                        lines: 168..211,
+                        common: 168..168,
+                        remainder: 168..211,
+                    },
+                    line_trivia: vec![LineTrivia::default()],
+                    frame: Frame::default(),
+                    child_sections: vec![Section {
+                        id: 4,
+                        kind: SectionKind::Frame,
+                        slice_log_prefix: String::new(),
+                        slice_frame_index: String::new(),
+                        slice_common_with_previous_frames: String::new(),
+                        slice_remainder: String::from(
+                            "        at com.example.adder.app.App.add(App.java:13)",
+                        ),
+                        continuation_lines: vec![String::from("        ... 2 more")],
+                        style_spans: Vec::new(),
+                        source_span: SourceSpan {
+                            line_number: 5,
+                            lines: 212..284,
+                            common: 212..212,
+                            remainder: 212..265,
+                        },
+                        line_trivia: vec![LineTrivia::default(); 2],
+                        frame: Frame::from("        at com.example.adder.app.App.add(App.java:13)"),
+                        child_sections: Vec::new(),
+                    }],
+                },
+            ],
+            diagnostics: Vec::new(),
+        };
+        assert_eq!(stacktrace_expected, stacktrace)
+    }
+
+    #[test]
+    fn parses_multiple_section_stacktrace_grouped_by_script_url() {
+        let stacktrace = Stacktrace::from(
+            "\
+            \x20   at Object.handler (/app/src/routes/user.js:42:13)\n\
+            \x20   at next (/app/node_modules/express/lib/router/route.js:137:13)\n\
+            \x20   at Route.dispatch (/app/node_modules/express/lib/router/route.js:112:3)\n\
+            ",
+        );
+
+        let stacktrace_expected = Stacktrace {
+            sections: vec![
+                Section {
+                    id: 0,
+                    kind: SectionKind::Frame,
+                    slice_log_prefix: String::new(),
+                    slice_frame_index: String::new(),
+                    slice_common_with_previous_frames: String::new(),
+                    slice_remainder: String::from("    at Object.handler (/app/src/routes/user.js:42:13)"),
+                    continuation_lines: Vec::new(),
+                    style_spans: Vec::new(),
+                    source_span: SourceSpan {
+                        line_number: 1,
+                        lines: 0..53,
+                        common: 0..0,
+                        remainder: 0..53,
+                    },
+                    line_trivia: vec![LineTrivia::default()],
+                    frame: JavascriptError::parse_frame("    at Object.handler (/app/src/routes/user.js:42:13)").unwrap_or_default(),
+                    child_sections: Vec::new(),
+                },
+                Section {
+                    id: 1,
+                    kind: SectionKind::Frame,
+                    slice_log_prefix: String::new(),
+                    slice_frame_index: String::new(),
+                    slice_common_with_previous_frames: String::new(),
+                    slice_remainder: String::from("    at next (/app/node_modules/express/lib/router/route.js:137:13)"),
+                    continuation_lines: Vec::new(),
+                    style_spans: Vec::new(),
+                    source_span: SourceSpan {
+                        line_number: 2,
+                        lines: 54..120,
+                        common: 54..54,
+                        remainder: 54..120,
+                    },
+                    line_trivia: vec![LineTrivia::default()],
+                    frame: JavascriptError::parse_frame("    at next (/app/node_modules/express/lib/router/route.js:137:13)").unwrap_or_default(),
+                    child_sections: vec![Section {
+                        id: 2,
+                        kind: SectionKind::Frame,
+                        slice_log_prefix: String::new(),
+                        slice_frame_index: String::new(),
+                        slice_common_with_previous_frames: String::from("    at"),
+                        slice_remainder: String::from(" Route.dispatch (/app/node_modules/express/lib/router/route.js:112:3)"),
+                        continuation_lines: Vec::new(),
+                        style_spans: Vec::new(),
+                        source_span: SourceSpan {
+                            line_number: 3,
+                            lines: 121..196,
+                            common: 121..127,
+                            remainder: 127..196,
+                        },
+                        line_trivia: vec![LineTrivia::default()],
+                        frame: JavascriptError::parse_frame("    at Route.dispatch (/app/node_modules/express/lib/router/route.js:112:3)").unwrap_or_default(),
+                        child_sections: Vec::new(),
+                    }],
+                },
+            ],
+            diagnostics: Vec::new(),
+        };
+        assert_eq!(stacktrace_expected, stacktrace)
+    }
+
+    #[test]
+    fn parses_multiple_section_stacktrace_dotnet_groups() {
+        let stacktrace = Stacktrace::from(
+            "\
+            System.InvalidOperationException: Failed to load user. ---> System.ArgumentNullException: Value cannot be null.\n\
+            \x20  at MyApp.Services.UserService.Load(String id) in C:\\src\\MyApp\\Services\\UserService.cs:line 42\n\
+            \x20  at MyApp.Services.UserService.LoadAsync(String id) in C:\\src\\MyApp\\Services\\UserService.cs:line 30\n\
+            \x20  --- End of inner exception stack trace ---\n\
+            \x20  at MyApp.Program.Main(String[] args) in C:\\src\\MyApp\\Program.cs:line 12\n\
+            --- End of stack trace from previous location ---\n\
+            \x20  at MyApp.Program.<Main>(String[] args)\n\
+            ",
+        );
+
+        let groups = stacktrace
+            .sections
+            .iter()
+            .map(|section| {
+                (
+                    section.kind(),
+                    section.slice_remainder().trim(),
+                    section.child_sections().len(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (
+                    SectionKind::Header,
+                    "System.InvalidOperationException: Failed to load user. ---> System.ArgumentNullException: Value cannot be null.",
+                    1
+                ),
+                (
+                    SectionKind::Header,
+                    "--- End of inner exception stack trace ---",
+                    1
+                ),
+                (
+                    SectionKind::Header,
+                    "--- End of stack trace from previous location ---",
+                    1
+                ),
+            ],
+            groups
+        );
+
+        let frame = stacktrace.sections[0].child_sections()[0].frame();
+        assert_eq!(Some("Load"), frame.function());
+        assert_eq!(Some("String id"), frame.arguments());
+        assert_eq!(Some(42), frame.line());
+    }
+
+    #[test]
+    fn parses_multiple_section_stacktrace_kernel_oops_metadata() {
+        let stacktrace = Stacktrace::from(
+            "\
+            BUG: kernel NULL pointer dereference, address: 0000000000000000\n\
+            CPU: 2 PID: 1234 Comm: insmod Tainted: G           O      6.1.0 #1\n\
+            RIP: 0010:my_init+0x15/0x30 [my_module]\n\
+            RSP: 0018:ffffb0a1c0d4bd08 EFLAGS: 00010246\n\
+            Call Trace:\n\
+            \x20do_one_initcall+0x46/0x1e0\n\
+            \x20do_init_module+0x52/0x220 [my_module]\n\
+            ",
+        );
+
+        // The register dump is kept with the header, rather than parsed as frames.
+        let [bug, call_trace] = stacktrace.sections.as_slice() else {
+            panic!("Expected two top level sections.");
+        };
+        assert_eq!(
+            vec![
+                "CPU: 2 PID: 1234 Comm: insmod Tainted: G           O      6.1.0 #1",
+                "RIP: 0010:my_init+0x15/0x30 [my_module]",
+                "RSP: 0018:ffffb0a1c0d4bd08 EFLAGS: 00010246",
+            ],
+            bug.continuation_lines()
+        );
+        assert!(bug.child_sections().is_empty());
+        assert_eq!(2, call_trace.child_sections().len());
+    }
+
+    #[test]
+    fn parses_multiple_section_stacktrace_log_prefixes() {
+        let s = "\
+            2026-10-17T10:00:01.123Z ERROR [pod-abc] java.lang.IllegalStateException: Failed\n\
+            2026-10-17T10:00:01.123Z ERROR [pod-abc]     at com.example.adder.app.App.run(App.java:21)\n\
+            2026-10-17T10:00:01.124Z ERROR [pod-abc]     at com.example.adder.app.App.main(App.java:14)\n\
+            ";
+
+        assert_eq!(Format::Java, Stacktrace::detect_format(s));
+
+        let stacktrace = Stacktrace::from(s);
+        let [header] = stacktrace.sections.as_slice() else {
+            panic!("Expected one top level section.");
+        };
+        assert_eq!(
+            "2026-10-17T10:00:01.123Z ERROR [pod-abc] ",
+            header.slice_log_prefix()
+        );
+        assert_eq!(
+            "java.lang.IllegalStateException: Failed",
+            header.slice_remainder()
+        );
+
+        let [app_run] = header.child_sections() else {
+            panic!("Expected one frame under the header.");
+        };
+        assert_eq!(
+            "    at com.example.adder.app.App.run(App.java:21)",
+            app_run.slice_remainder()
+        );
+
+        let [app_main] = app_run.child_sections() else {
+            panic!("Expected `App.main` to be nested under `App.run`.");
+        };
+        assert_eq!(
+            "2026-10-17T10:00:01.124Z ERROR [pod-abc] ",
+            app_main.slice_log_prefix()
+        );
+        assert_eq!(
+            "    at com.example.adder.app.App",
+            app_main.slice_common_with_previous_frames()
+        );
+        assert_eq!(Some("main"), app_main.frame().function());
+    }
+
+    #[test]
+    fn parses_multiple_section_stacktrace_logcat_prefixes() {
+        let s = "\
+            01-15 10:23:45.678  1234  1234 E AndroidRuntime: FATAL EXCEPTION: main\n\
+            01-15 10:23:45.678  1234  1234 E AndroidRuntime: Process: com.example.app, PID: 1234\n\
+            01-15 10:23:45.678  1234  1234 E AndroidRuntime: java.lang.NullPointerException: boom\n\
+            01-15 10:23:45.678  1234  1234 E AndroidRuntime: \tat com.example.MainActivity.onCreate(MainActivity.java:12)\n\
+            01-15 10:23:45.679  1234  1234 E AndroidRuntime: \tat com.example.MainActivity.onStart(MainActivity.java:20)\n\
+            ";
+
+        assert_eq!(Format::Android, Stacktrace::detect_format(s));
+
+        let stacktrace = Stacktrace::from(s);
+        let [fatal_exception, exception] = stacktrace.sections.as_slice() else {
+            panic!("Expected two top level sections.");
+        };
+        assert_eq!(
+            "01-15 10:23:45.678  1234  1234 E AndroidRuntime: ",
+            fatal_exception.slice_log_prefix()
+        );
+        assert_eq!("FATAL EXCEPTION: main", fatal_exception.slice_remainder());
+        assert_eq!(
+            ["Process: com.example.app, PID: 1234"],
+            fatal_exception.continuation_lines()
+        );
+        assert_eq!(
+            "java.lang.NullPointerException: boom",
+            exception.slice_remainder()
+        );
+
+        let [on_create] = exception.child_sections() else {
+            panic!("Expected one frame under the exception.");
+        };
+        assert_eq!("", on_create.slice_frame_index());
+        assert_eq!(
+            "\tat com.example.MainActivity.onCreate(MainActivity.java:12)",
+            on_create.slice_remainder()
+        );
+
+        let [on_start] = on_create.child_sections() else {
+            panic!("Expected `onStart` to be nested under `onCreate`.");
+        };
+        assert_eq!(
+            "01-15 10:23:45.679  1234  1234 E AndroidRuntime: ",
+            on_start.slice_log_prefix()
+        );
+        assert_eq!(
+            "\tat com.example.MainActivity",
+            on_start.slice_common_with_previous_frames()
+        );
+        assert_eq!(s, stacktrace.to_string());
+    }
+
+    #[test]
+    fn splits_log_prefixes_only_with_timestamps() {
+        [
+            (
+                "2026-10-17 10:00:01,123 INFO  [main] - at a.b(C.java:1)",
+                "2026-10-17 10:00:01,123 INFO  [main] - ",
+            ),
+            (
+                "10:00:01.123 | WARN:   at a.b(C.java:1)",
+                "10:00:01.123 | WARN: ",
+            ),
+            ("   12: core::fmt::write", ""),
+            ("12:34:56", ""),
+            ("[<ffffffff8100f2c3>] dump_trace+0x1a/0x40", ""),
+            ("ERROR [pod-abc] boom", ""),
+        ]
+        .into_iter()
+        .for_each(|(line, log_prefix_expected)| {
+            let [(log_prefix, rest)] = Stacktrace::split_log_prefixes([line], Format::Generic)[..]
+            else {
+                panic!("Expected one line.");
+            };
+            assert_eq!(log_prefix_expected, log_prefix, "{line}");
+            assert_eq!(line, format!("{log_prefix}{rest}"));
+        });
+    }
+
+    #[test]
+    fn parses_multiple_section_stacktrace_ansi_escapes() {
+        let stacktrace = Stacktrace::from(
+            "\
+            \x20  0: \x1b[91mapp::store::load\x1b[0m\n\
+            \x20     at \x1b[35msrc/store.rs\x1b[0m:12\n\
+            \x20  1: \x1b[91mapp::store::get\x1b[0m\n\
+            ",
+        );
+
+        let [store_load] = stacktrace.sections.as_slice() else {
+            panic!("Expected one top level section.");
+        };
+        assert_eq!("app::store::load", store_load.slice_remainder());
+        assert_eq!(
+            vec![String::from("      at src/store.rs:12")],
+            store_load.continuation_lines()
+        );
+
+        // The continuation line's span is after the line and `\n`.
+        let red = Style {
+            foreground: Some(Color::Indexed(9)),
+            ..Style::default()
+        };
+        let magenta = Style {
+            foreground: Some(Color::Indexed(5)),
+            ..Style::default()
+        };
+        assert_eq!(
+            vec![
+                StyleSpan {
+                    start: 6,
+                    end: 22,
+                    style: red,
+                },
+                StyleSpan {
+                    start: 32,
+                    end: 44,
+                    style: magenta,
+                },
+            ],
+            store_load.style_spans()
+        );
+
+        let [store_get] = store_load.child_sections() else {
+            panic!("Expected `store::get` to be nested under `store::load`.");
+        };
+        assert_eq!("app::store", store_get.slice_common_with_previous_frames());
+        assert_eq!("::get", store_get.slice_remainder());
+    }
+
+    #[test]
+    fn parse_str_records_diagnostics_with_line_numbers() {
+        let stacktrace = Stacktrace::parse_str(
+            "\
+            java.lang.IllegalStateException: Failed\n\
+            \tat com.example.App.run(App.java:21)\n\
+            garbage\n\
+            \x20 File \"main.py\", line 2, in main\n\
+            \tat com.example.App.main(App.ja\n\
+            ",
+            &ParseOptions {
+                format: Some(Format::Java),
+                ..ParseOptions::default()
+            },
+        )
+        .expect("Expected stack trace to be parsed.");
+
+        assert_eq!(
+            vec![
+                Diagnostic {
+                    line_number: 3,
+                    kind: DiagnosticKind::UnrecognisedLine,
+                },
+                Diagnostic {
+                    line_number: 4,
+                    kind: DiagnosticKind::MixedFormat(Format::Python),
+                },
+                Diagnostic {
+                    line_number: 5,
+                    kind: DiagnosticKind::TruncatedFrame,
+                },
+            ],
+            stacktrace.diagnostics
+        );
+        assert_eq!(
+            "line 4: frame is in a different format: Python traceback",
+            stacktrace.diagnostics[1].to_string()
+        );
+    }
+
+    #[test]
+    fn parse_str_returns_error_when_no_line_is_recognised() {
+        assert_eq!(
+            Err(ParseError::Empty),
+            Stacktrace::parse_str(" \n\n", &ParseOptions::default())
+        );
+        assert_eq!(
+            Err(ParseError::Unrecognised {
+                diagnostics: vec![Diagnostic {
+                    line_number: 1,
+                    kind: DiagnosticKind::UnrecognisedLine,
+                }],
+            }),
+            Stacktrace::parse_str("<html>not a stack trace</html>", &ParseOptions::default())
+        );
+        assert!(Stacktrace::parse_str("a::b::Class.method_one", &ParseOptions::default()).is_ok());
+
+        assert_eq!("stack trace is empty", ParseError::Empty.to_string());
+        assert_eq!(
+            "no lines were recognised as part of a stack trace",
+            ParseError::Unrecognised {
+                diagnostics: Vec::new()
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn parses_slice_common_with_ancestors_using_parse_options() {
+        let slice_common = |parse_options: &ParseOptions, previous_line, line| {
+            Stacktrace::parse_slice_common_with_ancestors(parse_options, Some(previous_line), line)
+        };
+
+        // `$` is a word character in Java, so inner classes are not split.
+        let generic = ParseOptions::for_format(Format::Generic);
+        let java = ParseOptions::for_format(Format::Java);
+        assert_eq!("a.B", slice_common(&generic, "a.B$C.run", "a.B$D.run"));
+        assert_eq!("a", slice_common(&java, "a.B$C.run", "a.B$D.run"));
+
+        let double_colon = ParseOptions {
+            segment_separators: Some(vec![String::from("::")]),
+            ..ParseOptions::default()
+        }
+        .with_format_defaults(Format::Rust);
+        assert_eq!("a::b", slice_common(&generic, "a::b.c", "a::b.d"));
+        assert_eq!("a", slice_common(&double_colon, "a::b.c", "a::b.d"));
+
+        let min_common_prefix_len = ParseOptions {
+            min_common_prefix_len: Some(5),
+            ..ParseOptions::default()
+        }
+        .with_format_defaults(Format::Rust);
+        assert_eq!("", slice_common(&min_common_prefix_len, "a::b.c", "a::b.d"));
+        assert_eq!(
+            "app::store",
+            slice_common(
+                &min_common_prefix_len,
+                "app::store::get",
+                "app::store::load"
+            )
+        );
+    }
+
+    #[test]
+    fn parses_deeply_nested_stacktrace_without_overflowing_stack() {
+        // Each line has one more segment in common with the previous line, so each
+        // line is nested within the previous one. The remaining lines make the stack
+        // trace 100k lines long.
+        let nested_depth = 1_000;
+        let line_count = 100_000;
+        let mut stacktrace_str = String::new();
+        let mut line = String::from("a::a");
+        for _ in 0..nested_depth {
+            stacktrace_str.push_str(&line);
+            stacktrace_str.push('\n');
+            line.push_str("::a");
+        }
+        for _ in nested_depth..line_count {
+            stacktrace_str.push_str("b::c\n");
+        }
+
+        // A small stack fails if parsing, cloning, comparing, hashing, formatting, or
+        // dropping recurses once per nesting level.
+        let (depth, last_id, is_clone_equal, is_hash_equal, debug_len) =
+            std::thread::Builder::new()
+                .stack_size(256 * 1024)
+                .spawn(move || {
+                    let mut stacktrace = Stacktrace::from_format(&stacktrace_str, Format::Rust);
+                    let stacktrace_ref = StacktraceRef::from_format(&stacktrace_str, Format::Rust);
+
+                    let mut depth = 0;
+                    let mut child_sections = stacktrace.sections.as_slice();
+                    while let Some(section) = child_sections.first() {
+                        depth += 1;
+                        child_sections = section.child_sections();
+                    }
+                    let mut last_id = 0;
+                    let mut sections = stacktrace.sections.iter().collect::<Vec<_>>();
+                    while let Some(section) = sections.pop() {
+                        last_id = last_id.max(section.id());
+                        sections.extend(section.child_sections());
+                    }
+
+                    let stacktrace_clone = stacktrace.clone();
+                    let is_clone_equal = stacktrace == stacktrace_clone
+                        && stacktrace_ref.clone() == stacktrace_ref
+                        && stacktrace_ref.into_owned() == stacktrace;
+                    let hash = |stacktrace: &Stacktrace| {
+                        let mut hasher = std::hash::DefaultHasher::new();
+                        stacktrace.sections.hash(&mut hasher);
+                        hasher.finish()
+                    };
+                    let is_hash_equal = hash(&stacktrace) == hash(&stacktrace_clone);
+                    let debug_len = format!("{stacktrace:?}").len();
+
+                    // Sections that are detached from their stack trace are also dropped
+                    // iteratively.
+                    let section_nested = stacktrace.sections.remove(0);
+                    drop(section_nested.clone());
+                    drop(section_nested);
+
+                    (depth, last_id, is_clone_equal, is_hash_equal, debug_len)
+                })
+                .expect("Failed to spawn thread.")
+                .join()
+                .expect("Failed to parse stack trace.");
+
+        assert_eq!(nested_depth, depth);
+        assert_eq!(line_count - 1, last_id as usize);
+        assert!(is_clone_equal);
+        assert!(is_hash_equal);
+        assert_ne!(0, debug_len);
+    }
+
+    #[test]
+    fn stacktraces_with_different_line_endings_are_not_equal() {
+        let stacktrace_lf = Stacktrace::from("a::b\na::c\n");
+        let stacktrace_crlf = Stacktrace::from("a::b\r\na::c");
+
+        assert_ne!(stacktrace_lf.to_string(), stacktrace_crlf.to_string());
+        assert_ne!(stacktrace_lf, stacktrace_crlf);
+        assert!(stacktrace_lf.eq_ignoring_source(&stacktrace_crlf));
+        assert!(!stacktrace_lf.eq_ignoring_source(&Stacktrace::from("a::b\na::d\n")));
+    }
+
+    #[test]
+    fn section_id_at_returns_section_containing_offset() {
+        let stacktrace = Stacktrace::from_format(
+            "\
+            app::main\n\
+            app::run\n\
+            \x20     at src/run.rs:3\n\
+            lib::call",
+            Format::Rust,
+        );
+
+        assert_eq!(Some(0), stacktrace.section_id_at(0));
+        assert_eq!(None, stacktrace.section_id_at(9));
+        assert_eq!(Some(1), stacktrace.section_id_at(12));
+        // Continuation lines are part of the section they belong to.
+        assert_eq!(Some(1), stacktrace.section_id_at(30));
+        assert_eq!(Some(2), stacktrace.section_id_at(45));
+        assert_eq!(None, stacktrace.section_id_at(50));
+    }
+
+    #[test]
+    fn writes_stacktrace_as_text_it_was_parsed_from() {
+        let stacktrace_str = "\
+            2026-10-17T10:00:01Z ERROR panicked at src/main.rs:3:5\r\n\
+            2026-10-17T10:00:01Z ERROR    0: \x1b[91mapp::store::load\x1b[0m  \r\n\
+            2026-10-17T10:00:01Z ERROR       at src/store.rs:12\r\n\
+            \r\n\
+            2026-10-17T10:00:01Z ERROR    1: app::store::get\t";
+
+        let stacktrace = Stacktrace::from(stacktrace_str);
+
+        assert_eq!(stacktrace_str, stacktrace.to_string());
+
+        let mut bytes = Vec::new();
+        stacktrace
+            .write_to(&mut bytes)
+            .expect("Expected writing to a `Vec` to succeed.");
+        assert_eq!(stacktrace_str.as_bytes(), bytes);
+    }
+
+    proptest! {
+        #[test]
+        fn parse_then_display_is_identity(
+            lines in prop::collection::vec(
+                (
+                    prop_oneof![
+                        "   [0-9]{1,2}: [a-z]{1,4}(::[a-z]{1,4}){0,3} {0,2}",
+                        " {4,8}at [a-z/]{1,8}\\.rs:[0-9]{1,3}",
+                        "\tat [a-z]{1,3}(\\.[a-z]{1,3}){0,3}\\([A-Z][a-z]{0,4}\\.java:[0-9]{1,2}\\)",
+                        "Caused by: [a-z.]{1,10}Exception",
+                        "2026-10-17T10:00:0[0-9]Z (INFO|ERROR) [ -~]{0,20}",
+                        "(\x1b\\[[0-9;]{0,5}m)?[a-z: ]{0,10}(\x1b\\[0m)?( |\t)?",
+                        "[ -~]{0,30}",
+                        "\\PC{0,10}\r?",
+                    ],
+                    prop_oneof![Just("\n"), Just("\r\n")],
+                ),
+                0..12,
+            ),
+            has_final_line_ending in any::<bool>(),
+        ) {
+            let mut stacktrace_str = lines
+                .into_iter()
+                .map(|(line, line_ending)| line + line_ending)
+                .collect::<String>();
+            if !has_final_line_ending {
+                let line_ending_len = match stacktrace_str.ends_with("\r\n") {
+                    true => 2,
+                    false => 1,
+                };
+                stacktrace_str.truncate(stacktrace_str.len().saturating_sub(line_ending_len));
+            }
+
+            prop_assert_eq!(&stacktrace_str, &Stacktrace::from(stacktrace_str.as_str()).to_string());
+        }
+    }
 }
@@ -0,0 +1,187 @@
+use crate::{Format, ParseOptions, SectionRef, Stacktrace};
+
+/// A `Stacktrace` whose sections' text borrows from the stack trace string.
+///
+/// This avoids copying the text of each line of large stack traces, such as
+/// thread dumps. Text is only copied for lines with ANSI escape sequences, and
+/// each section's `frame` is parsed when it is accessed.
+///
+/// Use [`StacktraceRef::into_owned`] to convert this into a `Stacktrace`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StacktraceRef<'s> {
+    pub sections: Vec<SectionRef<'s>>,
+}
+
+impl<'s> StacktraceRef<'s> {
+    /// Parses a stack trace string in the given format.
+    ///
+    /// See [`Stacktrace::from_format`] for how log prefixes and ANSI escape
+    /// sequences are handled.
+    pub fn from_format(s: &'s str, format: Format) -> Self {
+        Stacktrace::ref_from_format_with_options(s, format, &ParseOptions::for_format(format))
+    }
+
+    /// Returns the id of the section whose lines contain the given byte offset
+    /// in the stack trace string.
+    ///
+    /// See [`Stacktrace::section_id_at`].
+    pub fn section_id_at(&self, offset: usize) -> Option<u32> {
+        let mut sections = self.sections.iter().collect::<Vec<_>>();
+        while let Some(section) = sections.pop() {
+            if section.source_span.lines.contains(&offset) {
+                return Some(section.id);
+            }
+            sections.extend(section.child_sections.iter());
+        }
+
+        None
+    }
+
+    /// Returns an owned `Stacktrace` with the same sections.
+    pub fn into_owned(self) -> Stacktrace {
+        Stacktrace {
+            sections: self
+                .sections
+                .into_iter()
+                .map(SectionRef::into_owned)
+                .collect(),
+            diagnostics: Vec::new(),
+        }
+    }
+}
+
+impl<'s> From<&'s str> for StacktraceRef<'s> {
+    fn from(s: &'s str) -> Self {
+        Self::from_format(s, Stacktrace::detect_format(s))
+    }
+}
+
+impl<'s> From<StacktraceRef<'s>> for Stacktrace {
+    fn from(stacktrace_ref: StacktraceRef<'s>) -> Self {
+        stacktrace_ref.into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use crate::{Format, Stacktrace, StacktraceRef};
+
+    #[test]
+    fn borrows_slices_from_stacktrace_string() {
+        let stacktrace_str = "\
+            app::main\n\
+            app::server::run\n\
+            app::server::handle";
+
+        let stacktrace_ref = StacktraceRef::from_format(stacktrace_str, Format::Rust);
+
+        let [main] = stacktrace_ref.sections.as_slice() else {
+            panic!(
+                "Expected 1 top level section, got: {:#?}",
+                stacktrace_ref.sections
+            );
+        };
+        let [server_run] = main.child_sections() else {
+            panic!(
+                "Expected 1 child section, got: {:#?}",
+                main.child_sections()
+            );
+        };
+        assert_eq!("app", server_run.slice_common_with_previous_frames());
+        assert_eq!("::server::run", server_run.slice_remainder());
+        assert!(matches!(
+            server_run.slice_common_with_previous_frames,
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(server_run.slice_remainder, Cow::Borrowed(_)));
+        assert!(matches!(main.slice_remainder, Cow::Borrowed(_)));
+
+        assert_eq!(
+            Stacktrace::from_format(stacktrace_str, Format::Rust),
+            stacktrace_ref.into_owned()
+        );
+    }
+
+    #[test]
+    fn borrows_log_prefixes_continuation_lines_and_escape_sequences() {
+        let stacktrace_str = "\
+            2026-10-17T10:00:01Z ERROR app::main\n\
+            2026-10-17T10:00:01Z ERROR       at src/main.rs:3\n\
+            2026-10-17T10:00:01Z ERROR app::run\x1b[0m\n";
+        let points_into_stacktrace_str = |text: &str| {
+            let range = stacktrace_str.as_bytes().as_ptr_range();
+            let text_range = text.as_bytes().as_ptr_range();
+            range.start <= text_range.start && text_range.end <= range.end
+        };
+
+        let stacktrace_ref = StacktraceRef::from_format(stacktrace_str, Format::Rust);
+
+        let [main] = stacktrace_ref.sections.as_slice() else {
+            panic!(
+                "Expected 1 top level section, got: {:#?}",
+                stacktrace_ref.sections
+            );
+        };
+        let [run] = main.child_sections() else {
+            panic!(
+                "Expected 1 child section, got: {:#?}",
+                main.child_sections()
+            );
+        };
+        assert!(points_into_stacktrace_str(main.slice_log_prefix()));
+        assert!(points_into_stacktrace_str(main.slice_remainder()));
+        let [continuation_line] = main.continuation_lines() else {
+            panic!("Expected 1 continuation line.");
+        };
+        assert!(points_into_stacktrace_str(continuation_line));
+        let [_line_trivia, continuation_line_trivia] = main.line_trivia() else {
+            panic!("Expected line trivia for the line and its continuation line.");
+        };
+        assert_eq!(
+            "2026-10-17T10:00:01Z ERROR ",
+            continuation_line_trivia.log_prefix()
+        );
+        assert!(matches!(
+            continuation_line_trivia.log_prefix,
+            Cow::Borrowed(_)
+        ));
+        assert!(points_into_stacktrace_str(
+            continuation_line_trivia.log_prefix()
+        ));
+
+        // The line with an escape sequence is copied, but the escape sequence is not.
+        assert!(matches!(run.slice_remainder, Cow::Owned(_)));
+        let [escape_sequence] = run.line_trivia()[0].escape_sequences() else {
+            panic!("Expected 1 escape sequence.");
+        };
+        assert_eq!("\x1b[0m", escape_sequence.sequence());
+        assert!(points_into_stacktrace_str(escape_sequence.sequence()));
+
+        // The frame is parsed from the slices when accessed.
+        assert_eq!(Some("main"), main.frame().function());
+        assert_eq!(Some("src/main.rs"), main.frame().file_path());
+        assert_eq!(Some("run"), run.frame().function());
+    }
+
+    #[test]
+    fn copies_slices_of_lines_with_escape_sequences() {
+        let stacktrace_str = "\x1b[31mapp::main\x1b[0m\napp::run";
+
+        let stacktrace_ref = StacktraceRef::from_format(stacktrace_str, Format::Rust);
+
+        let [main] = stacktrace_ref.sections.as_slice() else {
+            panic!(
+                "Expected 1 top level section, got: {:#?}",
+                stacktrace_ref.sections
+            );
+        };
+        assert_eq!("app::main", main.slice_remainder());
+        assert!(matches!(main.slice_remainder, Cow::Owned(_)));
+        assert!(matches!(
+            main.child_sections()[0].slice_remainder,
+            Cow::Borrowed(_)
+        ));
+    }
+}
@@ -0,0 +1,307 @@
+use std::{borrow::Cow, ops::Range};
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+
+/// Styling applied to part of a section's text by ANSI SGR escape codes.
+///
+/// The escape codes are removed before the line is parsed, so that they are
+/// not compared with other lines. Renderers may use these spans to reproduce
+/// the original colours.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct StyleSpan {
+    /// Byte index in the section's text where the style begins.
+    pub start: usize,
+    /// Byte index in the section's text where the style ends, exclusive.
+    pub end: usize,
+    /// Style of the text within the span.
+    pub style: Style,
+}
+
+/// Text style set by ANSI SGR escape codes, e.g. `\x1b[1;31m`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Style {
+    /// Colour of the text.
+    pub foreground: Option<Color>,
+    /// Colour behind the text.
+    pub background: Option<Color>,
+    /// Whether the text is bold, from `1`.
+    pub bold: bool,
+    /// Whether the text is dimmed, from `2`.
+    pub dim: bool,
+    /// Whether the text is italic, from `3`.
+    pub italic: bool,
+    /// Whether the text is underlined, from `4`.
+    pub underline: bool,
+}
+
+/// Colour set by ANSI SGR escape codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    /// Colour from the 256 colour palette, e.g. `1` for red from `31`, or `9`
+    /// for bright red from `91`.
+    Indexed(u8),
+    /// 24 bit colour, from `38;2;r;g;b`.
+    Rgb(u8, u8, u8),
+}
+
+impl StyleSpan {
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    pub fn style(&self) -> Style {
+        self.style
+    }
+
+    /// Returns the line with ANSI escape sequences removed, the styles set by
+    /// SGR sequences, and the byte ranges of the removed escape sequences.
+    ///
+    /// The line is borrowed if it has no escape sequences.
+    ///
+    /// Other escape sequences, such as cursor movement and OSC 8 hyperlinks,
+    /// are removed without affecting the style.
+    pub(crate) fn strip_escapes(line: &str) -> (Cow<'_, str>, Vec<StyleSpan>, Vec<Range<usize>>) {
+        if !line.contains(ESC) {
+            return (Cow::Borrowed(line), Vec::new(), Vec::new());
+        }
+
+        let mut line_stripped = String::with_capacity(line.len());
+        let mut style_spans: Vec<StyleSpan> = Vec::new();
+        let mut escape_ranges = Vec::new();
+        let mut style = Style::default();
+        let mut chars = line.char_indices().peekable();
+
+        while let Some((index, c)) = chars.next() {
+            if c != ESC {
+                let start = line_stripped.len();
+                line_stripped.push(c);
+                if style == Style::default() {
+                    continue;
+                }
+
+                match style_spans.last_mut() {
+                    Some(style_span) if style_span.end == start && style_span.style == style => {
+                        style_span.end = line_stripped.len();
+                    }
+                    _ => style_spans.push(StyleSpan {
+                        start,
+                        end: line_stripped.len(),
+                        style,
+                    }),
+                }
+                continue;
+            }
+
+            match chars.next().map(|(_, c)| c) {
+                // CSI sequence, e.g. `\x1b[1;31m`, which ends with a character in `@..=~`.
+                Some('[') => {
+                    let mut parameters = String::new();
+                    let final_char = chars.by_ref().map(|(_, c)| c).find(|c| {
+                        let is_final = ('@'..='~').contains(c);
+                        if !is_final {
+                            parameters.push(*c);
+                        }
+                        is_final
+                    });
+                    if final_char == Some('m') {
+                        style = style.with_sgr_parameters(&parameters);
+                    }
+                }
+                // OSC sequence, e.g. a hyperlink, which ends with `BEL` or `ESC \`.
+                Some(']') => {
+                    while let Some((_, c)) = chars.next() {
+                        if c == BEL || (c == ESC && chars.next_if(|(_, c)| *c == '\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            let escape_end = chars
+                .peek()
+                .map_or(line.len(), |(escape_end, _)| *escape_end);
+            escape_ranges.push(index..escape_end);
+        }
+
+        (Cow::Owned(line_stripped), style_spans, escape_ranges)
+    }
+
+    /// Returns the byte index in the line with escape sequences of the given
+    /// byte index in the line without them.
+    ///
+    /// Escape sequences at the index are skipped if `after_escapes` is `true`,
+    /// so that the index is at the next character.
+    pub(crate) fn unstripped_index(
+        escape_ranges: &[Range<usize>],
+        index: usize,
+        after_escapes: bool,
+    ) -> usize {
+        escape_ranges
+            .iter()
+            .fold(index, |unstripped_index, escape_range| {
+                let is_before_index = escape_range.start < unstripped_index
+                    || (after_escapes && escape_range.start == unstripped_index);
+                match is_before_index {
+                    true => unstripped_index + escape_range.len(),
+                    false => unstripped_index,
+                }
+            })
+    }
+
+    /// Returns the spans after `skip_len`, moved by `offset`.
+    ///
+    /// This is used to place a line's spans within a section's text, when the
+    /// start of the line is not part of the text.
+    pub(crate) fn offset_spans(
+        style_spans: &[StyleSpan],
+        skip_len: usize,
+        offset: usize,
+    ) -> impl Iterator<Item = StyleSpan> + '_ {
+        style_spans
+            .iter()
+            .filter(move |style_span| style_span.end > skip_len)
+            .map(move |style_span| StyleSpan {
+                start: style_span.start.max(skip_len) - skip_len + offset,
+                end: style_span.end - skip_len + offset,
+                style: style_span.style,
+            })
+    }
+}
+
+impl Style {
+    pub fn foreground(&self) -> Option<Color> {
+        self.foreground
+    }
+
+    pub fn background(&self) -> Option<Color> {
+        self.background
+    }
+
+    pub fn bold(&self) -> bool {
+        self.bold
+    }
+
+    pub fn dim(&self) -> bool {
+        self.dim
+    }
+
+    pub fn italic(&self) -> bool {
+        self.italic
+    }
+
+    pub fn underline(&self) -> bool {
+        self.underline
+    }
+
+    /// Returns this style with the given SGR parameters applied, e.g. `1;31`.
+    fn with_sgr_parameters(mut self, parameters: &str) -> Self {
+        // `\x1b[m` is the same as `\x1b[0m`, so empty codes are `0`.
+        let mut codes = parameters
+            .split([';', ':'])
+            .map(|code| code.parse::<u8>().unwrap_or(0));
+
+        while let Some(code) = codes.next() {
+            match code {
+                0 => self = Self::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                22 => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                23 => self.italic = false,
+                24 => self.underline = false,
+                30..=37 => self.foreground = Some(Color::Indexed(code - 30)),
+                38 => self.foreground = Color::parse_extended(&mut codes),
+                39 => self.foreground = None,
+                40..=47 => self.background = Some(Color::Indexed(code - 40)),
+                48 => self.background = Color::parse_extended(&mut codes),
+                49 => self.background = None,
+                90..=97 => self.foreground = Some(Color::Indexed(code - 90 + 8)),
+                100..=107 => self.background = Some(Color::Indexed(code - 100 + 8)),
+                _ => {}
+            }
+        }
+
+        self
+    }
+}
+
+impl Color {
+    /// Parses the colour after `38` or `48`, e.g. `5;208` or `2;255;128;0`.
+    fn parse_extended(codes: &mut impl Iterator<Item = u8>) -> Option<Self> {
+        match codes.next()? {
+            5 => codes.next().map(Self::Indexed),
+            2 => Some(Self::Rgb(codes.next()?, codes.next()?, codes.next()?)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Color, Style, StyleSpan};
+
+    #[test]
+    fn strips_sgr_escapes_into_style_spans() {
+        let (line, style_spans, escape_ranges) =
+            StyleSpan::strip_escapes("   0: \x1b[1;31mapp::main\x1b[0m\x1b[2m::h1234\x1b[0m");
+
+        assert_eq!("   0: app::main::h1234", line);
+        assert_eq!(vec![6..13, 22..26, 26..30, 37..41], escape_ranges);
+        // `a` of `app` is after the first escape sequence, and `::h1234` ends
+        // before the last.
+        assert_eq!(13, StyleSpan::unstripped_index(&escape_ranges, 6, true));
+        assert_eq!(37, StyleSpan::unstripped_index(&escape_ranges, 22, false));
+        assert_eq!(
+            vec![
+                StyleSpan {
+                    start: 6,
+                    end: 15,
+                    style: Style {
+                        foreground: Some(Color::Indexed(1)),
+                        bold: true,
+                        ..Style::default()
+                    },
+                },
+                StyleSpan {
+                    start: 15,
+                    end: 22,
+                    style: Style {
+                        dim: true,
+                        ..Style::default()
+                    },
+                },
+            ],
+            style_spans
+        );
+    }
+
+    #[test]
+    fn strips_extended_colors_and_hyperlinks() {
+        let (line, style_spans, _escape_ranges) = StyleSpan::strip_escapes(
+            "at \x1b]8;;file:///src/main.rs\x07\x1b[38;2;255;128;0msrc/main.rs\x1b[39m\x1b]8;;\x1b\\:12",
+        );
+
+        assert_eq!("at src/main.rs:12", line);
+        assert_eq!(
+            vec![StyleSpan {
+                start: 3,
+                end: 14,
+                style: Style {
+                    foreground: Some(Color::Rgb(255, 128, 0)),
+                    ..Style::default()
+                },
+            }],
+            style_spans
+        );
+    }
+}
@@ -8,8 +8,9 @@ use leptos::{
     control_flow::For,
     hydration::{AutoReload, HydrationScripts},
     prelude::{
-        event_target_value, signal, ClassAttribute, ElementChild, Get, GlobalAttributes, IntoAny,
-        IntoView, LeptosOptions, OnAttribute, PropAttribute, RwSignal, Signal, Write,
+        event_target_value, signal, ClassAttribute, CollectView, ElementChild, Get,
+        GlobalAttributes, IntoAny, IntoView, LeptosOptions, OnAttribute, PropAttribute, RwSignal,
+        Signal, Write,
     },
     view,
 };
@@ -18,7 +19,7 @@ use leptos_router::{
     components::{Route, Router, Routes, RoutingProgress, A},
     StaticSegment,
 };
-use stacktrace::{Section, Stacktrace};
+use stacktrace::{Format, SanitizerReport, Section, SectionKind, Stacktrace};
 
 pub fn shell(options: LeptosOptions) -> impl IntoView {
     view! {
@@ -112,6 +113,21 @@ const STACKTRACE_SAMPLES_DIV_CLASSES: &str = "\
     lg:max-w-7xl \
 ";
 
+const FORMAT_DIV_CLASSES: &str = "\
+    flex \
+    gap-2 \
+    justify-end \
+    w-full \
+    lg:max-w-7xl \
+    py-2 \
+";
+
+const FORMAT_SELECT_CLASSES: &str = "\
+    bg-slate-900 \
+    text-slate-100 \
+    rounded \
+";
+
 const STACKTRACE_SAMPLE_JAVA: &str = r#"java.lang.IllegalArgumentException: foo
     com.example.stacktrace.Example.fail(Example.java:11)
     sun.reflect.NativeMethodAccessorImpl.invoke0(Native Method)
@@ -295,6 +311,27 @@ const STACKTRACE_DIV_CLASSES: &str = "\
     text-nowrap \
 ";
 
+const SANITIZER_REPORT_DIV_CLASSES: &str = "\
+    flex \
+    gap-4 \
+    w-full \
+    lg:max-w-7xl \
+    overflow-x-scroll \
+";
+
+const SANITIZER_STACK_DIV_CLASSES: &str = "\
+    flex \
+    flex-col \
+    gap-2 \
+    grow \
+    min-w-[32rem] \
+";
+
+const SANITIZER_STACK_LABEL_CLASSES: &str = "\
+    font-bold \
+    font-mono \
+";
+
 const STACKTRACE_DIV_PLACEHOLDER_CLASSES: &str = "\
     opacity-75 \
     italic \
@@ -345,10 +382,26 @@ const SECTION_DIV_SLICE_CLASSES: &str = "\
     hover:bg-slate-500 \
 ";
 
+const SECTION_DIV_SLICE_LOG_PREFIX_CLASSES: &str = "\
+    opacity-20 \
+";
+
+const SECTION_DIV_SLICE_FRAME_INDEX_CLASSES: &str = "\
+    opacity-50 \
+";
+
 const SECTION_DIV_SLICE_COMMON_CLASSES: &str = "\
     opacity-20 \
 ";
 
+const SECTION_DIV_SLICE_HEADER_CLASSES: &str = "\
+    font-bold \
+";
+
+const SECTION_DIV_CONTINUATION_LINES_CLASSES: &str = "\
+    opacity-50 \
+";
+
 #[component]
 pub fn App() -> impl IntoView {
     // Provides context that manages stylesheets, titles, meta tags, etc.
@@ -395,7 +448,24 @@ pub fn App() -> impl IntoView {
 fn HomePage() -> impl IntoView {
     let stacktrace_str = RwSignal::new(String::new());
     let stacktrace_on_input = move |ev| *stacktrace_str.write() = event_target_value(&ev);
-    let stacktrace = Signal::derive(move || Stacktrace::from(stacktrace_str.get().as_str()));
+    let format_override = RwSignal::new(None::<Format>);
+    let format_detected =
+        Signal::derive(move || Stacktrace::detect_format(stacktrace_str.get().as_str()));
+    let format = Signal::derive(move || {
+        format_override
+            .get()
+            .unwrap_or_else(|| format_detected.get())
+    });
+    let stacktrace = Signal::derive(move || {
+        Stacktrace::from_format(stacktrace_str.get().as_str(), format.get())
+    });
+    let sanitizer_report = Signal::derive(move || {
+        if format.get() == Format::Sanitizer {
+            SanitizerReport::parse(stacktrace_str.get().as_str())
+        } else {
+            None
+        }
+    });
 
     view! {
         <div class=HOMEPAGE_CLASSES>
@@ -408,8 +478,15 @@ fn HomePage() -> impl IntoView {
                     move || stacktrace_str.get()
                 }
             />
+            <FormatSelect format_detected format_override />
 
-            <StacktraceDiv stacktrace />
+            // Sanitizer reports have several stacks, which are shown side by side.
+            {move || match sanitizer_report.get() {
+                Some(sanitizer_report) => {
+                    view! { <SanitizerReportDiv sanitizer_report /> }.into_any()
+                }
+                None => view! { <StacktraceDiv stacktrace /> }.into_any(),
+            }}
         </div>
     }
 }
@@ -437,6 +514,45 @@ fn StacktraceSamples(stacktrace_str: RwSignal<String>) -> impl IntoView {
     }
 }
 
+/// Shows the detected format, and lets the user override it.
+#[component]
+fn FormatSelect(
+    format_detected: Signal<Format>,
+    format_override: RwSignal<Option<Format>>,
+) -> impl IntoView {
+    let format_on_change = move |ev| {
+        let format_name = event_target_value(&ev);
+        *format_override.write() = Format::ALL
+            .into_iter()
+            .find(|format| format.name() == format_name);
+    };
+    let format_options = Format::ALL
+        .into_iter()
+        .map(|format| {
+            view! {
+                <option
+                    value=format.name()
+                    prop:selected=move || format_override.get() == Some(format)
+                >
+                    {format.name()}
+                </option>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <div class=FORMAT_DIV_CLASSES>
+            <span>{move || format!("Detected: {}", format_detected.get().name())}</span>
+            <select class=FORMAT_SELECT_CLASSES on:change=format_on_change>
+                <option value="" prop:selected=move || format_override.get().is_none()>
+                    "Auto"
+                </option>
+                {format_options}
+            </select>
+        </div>
+    }
+}
+
 #[component]
 fn StacktraceDiv(stacktrace: Signal<Stacktrace>) -> impl IntoView {
     let placeholder_classes = move || {
@@ -460,6 +576,33 @@ fn StacktraceDiv(stacktrace: Signal<Stacktrace>) -> impl IntoView {
     }
 }
 
+/// Shows each stack of a sanitizer report side by side, under its label.
+#[component]
+fn SanitizerReportDiv(sanitizer_report: SanitizerReport) -> impl IntoView {
+    let stack_divs = sanitizer_report
+        .stacks()
+        .iter()
+        .map(|stack| {
+            let label = stack.label().unwrap_or_default().to_string();
+            let stacktrace = stack.stacktrace().clone();
+            let stacktrace = Signal::derive(move || stacktrace.clone());
+
+            view! {
+                <div class=SANITIZER_STACK_DIV_CLASSES>
+                    <span class=SANITIZER_STACK_LABEL_CLASSES>{label}</span>
+                    <StacktraceDiv stacktrace />
+                </div>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <div class=SANITIZER_REPORT_DIV_CLASSES>
+            {stack_divs}
+        </div>
+    }
+}
+
 #[component]
 fn SectionDiv(section: Section) -> impl IntoView {
     let section_name = {
@@ -467,6 +610,19 @@ fn SectionDiv(section: Section) -> impl IntoView {
         format!("section-{section_hash}")
     };
 
+    // `whitespace-pre` keeps the newlines, so each line is rendered on its own row.
+    let continuation_lines = section
+        .continuation_lines()
+        .iter()
+        .map(|continuation_line| format!("\n{continuation_line}"))
+        .collect::<String>();
+
+    // Headers such as `Caused by: ..` begin a new block of frames.
+    let slice_remainder_classes = match section.kind() {
+        SectionKind::Frame => "",
+        SectionKind::Header => SECTION_DIV_SLICE_HEADER_CLASSES,
+    };
+
     let triangle_classes = if section.child_sections().is_empty() {
         SECTION_DIV_TRIANGLE_HIDDEN_CLASSES
     } else {
@@ -494,12 +650,21 @@ fn SectionDiv(section: Section) -> impl IntoView {
                 for=section_name
                 class=SECTION_DIV_SLICE_CLASSES
             >
+                <span class=SECTION_DIV_SLICE_LOG_PREFIX_CLASSES>
+                    {section.slice_log_prefix().to_string()}
+                </span>
+                <span class=SECTION_DIV_SLICE_FRAME_INDEX_CLASSES>
+                    {section.slice_frame_index().to_string()}
+                </span>
                 <span class=SECTION_DIV_SLICE_COMMON_CLASSES>
                     {section.slice_common_with_previous_frames().to_string()}
                 </span>
-                <span>
+                <span class=slice_remainder_classes>
                     {section.slice_remainder().to_string()}
                 </span>
+                <span class=SECTION_DIV_CONTINUATION_LINES_CLASSES>
+                    {continuation_lines}
+                </span>
             </label>
             <div class=SECTION_DIV_CHILDREN_CLASSES>
                 <For